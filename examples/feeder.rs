@@ -0,0 +1,70 @@
+//! Goose load test example, demonstrating how to feed test data from a CSV file into
+//! tasks. Each user logs in with the next set of credentials from `users.csv`.
+//!
+//! Run from the root of the Goose repository so `examples/users.csv` can be found, for
+//! example: `cargo run --example feeder -- --host http://local.dev/ -u3`
+//!
+//! ## License
+//!
+//! Copyright 2020 Jeremy Andrews
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use goose::prelude::*;
+use serde::Deserialize;
+
+/// Each row of users.csv is deserialized into this struct, matching the column headers.
+#[derive(Deserialize)]
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+fn main() -> Result<(), GooseError> {
+    GooseAttack::initialize()?
+        // Load all credentials when the load test starts, handing them out in order.
+        .register_feeder("users", GooseFeeder::from_csv("examples/users.csv")?)
+        .register_taskset(
+            taskset!("WebsiteUser")
+                // After each task runs, sleep randomly from 5 to 15 seconds.
+                .set_wait_time(5, 15)?
+                // Each user logs in one time when it first starts.
+                .register_task(task!(website_login).set_on_start())
+                .register_task(task!(website_index)),
+        )
+        .execute()?
+        .print();
+
+    Ok(())
+}
+
+/// Log in with the next credentials from users.csv. Once all rows have been used, the
+/// feeder wraps around to the first row again.
+async fn website_login(user: &GooseUser) -> GooseTaskResult {
+    let credentials: Credentials = user.get_feeder_record("users").await?;
+    let request_builder = user.goose_post("/login").await?;
+    let params = [
+        ("username", credentials.username),
+        ("password", credentials.password),
+    ];
+    let _goose = user.goose_send(request_builder.form(&params), None).await?;
+
+    Ok(())
+}
+
+/// A very simple task that simply loads the front page.
+async fn website_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get("/").await?;
+
+    Ok(())
+}
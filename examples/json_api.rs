@@ -0,0 +1,58 @@
+//! Goose load test example, demonstrating how to chain API requests by extracting values
+//! from JSON responses. Each user creates an item, then reads back the item it created.
+//!
+//! For example: `cargo run --example json_api -- --host http://local.dev/ -u3`
+//!
+//! ## License
+//!
+//! Copyright 2020 Jeremy Andrews
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+
+use goose::prelude::*;
+
+fn main() -> Result<(), GooseError> {
+    GooseAttack::initialize()?
+        .register_taskset(
+            taskset!("ApiUser")
+                // After each task runs, sleep randomly from 1 to 3 seconds.
+                .set_wait_time(1, 3)?
+                .register_task(task!(create_then_read)),
+        )
+        .execute()?
+        .print();
+
+    Ok(())
+}
+
+/// Create an item, then load it using the id returned in the create response, for
+/// example `{"data": {"id": 42}}`.
+async fn create_then_read(user: &GooseUser) -> GooseTaskResult {
+    let request_builder = user.goose_post("/api/items").await?;
+    let goose = user
+        .goose_send(
+            request_builder.json(&serde_json::json!({"name": "goose"})),
+            None,
+        )
+        .await?;
+
+    // If the response doesn't include an id, the create request is marked as a failure.
+    let id = user.require_json_value(goose, "$.data.id").await?;
+    // Numeric ids format as is, but string ids would keep their JSON quotes.
+    let id = id.as_u64().unwrap_or_default();
+    let _goose = user
+        .get_named(&format!("/api/items/{}", id), "/api/items/{id}")
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Usage returned for `help` and any command that isn't understood.
+const USAGE: &str = "commands:\n  add N: launch N more users\n  help: display this help\n";
+
+/// This control thread accepts line-based commands while the load test runs, so it can
+/// be adjusted without being restarted. Commands are forwarded to the parent thread,
+/// which acts on them the next time it syncs statistics from the GooseUser threads.
+pub async fn control_main(mut listener: TcpListener, sender: mpsc::UnboundedSender<usize>) {
+    loop {
+        let (stream, address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("failed to accept control connection: {}", e);
+                continue;
+            }
+        };
+        debug!("accepted control connection from {}", address);
+        tokio::spawn(control_connection(stream, sender.clone()));
+    }
+}
+
+/// Read commands from a control connection until it is closed.
+async fn control_connection(stream: TcpStream, sender: mpsc::UnboundedSender<usize>) {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let response = match parse_command(&line) {
+            Some(users) => {
+                info!("control: adding {} users", users);
+                if sender.send(users).is_err() {
+                    // The load test is shutting down.
+                    break;
+                }
+                format!("adding {} users\n", users)
+            }
+            None => USAGE.to_string(),
+        };
+        if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Parse a control command, returning how many users to add.
+fn parse_command(line: &str) -> Option<usize> {
+    let mut words = line.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("add"), Some(users), None) => match users.parse() {
+            Ok(users) if users > 0 => Some(users),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse_command("add 5"), Some(5));
+        assert_eq!(parse_command("  add   12 \r"), Some(12));
+        assert_eq!(parse_command("add 0"), None);
+        assert_eq!(parse_command("add -1"), None);
+        assert_eq!(parse_command("add five"), None);
+        assert_eq!(parse_command("add"), None);
+        assert_eq!(parse_command("add 5 users"), None);
+        assert_eq!(parse_command("help"), None);
+        assert_eq!(parse_command(""), None);
+    }
+}
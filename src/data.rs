@@ -0,0 +1,181 @@
+//! Helpers for generating random test data from within tasks.
+//!
+//! Each user has its own random number generator, so users running in parallel don't
+//! contend over a shared generator. When the load test is started with `--seed`, each
+//! user's generator is seeded from it, and the helpers return the same values every time
+//! the load test runs. Outside of a running user, the helpers use the thread-local
+//! random number generator provided by `rand`.
+//!
+//! ## Example
+//! ```rust
+//! use goose::prelude::*;
+//! use goose::data;
+//!
+//! let mut task = task!(register);
+//!
+//! async fn register(user: &GooseUser) -> GooseTaskResult {
+//!     let request_builder = user.goose_post("/register").await?;
+//!     let params = [
+//!         ("email", data::random_email()),
+//!         ("name", data::random_string(12)),
+//!         ("age", data::random_int(18, 99).to_string()),
+//!         ("invite", data::uuid()),
+//!     ];
+//!     let _goose = user.goose_send(request_builder.form(&params), None).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use rand::distributions::{Alphanumeric, Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use std::cell::RefCell;
+
+tokio::task_local! {
+    /// The random number generator of the user running the current task.
+    static USER_RNG: RefCell<StdRng>;
+}
+
+/// Build the random number generator of a user. With a `--seed`, each user derives its
+/// own seed by adding its index, so users make different but reproducible choices.
+pub(crate) fn user_rng(seed: Option<u64>, weighted_users_index: usize) -> RefCell<StdRng> {
+    RefCell::new(match seed {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(weighted_users_index as u64)),
+        None => StdRng::from_entropy(),
+    })
+}
+
+/// Run a user's future with its random number generator.
+pub(crate) async fn with_user_rng<F: std::future::Future>(rng: RefCell<StdRng>, f: F) -> F::Output {
+    USER_RNG.scope(rng, f).await
+}
+
+/// Call `f` with the random number generator of the user running the current task, or
+/// the thread-local random number generator outside of a running user.
+pub(crate) fn with_rng<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut dyn RngCore) -> R,
+{
+    if USER_RNG.try_with(|_| ()).is_ok() {
+        USER_RNG.with(|rng| f(&mut *rng.borrow_mut()))
+    } else {
+        f(&mut rand::thread_rng())
+    }
+}
+
+/// Returns a random string of `length` ASCII letters and digits.
+///
+/// # Example
+/// ```rust
+/// use goose::data;
+///
+/// let username = data::random_string(8);
+/// assert_eq!(username.len(), 8);
+/// ```
+pub fn random_string(length: usize) -> String {
+    with_rng(|rng| rng.sample_iter(&Alphanumeric).take(length).collect())
+}
+
+/// Returns a random email address at the reserved `example.com` domain, so no mail is
+/// accidentally sent to real users.
+///
+/// # Example
+/// ```rust
+/// use goose::data;
+///
+/// let email = data::random_email();
+/// assert!(email.ends_with("@example.com"));
+/// ```
+pub fn random_email() -> String {
+    format!("{}@example.com", random_string(12).to_lowercase())
+}
+
+/// Returns a random integer from `min` to `max` inclusively. Panics if `min` is larger
+/// than `max`.
+///
+/// # Example
+/// ```rust
+/// use goose::data;
+///
+/// let quantity = data::random_int(1, 10);
+/// assert!(quantity >= 1 && quantity <= 10);
+/// ```
+pub fn random_int(min: i64, max: i64) -> i64 {
+    let range = Uniform::new_inclusive(min, max);
+    with_rng(|rng| range.sample(rng))
+}
+
+/// Returns a random (version 4) UUID, formatted as lowercase hyphenated hex digits.
+///
+/// # Example
+/// ```rust
+/// use goose::data;
+///
+/// let id = data::uuid();
+/// assert_eq!(id.len(), 36);
+/// ```
+pub fn uuid() -> String {
+    let mut bytes: [u8; 16] = with_rng(|rng| rng.gen());
+    // Set the version to 4 (random) and the variant to RFC 4122.
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_data() {
+        let string = random_string(20);
+        assert_eq!(string.len(), 20);
+        assert!(string.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(random_string(0), "");
+
+        let email = random_email();
+        assert!(email.ends_with("@example.com"));
+        assert_eq!(email.len(), 24);
+
+        for _ in 0..100 {
+            assert!((-5..=5).contains(&random_int(-5, 5)));
+        }
+        assert_eq!(random_int(7, 7), 7);
+
+        let id = uuid();
+        assert_eq!(id.len(), 36);
+        let groups: Vec<&str> = id.split('-').collect();
+        let lengths: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+        assert_eq!(lengths, [8, 4, 4, 4, 12]);
+        assert!(groups[2].starts_with('4'));
+        assert!(["8", "9", "a", "b"].contains(&&groups[3][0..1]));
+        assert_ne!(id, uuid());
+    }
+
+    #[tokio::test]
+    async fn seeded_user_rng() {
+        async fn sample() -> (String, i64, String) {
+            (random_string(16), random_int(0, 1_000_000), uuid())
+        }
+
+        // Users with the same seed and index generate the same data.
+        let first = with_user_rng(user_rng(Some(42), 0), sample()).await;
+        let second = with_user_rng(user_rng(Some(42), 0), sample()).await;
+        assert_eq!(first, second);
+
+        // Each user derives its own seed, and unseeded users are random.
+        let other_user = with_user_rng(user_rng(Some(42), 1), sample()).await;
+        assert_ne!(first, other_user);
+        let unseeded = with_user_rng(user_rng(None, 0), sample()).await;
+        assert_ne!(first, unseeded);
+    }
+}
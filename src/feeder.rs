@@ -0,0 +1,221 @@
+//! Optional feeders of test data, loaded from CSV files.
+//!
+//! Load tests often need to vary the data sent with each request, for example logging in
+//! with a different user, or loading a different product. A `GooseFeeder` loads all rows
+//! of a CSV file when the load test starts, and hands them out to users as they run tasks.
+//! Once all rows have been handed out, the feeder wraps around to the first row again.
+//!
+//! The first line of the CSV file must contain column headers, which are used as the keys
+//! of each row, or to deserialize each row into a struct.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use goose::prelude::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Credentials {
+//!     username: String,
+//!     password: String,
+//! }
+//!
+//! fn main() -> Result<(), GooseError> {
+//!     GooseAttack::initialize()?
+//!         .register_feeder("users", GooseFeeder::from_csv("users.csv")?)
+//!         .register_taskset(taskset!("LoadtestTasks")
+//!             .register_task(task!(login).set_on_start())
+//!         )
+//!         .execute()?;
+//!
+//!     Ok(())
+//! }
+//!
+//! async fn login(user: &GooseUser) -> GooseTaskResult {
+//!     let credentials: Credentials = user.get_feeder_record("users").await?;
+//!     let request_builder = user.goose_post("/login").await?;
+//!     let params = [("username", credentials.username), ("password", credentials.password)];
+//!     let _goose = user.goose_send(request_builder.form(&params), None).await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::data;
+use crate::GooseError;
+
+/// The order in which a `GooseFeeder` hands out rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GooseFeederOrder {
+    /// Rows are handed out in the order they appear in the file, wrapping around to the
+    /// first row after the last row has been handed out.
+    Sequential,
+    /// Rows are selected randomly, so the same row can be handed out more than once before
+    /// all rows have been handed out.
+    Random,
+}
+
+/// Test data loaded from a CSV file, shared by all users.
+#[derive(Debug, Clone)]
+pub struct GooseFeeder {
+    /// Column headers from the first line of the CSV file.
+    headers: Arc<csv::StringRecord>,
+    /// All rows of test data from the CSV file.
+    rows: Arc<Vec<csv::StringRecord>>,
+    /// The next row to hand out when rows are handed out sequentially.
+    next_row: Arc<AtomicUsize>,
+    /// The order in which rows are handed out.
+    order: GooseFeederOrder,
+}
+impl GooseFeeder {
+    /// Load all rows of test data from a CSV file. The first line of the file must contain
+    /// column headers. By default rows are handed out sequentially.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     let feeder = GooseFeeder::from_csv("users.csv")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_csv(path: &str) -> Result<Self, GooseError> {
+        let invalid_feeder = |detail: String| GooseError::InvalidOption {
+            option: "GooseFeeder".to_string(),
+            value: path.to_string(),
+            detail: Some(detail),
+        };
+
+        let mut reader = csv::Reader::from_path(path).map_err(|e| invalid_feeder(e.to_string()))?;
+        let headers = reader
+            .headers()
+            .map_err(|e| invalid_feeder(e.to_string()))?
+            .clone();
+        let rows = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| invalid_feeder(e.to_string()))?;
+        if rows.is_empty() {
+            return Err(invalid_feeder("CSV file contains no rows".to_string()));
+        }
+        debug!("loaded {} rows from {}", rows.len(), path);
+
+        Ok(GooseFeeder {
+            headers: Arc::new(headers),
+            rows: Arc::new(rows),
+            next_row: Arc::new(AtomicUsize::new(0)),
+            order: GooseFeederOrder::Sequential,
+        })
+    }
+
+    /// Configure the order in which rows are handed out.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     let feeder = GooseFeeder::from_csv("products.csv")?.set_order(GooseFeederOrder::Random);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_order(mut self, order: GooseFeederOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// The number of rows of test data.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if there are no rows of test data.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Hand out the next row of test data, keyed by column header.
+    pub fn next_row(&self) -> HashMap<String, String> {
+        self.headers
+            .iter()
+            .map(|header| header.to_string())
+            .zip(self.select_row().iter().map(|value| value.to_string()))
+            .collect()
+    }
+
+    /// Hand out the next row of test data, deserialized into a struct with fields named
+    /// after the column headers.
+    pub fn next_record<T: DeserializeOwned>(&self) -> Result<T, csv::Error> {
+        self.select_row().deserialize(Some(&self.headers))
+    }
+
+    /// Select the next row, shared safely between all user threads.
+    fn select_row(&self) -> &csv::StringRecord {
+        let index = match self.order {
+            GooseFeederOrder::Sequential => {
+                self.next_row.fetch_add(1, Ordering::SeqCst) % self.rows.len()
+            }
+            GooseFeederOrder::Random => data::with_rng(|rng| rng.gen_range(0, self.rows.len())),
+        };
+        &self.rows[index]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Credentials {
+        username: String,
+        password: String,
+    }
+
+    #[test]
+    fn feeder() {
+        let path = std::env::temp_dir().join("goose-feeder-test.csv");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "username,password\nfoo,secret\nbar,hunter2\n").unwrap();
+
+        let feeder = GooseFeeder::from_csv(path).unwrap();
+        assert_eq!(feeder.len(), 2);
+
+        // Rows are handed out in order, wrapping around at the end of the file.
+        let row = feeder.next_row();
+        assert_eq!(row.get("username").unwrap(), "foo");
+        assert_eq!(row.get("password").unwrap(), "secret");
+        let credentials: Credentials = feeder.next_record().unwrap();
+        assert_eq!(credentials.username, "bar");
+        assert_eq!(credentials.password, "hunter2");
+        assert_eq!(feeder.next_row().get("username").unwrap(), "foo");
+
+        // Clones share the position in the file.
+        let clone = feeder.clone();
+        assert_eq!(clone.next_row().get("username").unwrap(), "bar");
+        assert_eq!(feeder.next_row().get("username").unwrap(), "foo");
+
+        // Random rows are always rows from the file.
+        let feeder = feeder.set_order(GooseFeederOrder::Random);
+        for _ in 0..10 {
+            let username = feeder.next_row().get("username").unwrap().to_string();
+            assert!(username == "foo" || username == "bar");
+        }
+
+        // A file without rows can't feed any test data.
+        std::fs::write(path, "username,password\n").unwrap();
+        assert!(GooseFeeder::from_csv(path).is_err());
+        std::fs::remove_file(path).unwrap();
+
+        // A missing file is an error.
+        assert!(GooseFeeder::from_csv(path).is_err());
+    }
+}
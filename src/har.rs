@@ -0,0 +1,384 @@
+//! Optional replay of browser sessions recorded in HAR files.
+//!
+//! Requires the `har` compile-time feature. [`task_from_har`](./fn.task_from_har.html)
+//! reads a HAR (HTTP Archive) file, as exported by the developer tools of most browsers,
+//! and returns a `GooseTask` that replays its requests in the order they were recorded.
+//! Before each request the task waits as long as passed between starting it and the
+//! previous request in the recording, so users follow the recorded think time.
+//!
+//! Only requests made to the origin of the first recorded request are replayed, leaving
+//! out requests to third-party domains such as CDNs and analytics. They're made against
+//! the load test host with the path and query string they were recorded with, so a
+//! session recorded against a staging server can be replayed against another server.
+//! Each request is named after its path, so statistics are grouped by path and not by
+//! query string. Recorded headers and request bodies are sent as-is, except for headers
+//! Goose sets itself such as `Host`, `Content-Length` and `Cookie`.
+//!
+//! The task is named after the HAR file, and can be renamed with `set_name()`.
+//!
+//! ## Limitations
+//!
+//! Responses aren't correlated with later requests: dynamic values such as CSRF tokens,
+//! session ids or the ids of created items are replayed with the values that were
+//! recorded. Sessions that depend on them need to be turned into a load test by hand,
+//! for example with `GooseUser::response_json_value()`.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use goose::prelude::*;
+//!
+//! fn main() -> Result<(), GooseError> {
+//!     GooseAttack::initialize()?
+//!         .register_taskset(
+//!             taskset!("RecordedUser").register_task(goose::har::task_from_har("session.har")?),
+//!         )
+//!         .execute()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use serde_json::Value;
+use std::sync::Arc;
+use std::{fs, time};
+use url::Url;
+
+use crate::goose::{GooseMethod, GooseTask, GooseTaskResult, GooseUser};
+use crate::GooseError;
+
+/// Recorded headers that aren't replayed, as Goose or Reqwest set them.
+const SKIPPED_HEADERS: [&str; 5] = ["connection", "content-length", "cookie", "host", "te"];
+
+/// A request recorded in a HAR file.
+#[derive(Debug, Clone, PartialEq)]
+struct HarRequest {
+    /// How long after starting the previous request to start this one.
+    delay: time::Duration,
+    method: GooseMethod,
+    /// The path and query string of the request.
+    path: String,
+    /// The name the request is recorded with, its path without the query string.
+    name: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+}
+
+/// Load a HAR file, and return a `GooseTask` that replays the requests it recorded in
+/// order, waiting between them as long as they were apart when recorded.
+pub fn task_from_har(path: &str) -> Result<GooseTask, GooseError> {
+    let har: Value = serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        GooseError::InvalidOption {
+            option: "har::task_from_har".to_string(),
+            value: path.to_string(),
+            detail: Some(format!("failed to parse HAR file: {}", e)),
+        }
+    })?;
+    let requests = requests_from_har(&har).map_err(|detail| GooseError::InvalidOption {
+        option: "har::task_from_har".to_string(),
+        value: path.to_string(),
+        detail: Some(detail),
+    })?;
+
+    let mut task = GooseTask::new(|user| Box::pin(replay(user))).set_name(path);
+    task.data = Some(Arc::new(requests));
+    Ok(task)
+}
+
+/// Extract the requests to replay from a HAR file.
+fn requests_from_har(har: &Value) -> Result<Vec<HarRequest>, String> {
+    let entries = match har.pointer("/log/entries").and_then(Value::as_array) {
+        Some(e) => e,
+        None => return Err("HAR file has no log entries".to_string()),
+    };
+
+    let mut origin = None;
+    let mut previous_started = None;
+    let mut requests = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let invalid = |detail: &str| format!("entry {} {}", index, detail);
+        let request = entry
+            .get("request")
+            .ok_or_else(|| invalid("has no request"))?;
+        let url = request
+            .get("url")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("has no url"))?;
+        let url = Url::parse(url).map_err(|e| invalid(&format!("has invalid url: {}", e)))?;
+
+        // Requests to other origins than the first request are left out.
+        let url_origin = url.origin().ascii_serialization();
+        match &origin {
+            Some(o) if o != &url_origin => continue,
+            Some(_) => (),
+            None => origin = Some(url_origin),
+        }
+
+        let method = match request.get("method").and_then(Value::as_str) {
+            Some("DELETE") => GooseMethod::DELETE,
+            Some("GET") => GooseMethod::GET,
+            Some("HEAD") => GooseMethod::HEAD,
+            Some("PATCH") => GooseMethod::PATCH,
+            Some("POST") => GooseMethod::POST,
+            Some("PUT") => GooseMethod::PUT,
+            // Methods Goose can't make, such as OPTIONS preflight requests, are left out.
+            Some(_) => continue,
+            None => return Err(invalid("has no method")),
+        };
+
+        let started = entry
+            .get("startedDateTime")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("has no startedDateTime"))?;
+        let started = parse_date_time(started)
+            .ok_or_else(|| invalid(&format!("has invalid startedDateTime: {}", started)))?;
+        let delay = match previous_started {
+            // Entries are sorted by start time, but don't trust the clock to never go back.
+            Some(previous) if started > previous => {
+                time::Duration::from_millis((started - previous) as u64)
+            }
+            _ => time::Duration::from_millis(0),
+        };
+        previous_started = Some(started);
+
+        let mut headers = Vec::new();
+        for header in request
+            .get("headers")
+            .and_then(Value::as_array)
+            .unwrap_or(&Vec::new())
+        {
+            if let (Some(name), Some(value)) = (
+                header.get("name").and_then(Value::as_str),
+                header.get("value").and_then(Value::as_str),
+            ) {
+                // HTTP/2 pseudo-headers such as `:authority` aren't real headers.
+                let lowercase = name.to_lowercase();
+                if !name.starts_with(':') && !SKIPPED_HEADERS.contains(&lowercase.as_str()) {
+                    headers.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        requests.push(HarRequest {
+            delay,
+            method,
+            path,
+            name: url.path().to_string(),
+            headers,
+            body: request
+                .pointer("/postData/text")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        });
+    }
+    if requests.is_empty() {
+        return Err("HAR file has no requests Goose can replay".to_string());
+    }
+    Ok(requests)
+}
+
+/// Parse an ISO 8601 date and time such as `2021-03-01T12:30:05.123+01:00`, as used in
+/// HAR files, into milliseconds since the Unix epoch.
+fn parse_date_time(date_time: &str) -> Option<i64> {
+    let number = |start: usize, end: usize| -> Option<i64> {
+        let digits = date_time.get(start..end)?;
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+    let separators = date_time.as_bytes();
+    if separators.len() < 20
+        || separators[4] != b'-'
+        || separators[7] != b'-'
+        || separators[10] != b'T'
+        || separators[13] != b':'
+        || separators[16] != b':'
+    {
+        return None;
+    }
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Optional fractional seconds, of which milliseconds are kept.
+    let mut rest = &date_time[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        millis = format!("{:0<3}", &fraction[..digits.min(3)]).parse().ok()?;
+        rest = &fraction[digits..];
+    }
+
+    // The time zone is either `Z` or an offset such as `+01:00`.
+    let offset = match rest {
+        "Z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let hours: i64 = rest[1..3].parse().ok()?;
+            let minutes: i64 = rest[4..6].parse().ok()?;
+            sign * (hours * 60 + minutes) * 60
+        }
+        _ => return None,
+    };
+
+    // Days since the epoch of the proleptic Gregorian calendar date, see
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    Some(seconds * 1_000 + millis)
+}
+
+/// Replay the requests recorded in the HAR file the running task was created from.
+async fn replay(user: &GooseUser) -> GooseTaskResult {
+    let requests = user.get_task_data::<Vec<HarRequest>>()?;
+    let mut previous_started: Option<time::Instant> = None;
+    for request in requests.iter() {
+        // The recorded delay is between starting requests, so the time the previous
+        // request took counts towards it.
+        if let Some(previous_started) = previous_started {
+            let elapsed = previous_started.elapsed();
+            if request.delay > elapsed {
+                tokio::time::delay_for(request.delay - elapsed).await;
+            }
+        }
+        previous_started = Some(time::Instant::now());
+        let mut request_builder = match request.method {
+            GooseMethod::DELETE => user.goose_delete(&request.path).await?,
+            GooseMethod::HEAD => user.goose_head(&request.path).await?,
+            GooseMethod::PATCH => user.goose_patch(&request.path).await?,
+            GooseMethod::POST => user.goose_post(&request.path).await?,
+            GooseMethod::PUT => user.goose_put(&request.path).await?,
+            _ => user.goose_get(&request.path).await?,
+        };
+        for (name, value) in &request.headers {
+            request_builder = request_builder.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = &request.body {
+            request_builder = request_builder.body(body.clone());
+        }
+        let _goose = user
+            .goose_send(request_builder, Some(&request.name))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn date_times() {
+        assert_eq!(parse_date_time("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_date_time("1970-01-01T00:00:01.5Z"), Some(1_500));
+        assert_eq!(
+            parse_date_time("2021-03-01T12:30:05.123Z"),
+            Some(1_614_601_805_123)
+        );
+        // Offsets and extra precision.
+        assert_eq!(
+            parse_date_time("2021-03-01T13:30:05.123456+01:00"),
+            Some(1_614_601_805_123)
+        );
+        assert_eq!(
+            parse_date_time("2021-03-01T07:30:05.123-05:00"),
+            Some(1_614_601_805_123)
+        );
+        assert_eq!(parse_date_time("2021-03-01T12:30:05"), None);
+        assert_eq!(parse_date_time("2021-03-01 12:30:05Z"), None);
+        assert_eq!(parse_date_time("2021-13-01T12:30:05Z"), None);
+        assert_eq!(parse_date_time("2021-03-01T12:30:05.Z"), None);
+        assert_eq!(parse_date_time("2021-03-01T12:30:05+0100"), None);
+    }
+
+    #[test]
+    fn requests() {
+        let har = json!({"log": {"entries": [
+            {
+                "startedDateTime": "2021-03-01T12:30:05.000Z",
+                "request": {
+                    "method": "GET",
+                    "url": "https://example.com/search?q=goose",
+                    "headers": [
+                        {"name": ":authority", "value": "example.com"},
+                        {"name": "Accept", "value": "text/html"},
+                        {"name": "Cookie", "value": "session=1"},
+                    ],
+                },
+            },
+            {
+                "startedDateTime": "2021-03-01T12:30:05.100Z",
+                "request": {"method": "GET", "url": "https://cdn.example.com/style.css"},
+            },
+            {
+                "startedDateTime": "2021-03-01T12:30:05.200Z",
+                "request": {"method": "OPTIONS", "url": "https://example.com/api"},
+            },
+            {
+                "startedDateTime": "2021-03-01T12:30:06.500Z",
+                "request": {
+                    "method": "POST",
+                    "url": "https://example.com/api",
+                    "postData": {"mimeType": "application/json", "text": "{}"},
+                },
+            },
+        ]}});
+        let requests = requests_from_har(&har).unwrap();
+        // Requests to other origins and with unsupported methods are left out, without
+        // affecting the delay.
+        assert_eq!(
+            requests,
+            vec![
+                HarRequest {
+                    delay: time::Duration::from_millis(0),
+                    method: GooseMethod::GET,
+                    path: "/search?q=goose".to_string(),
+                    name: "/search".to_string(),
+                    headers: vec![("Accept".to_string(), "text/html".to_string())],
+                    body: None,
+                },
+                HarRequest {
+                    delay: time::Duration::from_millis(1_500),
+                    method: GooseMethod::POST,
+                    path: "/api".to_string(),
+                    name: "/api".to_string(),
+                    headers: Vec::new(),
+                    body: Some("{}".to_string()),
+                },
+            ]
+        );
+
+        assert!(requests_from_har(&json!({"log": {}})).is_err());
+        assert!(requests_from_har(&json!({"log": {"entries": []}})).is_err());
+        assert!(requests_from_har(&json!({"log": {"entries": [
+            {"startedDateTime": "2021-03-01T12:30:05Z", "request": {"method": "GET"}},
+        ]}}))
+        .is_err());
+        assert!(requests_from_har(&json!({"log": {"entries": [
+            {"startedDateTime": "yesterday", "request": {"method": "GET", "url": "http://a/"}},
+        ]}}))
+        .is_err());
+    }
+}
@@ -208,9 +208,9 @@
 //! ```
 //!
 //! When printing statistics, by default Goose will display running values approximately
-//! every 15 seconds. Running statistics are broken into two tables. The first, above,
-//! shows how many requests have been made, how many of them failed (non-2xx response),
-//! and the corresponding per-second rates.
+//! every 15 seconds, configurable with `--running-stats-interval`. Running statistics are
+//! broken into two tables. The first, above, shows how many requests have been made, how
+//! many of them failed (non-2xx response), and the corresponding per-second rates.
 //!
 //! Note that Goose respected the per-task weights we set, and `foo` (with a weight of
 //! 10) is being loaded five times as often as `bar` (with a weight of 2). Also notice
@@ -302,51 +302,103 @@ extern crate log;
 
 extern crate structopt;
 
+mod control;
+pub mod data;
+pub mod feeder;
 pub mod goose;
+#[cfg(feature = "har")]
+pub mod har;
 pub mod logger;
 #[cfg(feature = "gaggle")]
 mod manager;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod prelude;
+mod prometheus;
 mod stats;
+mod threshold;
 mod throttle;
 mod user;
 mod util;
+pub mod websocket;
 #[cfg(feature = "gaggle")]
 mod worker;
 
 use lazy_static::lazy_static;
 #[cfg(feature = "gaggle")]
 use nng::Socket;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use simplelog::*;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
     Arc,
 };
 use std::{f32, fmt, io, time};
 use structopt::StructOpt;
-use tokio::fs::File;
+use tokio::fs::{File, OpenOptions};
 use tokio::io::BufWriter;
 use tokio::prelude::*;
-use tokio::sync::mpsc;
-use url::Url;
+use tokio::sync::{mpsc, RwLock};
 
+use crate::feeder::GooseFeeder;
 use crate::goose::{
-    GooseDebug, GooseRawRequest, GooseRequest, GooseTask, GooseTaskSet, GooseUser, GooseUserCommand,
+    GooseClientBuilder, GooseHistogram, GooseLog, GooseRawRequest, GooseRequest,
+    GooseRequestCallback, GooseRequestLog, GooseRequestLogger, GooseScheduler, GooseTask,
+    GooseTaskSet, GooseUser, GooseUserCommand,
 };
-use crate::stats::GooseStats;
-
-/// Constant defining how often statistics should be displayed while load test is running.
-const RUNNING_STATS_EVERY: usize = 15;
+use crate::stats::{GoosePause, GooseSlowRequest, GooseStats};
 
 /// Constant defining Goose's default port when running a Gaggle.
 const DEFAULT_PORT: &str = "5115";
 
+/// Columns that can be logged with `--stats-log-format csv`, see `--stats-log-columns`.
+const STATS_LOG_CSV_COLUMNS: [&str; 22] = [
+    "elapsed",
+    "timestamp",
+    "method",
+    "name",
+    "url",
+    "final_url",
+    "redirected",
+    "response_time",
+    "status_code",
+    "success",
+    "update",
+    "user",
+    "retried",
+    "bytes",
+    "bytes_sent",
+    "over_budget",
+    "too_fast",
+    "server_time",
+    "streamed",
+    "task_set",
+    "error_category",
+    "failure_tag",
+];
+/// Columns logged with `--stats-log-format csv` if `--stats-log-columns` isn't set.
+const DEFAULT_STATS_LOG_CSV_COLUMNS: &str =
+    "elapsed,timestamp,method,name,url,final_url,redirected,response_time,status_code,success,update,user,retried,bytes";
+/// The name all requests are grouped under with `--aggregate-only`, so only one set of
+/// statistics is tracked for each method.
+const AGGREGATE_ONLY_NAME: &str = "(all requests)";
+/// How many requests must be made before `--hatch-error-rate` stops launching users, so a
+/// few failed requests at startup don't.
+const HATCH_ERROR_MIN_REQUESTS: usize = 10;
+/// How many writes to the `--stats-log-file` must fail before it's disabled, for example
+/// because the disk is full. Buffered writes succeed between failures, so they're not
+/// failures in a row.
+const STATS_LOG_MAX_WRITE_FAILURES: usize = 3;
+/// Columns written to the `--throughput-log`, one row per `--throughput-interval`.
+const THROUGHPUT_LOG_CSV_HEADER: &str = "timestamp,second,requests,fails,users,paused";
+
 // WORKER_ID is only used when running a gaggle (a distributed load test).
 lazy_static! {
     static ref WORKER_ID: AtomicUsize = AtomicUsize::new(0);
@@ -355,6 +407,38 @@ lazy_static! {
 /// Internal representation of a weighted task list.
 type WeightedGooseTasks = Vec<Vec<usize>>;
 
+/// A launched user thread, and the channel used to control it.
+type GooseUserThread = (
+    tokio::task::JoinHandle<()>,
+    mpsc::UnboundedSender<GooseUserCommand>,
+    Arc<AtomicBool>,
+);
+
+/// Channels and flags shared by all user threads, used to launch each user.
+struct GooseUserLaunch {
+    /// Channel to the logger thread, if enabled.
+    logger: Option<mpsc::UnboundedSender<Option<GooseLog>>>,
+    /// Channel to the throttle thread used by each task set, if enabled.
+    throttles: Vec<Option<mpsc::Sender<bool>>>,
+    /// Channel users send statistics to the parent with.
+    parent: mpsc::UnboundedSender<GooseRawRequest>,
+    /// Counts users that have exited on their own.
+    finished_users: Arc<AtomicUsize>,
+    /// Set when the load test is canceled.
+    canceled: Arc<AtomicBool>,
+    /// Set while users are paused with --pause-file.
+    paused: Arc<AtomicBool>,
+}
+
+/// The `--throughput-log`, written as each bucket of the throughput time series completes.
+struct ThroughputLog {
+    file: BufWriter<File>,
+    /// Unix timestamp of when the load test started, bucket timestamps are offset from it.
+    started: u64,
+    /// How many buckets have already been written.
+    written: usize,
+}
+
 /// Worker ID to aid in tracing logs when running a Gaggle.
 pub fn get_worker_id() -> usize {
     WORKER_ID.load(Ordering::Relaxed)
@@ -411,6 +495,18 @@ pub enum GooseError {
     /// `GooseAttack` has no `GooseTaskSet` defined. An optional explanation may be found in
     /// `.detail`.
     NoTaskSets { detail: Option<String> },
+    /// One or more thresholds set with `--fail-if` were exceeded by the load test. Each
+    /// failed threshold is described in `.failures`. The statistics are displayed before
+    /// this is returned.
+    ThresholdsFailed { failures: Vec<String> },
+    /// A request failed while `--exit-on-error` was enabled, stopping the load test. The
+    /// failed request is found in `.request`. The statistics collected so far are
+    /// displayed before this is returned.
+    RequestFailed { request: Box<GooseRawRequest> },
+    /// Too many requests failed while launching users with `--hatch-error-rate` and
+    /// `--hatch-error-exit` enabled, stopping the load test. Why is found in `.detail`.
+    /// The statistics collected so far are displayed before this is returned.
+    HatchAborted { detail: String },
 }
 
 // Define how to display errors.
@@ -455,6 +551,10 @@ pub struct GooseAttack {
     test_start_task: Option<GooseTask>,
     /// An optional task to run one time after users have finished running task sets.
     test_stop_task: Option<GooseTask>,
+    /// An optional task to run once in each Worker process before it starts its users.
+    worker_start_task: Option<GooseTask>,
+    /// An optional task to run once in each Worker process after its users stop.
+    worker_stop_task: Option<GooseTask>,
     /// A vector containing one copy of each GooseTaskSet that will run during this load test.
     task_sets: Vec<GooseTaskSet>,
     /// A weighted vector containing a GooseUser object for each user that will run during this load test.
@@ -469,14 +569,48 @@ pub struct GooseAttack {
     run_time: usize,
     /// Track total number of users to run for this load test.
     users: usize,
+    /// Optional schedule of (users, seconds to hold) steps to launch users in.
+    step_load: Vec<(usize, usize)>,
     /// When the load test started.
     started: Option<time::Instant>,
+    /// When the load test started, not restarted after all users launch.
+    load_test_started: Option<time::Instant>,
     /// All requests statistics merged together.
     stats: GooseStats,
+    /// Optional named feeders of test data, shared by all users.
+    feeders: HashMap<String, GooseFeeder>,
+    /// Optional rules rewriting request paths before they're used as statistics keys.
+    stats_normalizations: Vec<(Regex, String)>,
+    /// Thresholds that fail the load test if exceeded, configured with `--fail-if`.
+    thresholds: Vec<threshold::GooseThreshold>,
+    /// Optional custom client builder, called once for each user.
+    client_builder: Option<GooseClientBuilder>,
+    /// Optional custom formatter for `--log-requests`.
+    request_logger: Option<GooseRequestLogger>,
+    /// Optional function called with each request as it's recorded.
+    request_callback: Option<GooseRequestCallback>,
+    /// Optional weighted pool of (host, weight) users are distributed across.
+    hosts: Vec<(String, usize)>,
+    /// Whether users rotate through the pool of hosts with each request.
+    rotate_hosts: bool,
+    /// Optional population of (task set name, percent) that replaces task set weights.
+    population: Vec<(String, usize)>,
+    /// The first request that failed, only tracked if `--exit-on-error` is enabled.
+    failed_request: Option<GooseRawRequest>,
+    /// Why launching users was stopped, only tracked if `--hatch-error-exit` is enabled.
+    hatch_aborted: Option<String>,
+    /// How many writes to the `--stats-log-file` failed.
+    stats_log_write_failures: usize,
+    /// Why the `--stats-log-file` couldn't be written, only tracked if
+    /// `--stats-log-required` is enabled.
+    stats_log_failed: Option<(io::ErrorKind, String)>,
 }
 /// Goose's internal global state.
 impl GooseAttack {
-    /// Load configuration from command line and initialize a GooseAttack.
+    /// Load configuration from command line and environment variables, and initialize a
+    /// GooseAttack. Each option can also be set by a variable named after it with the
+    /// `GOOSE_` prefix, for example `GOOSE_RUN_TIME=30m`, which is overridden by the
+    /// command line.
     ///
     /// # Example
     /// ```rust,no_run
@@ -488,19 +622,81 @@ impl GooseAttack {
         let goose_attack = GooseAttack {
             test_start_task: None,
             test_stop_task: None,
+            worker_start_task: None,
+            worker_stop_task: None,
             task_sets: Vec::new(),
             weighted_users: Vec::new(),
             host: None,
-            configuration: GooseConfiguration::from_args(),
+            configuration: GooseConfiguration::from_args_and_config_file(None)?,
             number_of_cpus: num_cpus::get(),
             run_time: 0,
             users: 0,
+            step_load: Vec::new(),
             started: None,
+            load_test_started: None,
             stats: GooseStats::default(),
+            feeders: HashMap::new(),
+            stats_normalizations: Vec::new(),
+            thresholds: Vec::new(),
+            client_builder: None,
+            request_logger: None,
+            request_callback: None,
+            hosts: Vec::new(),
+            rotate_hosts: false,
+            population: Vec::new(),
+            failed_request: None,
+            hatch_aborted: None,
+            stats_log_write_failures: 0,
+            stats_log_failed: None,
         };
         Ok(goose_attack.setup()?)
     }
 
+    /// Load configuration from a TOML or YAML file and initialize a GooseAttack. Options
+    /// set on the command line override options set with environment variables, which
+    /// override options set in the file, which in turn override the defaults. Files ending in `.yaml` or `.yml` are parsed as YAML, all others as
+    /// TOML.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut goose_attack = GooseAttack::initialize_with_config_file("goose.toml");
+    /// ```
+    pub fn initialize_with_config_file(path: &str) -> Result<GooseAttack, GooseError> {
+        let goose_attack = GooseAttack {
+            test_start_task: None,
+            test_stop_task: None,
+            worker_start_task: None,
+            worker_stop_task: None,
+            task_sets: Vec::new(),
+            weighted_users: Vec::new(),
+            host: None,
+            configuration: GooseConfiguration::from_args_and_config_file(Some(path))?,
+            number_of_cpus: num_cpus::get(),
+            run_time: 0,
+            users: 0,
+            step_load: Vec::new(),
+            started: None,
+            load_test_started: None,
+            stats: GooseStats::default(),
+            feeders: HashMap::new(),
+            stats_normalizations: Vec::new(),
+            thresholds: Vec::new(),
+            client_builder: None,
+            request_logger: None,
+            request_callback: None,
+            hosts: Vec::new(),
+            rotate_hosts: false,
+            population: Vec::new(),
+            failed_request: None,
+            hatch_aborted: None,
+            stats_log_write_failures: 0,
+            stats_log_failed: None,
+        };
+        goose_attack.setup()
+    }
+
     /// Initialize a GooseAttack with an already loaded configuration.
     /// This should only be called by worker instances.
     ///
@@ -516,6 +712,8 @@ impl GooseAttack {
         GooseAttack {
             test_start_task: None,
             test_stop_task: None,
+            worker_start_task: None,
+            worker_stop_task: None,
             task_sets: Vec::new(),
             weighted_users: Vec::new(),
             host: None,
@@ -523,8 +721,23 @@ impl GooseAttack {
             number_of_cpus: num_cpus::get(),
             run_time: 0,
             users: 0,
+            step_load: Vec::new(),
             started: None,
+            load_test_started: None,
             stats: GooseStats::default(),
+            feeders: HashMap::new(),
+            stats_normalizations: Vec::new(),
+            thresholds: Vec::new(),
+            client_builder: None,
+            request_logger: None,
+            request_callback: None,
+            hosts: Vec::new(),
+            rotate_hosts: false,
+            population: Vec::new(),
+            failed_request: None,
+            hatch_aborted: None,
+            stats_log_write_failures: 0,
+            stats_log_failed: None,
         }
     }
 
@@ -532,6 +745,7 @@ impl GooseAttack {
         // Allow optionally controlling debug output level
         let debug_level;
         match self.configuration.verbose {
+            _ if self.configuration.quiet => debug_level = LevelFilter::Error,
             0 => debug_level = LevelFilter::Warn,
             1 => debug_level = LevelFilter::Info,
             2 => debug_level = LevelFilter::Debug,
@@ -588,6 +802,41 @@ impl GooseAttack {
                 });
             }
 
+            // Don't allow overhead of collecting statistics unless we're printing them.
+            if self.configuration.aggregate_only {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --aggregate-only."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // Server timings are only displayed with statistics.
+            if self.configuration.server_timing {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --server-timing.".to_string(),
+                    ),
+                });
+            }
+
+            // Don't allow overhead of collecting statistics unless we're printing them.
+            if self.configuration.running_percentiles {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --running-percentiles."
+                            .to_string(),
+                    ),
+                });
+            }
+
             // Don't allow overhead of collecting statistics unless we're printing them.
             if self.configuration.only_summary {
                 return Err(GooseError::InvalidOption {
@@ -622,6 +871,121 @@ impl GooseAttack {
                     ),
                 });
             }
+
+            // There is nothing to reset if statistics are disabled.
+            if !self.configuration.reset_interval.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --reset-interval."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to expose if statistics are disabled.
+            if self.configuration.prometheus_port.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --prometheus-port."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to correct if statistics are disabled.
+            if self.configuration.co_correction {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --co-correction.".to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to report if statistics are disabled.
+            if !self.configuration.report_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --report-file.".to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to summarize if statistics are disabled.
+            if !self.configuration.stats_summary_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --stats-summary-file."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to check thresholds against if statistics are disabled.
+            if !self.configuration.fail_if.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --fail-if.".to_string(),
+                    ),
+                });
+            }
+
+            // There is no throughput to log if statistics are disabled.
+            if !self.configuration.throughput_log.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --throughput-log."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // There is nothing to discard if statistics are disabled.
+            if !self.configuration.warmup.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --warmup.".to_string(),
+                    ),
+                });
+            }
+
+            // Failed requests are only noticed if statistics are enabled.
+            if self.configuration.exit_on_error {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --exit-on-error.".to_string(),
+                    ),
+                });
+            }
+
+            // Failed requests are only noticed if statistics are enabled.
+            if self.configuration.hatch_error_rate.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-stats".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "--no-stats must not be enabled when enabling --hatch-error-rate."
+                            .to_string(),
+                    ),
+                });
+            }
         }
 
         if self.configuration.stats_log_format != "json" {
@@ -651,95 +1015,764 @@ impl GooseAttack {
             }
         }
 
-        if self.configuration.debug_log_format != "json" {
-            // Log format isn't relevant if log not enabled.
-            if self.configuration.debug_log_file.is_empty() {
+        // There is nothing to require if the statistics log isn't enabled.
+        if self.configuration.stats_log_required && self.configuration.stats_log_file.is_empty() {
+            return Err(GooseError::InvalidOption {
+                option: "--stats-log-required".to_string(),
+                value: "true".to_string(),
+                detail: Some(
+                    "--stats-log-file must be enabled when enabling --stats-log-required."
+                        .to_string(),
+                ),
+            });
+        }
+
+        // There is nothing to append to if the statistics log isn't enabled.
+        if self.configuration.stats_log_append && self.configuration.stats_log_file.is_empty() {
+            return Err(GooseError::InvalidOption {
+                option: "--stats-log-append".to_string(),
+                value: "true".to_string(),
+                detail: Some(
+                    "--stats-log-file must be enabled when enabling --stats-log-append."
+                        .to_string(),
+                ),
+            });
+        }
+
+        if !self.configuration.stats_log_columns.is_empty() {
+            // Columns can only be selected for the CSV log format.
+            if self.configuration.stats_log_format != "csv" {
                 return Err(GooseError::InvalidOption {
-                    option: "--debug-log-format".to_string(),
-                    value: self.configuration.debug_log_format,
+                    option: "--stats-log-columns".to_string(),
+                    value: self.configuration.stats_log_columns,
                     detail: Some(
-                        "--debug-log-file must be enabled when setting --debug-log-format."
+                        "--stats-log-format must be set to csv when setting --stats-log-columns."
                             .to_string(),
                     ),
                 });
             }
 
-            // All of these options must be defined below, search for formatted_log.
-            let options = vec!["json", "raw"];
-            if !options.contains(&self.configuration.debug_log_format.as_str()) {
-                return Err(GooseError::InvalidOption {
-                    option: "--debug-log-format".to_string(),
-                    value: self.configuration.debug_log_format,
-                    detail: Some(format!(
-                        "--debug-log-format must be set to one of: {}.",
-                        options.join(", ")
-                    )),
-                });
+            for column in self.configuration.stats_log_columns.split(',') {
+                if !STATS_LOG_CSV_COLUMNS.contains(&column.trim()) {
+                    return Err(GooseError::InvalidOption {
+                        option: "--stats-log-columns".to_string(),
+                        value: self.configuration.stats_log_columns.clone(),
+                        detail: Some(format!(
+                            "unknown column {:?}, --stats-log-columns must be a list of: {}.",
+                            column.trim(),
+                            STATS_LOG_CSV_COLUMNS.join(", ")
+                        )),
+                    });
+                }
             }
         }
 
-        // Configure maximum run time if specified, otherwise run until canceled.
-        if self.configuration.worker {
-            if self.configuration.run_time != "" {
+        // Running statistics are displayed at most once per second.
+        if self.configuration.running_stats_interval == 0 {
+            return Err(GooseError::InvalidOption {
+                option: "--running-stats-interval".to_string(),
+                value: self.configuration.running_stats_interval.to_string(),
+                detail: Some("--running-stats-interval must be at least 1.".to_string()),
+            });
+        }
+
+        // Percentiles are added to running statistics, which aren't displayed with
+        // --only-summary or --quiet.
+        if self.configuration.running_percentiles {
+            if self.configuration.only_summary {
                 return Err(GooseError::InvalidOption {
-                    option: "--run-time".to_string(),
-                    value: "true".to_string(),
+                    option: "--running-percentiles".to_string(),
+                    value: self.configuration.running_percentiles.to_string(),
                     detail: Some(
-                        "The --run-time option is only available to the manager.".to_string(),
+                        "--running-percentiles and --only-summary can not be enabled together."
+                            .to_string(),
                     ),
                 });
             }
-            self.run_time = 0;
-        } else if self.configuration.run_time != "" {
-            self.run_time = util::parse_timespan(&self.configuration.run_time);
-            info!("run_time = {}", self.run_time);
-        } else {
-            self.run_time = 0;
+            if self.configuration.quiet {
+                return Err(GooseError::InvalidOption {
+                    option: "--running-percentiles".to_string(),
+                    value: self.configuration.running_percentiles.to_string(),
+                    detail: Some(
+                        "--running-percentiles and --quiet can not be enabled together."
+                            .to_string(),
+                    ),
+                });
+            }
+            info!(
+                "running_percentiles = {}",
+                self.configuration.running_percentiles
+            );
         }
+        self.stats.running_percentiles = self.configuration.running_percentiles;
 
-        // Configure number of user threads to launch, default to the number of CPU cores available.
-        self.users = match self.configuration.users {
-            Some(u) => {
-                if u == 0 {
-                    if self.configuration.worker {
-                        return Err(GooseError::InvalidOption {
-                            option: "--users".to_string(),
-                            value: self.users.to_string(),
-                            detail: Some("at least 1 user is required.".to_string()),
-                        });
-                    }
-                    0
-                } else {
-                    if self.configuration.worker {
-                        return Err(GooseError::InvalidOption {
-                            option: "--users".to_string(),
-                            value: self.users.to_string(),
-                            detail: Some(
-                                "--users option only available to manager process".to_string(),
-                            ),
-                        });
-                    }
-                    u
-                }
-            }
-            None => {
-                let u = self.number_of_cpus;
-                if !self.configuration.manager && !self.configuration.worker {
-                    info!("concurrent users defaulted to {} (number of CPUs)", u);
-                }
-                u
+        // Users make at least one request at a time.
+        if self.configuration.max_in_flight == 0 {
+            return Err(GooseError::InvalidOption {
+                option: "--max-in-flight".to_string(),
+                value: self.configuration.max_in_flight.to_string(),
+                detail: Some("--max-in-flight must be at least 1.".to_string()),
+            });
+        }
+
+        // Throughput is tracked in buckets of at least one second.
+        if self.configuration.throughput_interval == 0 {
+            return Err(GooseError::InvalidOption {
+                option: "--throughput-interval".to_string(),
+                value: self.configuration.throughput_interval.to_string(),
+                detail: Some("--throughput-interval must be at least 1.".to_string()),
+            });
+        }
+        self.stats.throughput_interval = self.configuration.throughput_interval;
+
+        // The throughput log is written by the process running users.
+        if !self.configuration.throughput_log.is_empty() {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--throughput-log".to_string(),
+                    value: self.configuration.throughput_log,
+                    detail: Some(
+                        "--throughput-log is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
             }
-        };
+            info!("throughput_log = {}", self.configuration.throughput_log);
+        }
 
-        if !self.configuration.manager && !self.configuration.worker {
-            debug!("users = {}", self.users);
+        // The slowest requests are tracked by the process recording individual requests.
+        if self.configuration.show_slowest > 0
+            && (self.configuration.manager || self.configuration.worker)
+        {
+            return Err(GooseError::InvalidOption {
+                option: "--show-slowest".to_string(),
+                value: self.configuration.show_slowest.to_string(),
+                detail: Some(
+                    "--show-slowest is only available when running in stand-alone mode."
+                        .to_string(),
+                ),
+            });
         }
+        self.stats.slowest_count = self.configuration.show_slowest;
 
-        Ok(self)
-    }
+        // Failed requests are noticed by the process recording individual requests.
+        if self.configuration.exit_on_error
+            && (self.configuration.manager || self.configuration.worker)
+        {
+            return Err(GooseError::InvalidOption {
+                option: "--exit-on-error".to_string(),
+                value: self.configuration.exit_on_error.to_string(),
+                detail: Some(
+                    "--exit-on-error is only available when running in stand-alone mode."
+                        .to_string(),
+                ),
+            });
+        }
 
-    /// A load test must contain one or more `GooseTaskSet`s. Each task set must
-    /// be registered into Goose's global state with this method for it to run.
+        // Validate the error rate that stops launching users, if specified.
+        if let Some(hatch_error_rate) = self.configuration.hatch_error_rate {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--hatch-error-rate".to_string(),
+                    value: hatch_error_rate.to_string(),
+                    detail: Some(
+                        "--hatch-error-rate is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+            if hatch_error_rate <= 0.0 || hatch_error_rate > 100.0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--hatch-error-rate".to_string(),
+                    value: hatch_error_rate.to_string(),
+                    detail: Some(
+                        "--hatch-error-rate must be greater than 0 and at most 100.".to_string(),
+                    ),
+                });
+            }
+            info!("hatch_error_rate = {}%", hatch_error_rate);
+        } else if self.configuration.hatch_error_exit {
+            return Err(GooseError::InvalidOption {
+                option: "--hatch-error-exit".to_string(),
+                value: self.configuration.hatch_error_exit.to_string(),
+                detail: Some("--hatch-error-exit requires --hatch-error-rate.".to_string()),
+            });
+        }
+
+        // Requests are grouped by host by the process recording individual requests.
+        if self.configuration.host.len() > 1 && self.configuration.manager {
+            return Err(GooseError::InvalidOption {
+                option: "--host".to_string(),
+                value: self.configuration.host.join(","),
+                detail: Some(
+                    "--host can only be set more than once when running in stand-alone mode."
+                        .to_string(),
+                ),
+            });
+        }
+
+        // The user is identified in a header, which must have a valid name.
+        if self.configuration.user_id {
+            if reqwest::header::HeaderName::from_bytes(self.configuration.user_id_header.as_bytes())
+                .is_err()
+            {
+                return Err(GooseError::InvalidOption {
+                    option: "--user-id-header".to_string(),
+                    value: self.configuration.user_id_header,
+                    detail: Some("--user-id-header must be a valid header name.".to_string()),
+                });
+            }
+            info!("user_id_header = {}", self.configuration.user_id_header);
+        }
+
+        // Users can't follow a redirect of base_url if redirects aren't followed.
+        if self.configuration.max_redirects == Some(0) && self.configuration.sticky_follow {
+            return Err(GooseError::InvalidOption {
+                option: "--max-redirects".to_string(),
+                value: "0".to_string(),
+                detail: Some("--sticky-follow requires following redirects.".to_string()),
+            });
+        }
+
+        // Parse thresholds now, so invalid expressions are reported before the load test runs.
+        for expression in &self.configuration.fail_if {
+            match threshold::GooseThreshold::parse(expression) {
+                Ok(threshold) => self.thresholds.push(threshold),
+                Err(detail) => {
+                    return Err(GooseError::InvalidOption {
+                        option: "--fail-if".to_string(),
+                        value: expression.to_string(),
+                        detail: Some(detail),
+                    })
+                }
+            }
+        }
+
+        // The histogram used to calculate percentiles can track response times from 1 ms.
+        if self.configuration.stats_max_response_time == 1 {
+            return Err(GooseError::InvalidOption {
+                option: "--stats-max-response-time".to_string(),
+                value: self.configuration.stats_max_response_time.to_string(),
+                detail: Some("--stats-max-response-time must be at least 2.".to_string()),
+            });
+        }
+        if self.configuration.stats_significant_figures > 5 {
+            return Err(GooseError::InvalidOption {
+                option: "--stats-significant-figures".to_string(),
+                value: self.configuration.stats_significant_figures.to_string(),
+                detail: Some("--stats-significant-figures must be between 1 and 5.".to_string()),
+            });
+        }
+
+        // Configure which percentiles to display, if not set the defaults are used.
+        if !self.configuration.stats_percentiles.is_empty() {
+            self.stats.percentiles =
+                match stats::parse_percentiles(&self.configuration.stats_percentiles) {
+                    Ok(p) => p,
+                    Err(detail) => {
+                        return Err(GooseError::InvalidOption {
+                            option: "--stats-percentiles".to_string(),
+                            value: self.configuration.stats_percentiles,
+                            detail: Some(detail),
+                        })
+                    }
+                };
+        }
+
+        if self.configuration.debug_log_format != "json" {
+            // Log format isn't relevant if log not enabled.
+            if self.configuration.debug_log_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--debug-log-format".to_string(),
+                    value: self.configuration.debug_log_format,
+                    detail: Some(
+                        "--debug-log-file must be enabled when setting --debug-log-format."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            // All of these options must be defined below, search for formatted_log.
+            let options = vec!["json", "raw"];
+            if !options.contains(&self.configuration.debug_log_format.as_str()) {
+                return Err(GooseError::InvalidOption {
+                    option: "--debug-log-format".to_string(),
+                    value: self.configuration.debug_log_format,
+                    detail: Some(format!(
+                        "--debug-log-format must be set to one of: {}.",
+                        options.join(", ")
+                    )),
+                });
+            }
+        }
+
+        if self.configuration.debug_log_sample != 1.0 {
+            // Sampling isn't relevant if log not enabled.
+            if self.configuration.debug_log_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--debug-log-sample".to_string(),
+                    value: self.configuration.debug_log_sample.to_string(),
+                    detail: Some(
+                        "--debug-log-file must be enabled when setting --debug-log-sample."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            if self.configuration.debug_log_sample <= 0.0
+                || self.configuration.debug_log_sample > 1.0
+            {
+                return Err(GooseError::InvalidOption {
+                    option: "--debug-log-sample".to_string(),
+                    value: self.configuration.debug_log_sample.to_string(),
+                    detail: Some(
+                        "--debug-log-sample must be greater than 0 and at most 1.".to_string(),
+                    ),
+                });
+            }
+        }
+
+        if self.configuration.log_requests_body || self.configuration.log_requests_sample != 100.0 {
+            // Request logging options aren't relevant if requests aren't logged.
+            if self.configuration.log_requests.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: if self.configuration.log_requests_body {
+                        "--log-requests-body".to_string()
+                    } else {
+                        "--log-requests-sample".to_string()
+                    },
+                    value: "".to_string(),
+                    detail: Some(
+                        "--log-requests must be enabled when setting --log-requests-body or --log-requests-sample."
+                            .to_string(),
+                    ),
+                });
+            }
+
+            if self.configuration.log_requests_sample <= 0.0
+                || self.configuration.log_requests_sample > 100.0
+            {
+                return Err(GooseError::InvalidOption {
+                    option: "--log-requests-sample".to_string(),
+                    value: self.configuration.log_requests_sample.to_string(),
+                    detail: Some(
+                        "--log-requests-sample must be greater than 0 and at most 100.".to_string(),
+                    ),
+                });
+            }
+        }
+
+        // Configure maximum run time if specified, otherwise run until canceled.
+        if self.configuration.worker {
+            if self.configuration.run_time != "" {
+                return Err(GooseError::InvalidOption {
+                    option: "--run-time".to_string(),
+                    value: "true".to_string(),
+                    detail: Some(
+                        "The --run-time option is only available to the manager.".to_string(),
+                    ),
+                });
+            }
+            self.run_time = 0;
+        } else if self.configuration.run_time != "" {
+            self.run_time = util::parse_timespan(&self.configuration.run_time);
+            info!("run_time = {}", self.run_time);
+        } else {
+            self.run_time = 0;
+        }
+
+        // Prometheus metrics are served by the process collecting statistics.
+        if let Some(port) = self.configuration.prometheus_port {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--prometheus-port".to_string(),
+                    value: port.to_string(),
+                    detail: Some(
+                        "--prometheus-port is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+
+        // Users can only be added by the process launching them.
+        if let Some(port) = self.configuration.control_port {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--control-port".to_string(),
+                    value: port.to_string(),
+                    detail: Some(
+                        "--control-port is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+
+        // Validate the shutdown time if specified, otherwise all users are stopped at once.
+        if !self.configuration.shutdown_time.is_empty() {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--shutdown-time".to_string(),
+                    value: self.configuration.shutdown_time,
+                    detail: Some(
+                        "--shutdown-time is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+            if self.configuration.iterations > 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--shutdown-time".to_string(),
+                    value: self.configuration.shutdown_time,
+                    detail: Some(
+                        "--shutdown-time and --iterations can not be enabled together.".to_string(),
+                    ),
+                });
+            }
+            let shutdown_time = util::parse_timespan(&self.configuration.shutdown_time);
+            if shutdown_time == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--shutdown-time".to_string(),
+                    value: self.configuration.shutdown_time,
+                    detail: Some("--shutdown-time must be at least 1 second.".to_string()),
+                });
+            }
+            info!("shutdown_time = {}", shutdown_time);
+        }
+
+        // Validate the warmup if specified, otherwise all statistics are kept.
+        if !self.configuration.warmup.is_empty() {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--warmup".to_string(),
+                    value: self.configuration.warmup,
+                    detail: Some(
+                        "--warmup is only available when running in stand-alone mode.".to_string(),
+                    ),
+                });
+            }
+            let warmup = util::parse_timespan(&self.configuration.warmup);
+            if warmup == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--warmup".to_string(),
+                    value: self.configuration.warmup,
+                    detail: Some("--warmup must be at least 1 second.".to_string()),
+                });
+            }
+            // The run time starts once all users are launched, as does the warmup.
+            if self.run_time > 0 && warmup >= self.run_time {
+                return Err(GooseError::InvalidOption {
+                    option: "--warmup".to_string(),
+                    value: self.configuration.warmup,
+                    detail: Some("--warmup must be shorter than --run-time.".to_string()),
+                });
+            }
+            info!("warmup = {}", warmup);
+        }
+
+        // Connection warmup requests are sent by each user before it starts.
+        if self.configuration.warmup_requests > 0 {
+            info!("warmup_requests = {}", self.configuration.warmup_requests);
+        } else if !self.configuration.warmup_path.is_empty() {
+            return Err(GooseError::InvalidOption {
+                option: "--warmup-path".to_string(),
+                value: self.configuration.warmup_path,
+                detail: Some("--warmup-path requires --warmup-requests.".to_string()),
+            });
+        }
+
+        // Validate the statistics reset interval if specified, otherwise statistics aren't reset.
+        if !self.configuration.reset_interval.is_empty() {
+            if self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--reset-interval".to_string(),
+                    value: self.configuration.reset_interval,
+                    detail: Some("--reset-interval is only available to the manager.".to_string()),
+                });
+            }
+            let reset_interval = util::parse_timespan(&self.configuration.reset_interval);
+            if reset_interval == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--reset-interval".to_string(),
+                    value: self.configuration.reset_interval,
+                    detail: Some("--reset-interval must be at least 1 second.".to_string()),
+                });
+            }
+            info!("reset_interval = {}", reset_interval);
+        }
+
+        // Validate the worker timeout if specified, otherwise the manager waits for workers
+        // indefinitely.
+        if !self.configuration.worker_timeout.is_empty() {
+            if !self.configuration.manager {
+                return Err(GooseError::InvalidOption {
+                    option: "--worker-timeout".to_string(),
+                    value: self.configuration.worker_timeout,
+                    detail: Some("--worker-timeout is only available to the manager.".to_string()),
+                });
+            }
+            let worker_timeout = util::parse_timespan(&self.configuration.worker_timeout);
+            if worker_timeout == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--worker-timeout".to_string(),
+                    value: self.configuration.worker_timeout,
+                    detail: Some("--worker-timeout must be at least 1 second.".to_string()),
+                });
+            }
+            info!("worker_timeout = {}", worker_timeout);
+        }
+
+        // Configure the optional number of iterations, which replaces the maximum run time.
+        if self.configuration.iterations > 0 {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--iterations".to_string(),
+                    value: self.configuration.iterations.to_string(),
+                    detail: Some(
+                        "--iterations is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+            if !self.configuration.run_time.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--iterations".to_string(),
+                    value: self.configuration.iterations.to_string(),
+                    detail: Some(
+                        "--iterations and --run-time can not be enabled together.".to_string(),
+                    ),
+                });
+            }
+            if !self.configuration.step_load.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--iterations".to_string(),
+                    value: self.configuration.iterations.to_string(),
+                    detail: Some(
+                        "--iterations and --step-load can not be enabled together.".to_string(),
+                    ),
+                });
+            }
+            info!("iterations = {}", self.configuration.iterations);
+        }
+
+        if let Some(seed) = self.configuration.seed {
+            info!("seed = {}", seed);
+        }
+
+        // Quiet mode only prints the final summary.
+        if self.configuration.quiet {
+            if self.configuration.verbose > 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--quiet".to_string(),
+                    value: self.configuration.quiet.to_string(),
+                    detail: Some("--quiet and --verbose can not be enabled together.".to_string()),
+                });
+            }
+            if !self.configuration.reset_interval.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--quiet".to_string(),
+                    value: self.configuration.quiet.to_string(),
+                    detail: Some(
+                        "--quiet and --reset-interval can not be enabled together.".to_string(),
+                    ),
+                });
+            }
+            info!("quiet = {}", self.configuration.quiet);
+        }
+
+        // Validate the request timeout if specified, otherwise requests don't time out.
+        if !self.configuration.timeout.is_empty() {
+            let timeout = util::parse_timespan(&self.configuration.timeout);
+            if timeout == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--timeout".to_string(),
+                    value: self.configuration.timeout,
+                    detail: Some("--timeout must be at least 1 second.".to_string()),
+                });
+            }
+            info!("timeout = {}", timeout);
+        }
+
+        // Validate the connect timeout if specified, otherwise only --timeout applies.
+        if !self.configuration.connect_timeout.is_empty() {
+            let connect_timeout = util::parse_timespan(&self.configuration.connect_timeout);
+            if connect_timeout == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--connect-timeout".to_string(),
+                    value: self.configuration.connect_timeout,
+                    detail: Some("--connect-timeout must be at least 1 second.".to_string()),
+                });
+            }
+            info!("connect_timeout = {}", connect_timeout);
+        }
+
+        // Validate the TCP keepalive interval if specified, otherwise keepalive isn't enabled.
+        if !self.configuration.tcp_keepalive.is_empty() {
+            let tcp_keepalive = util::parse_timespan(&self.configuration.tcp_keepalive);
+            if tcp_keepalive == 0 {
+                return Err(GooseError::InvalidOption {
+                    option: "--tcp-keepalive".to_string(),
+                    value: self.configuration.tcp_keepalive,
+                    detail: Some("--tcp-keepalive must be at least 1 second.".to_string()),
+                });
+            }
+            info!("tcp_keepalive = {}", tcp_keepalive);
+        }
+
+        // Certificate verification is only disabled when explicitly requested.
+        if self.configuration.accept_invalid_certs {
+            warn!("--accept-invalid-certs: TLS certificates are NOT verified, do not load test production servers with this option!");
+        }
+
+        // Validate the proxy if specified, otherwise requests are made directly.
+        if !self.configuration.proxy.is_empty() {
+            if let Err(e) = reqwest::Proxy::all(&self.configuration.proxy) {
+                return Err(GooseError::InvalidOption {
+                    option: "--proxy".to_string(),
+                    value: self.configuration.proxy,
+                    detail: Some(format!("invalid proxy URL: {}", e)),
+                });
+            }
+            info!("proxy = {}", self.configuration.proxy);
+        }
+        if !self.configuration.proxy_user.is_empty() {
+            if self.configuration.proxy.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--proxy-user".to_string(),
+                    value: self.configuration.proxy_user,
+                    detail: Some("--proxy-user requires --proxy.".to_string()),
+                });
+            }
+            if !self.configuration.proxy_user.contains(':') {
+                return Err(GooseError::InvalidOption {
+                    option: "--proxy-user".to_string(),
+                    value: self.configuration.proxy_user,
+                    detail: Some("--proxy-user must be formatted username:password.".to_string()),
+                });
+            }
+        }
+
+        // Validate host mappings, requests to these hosts connect to the mapped IP.
+        for resolve in &self.configuration.resolve {
+            let (host, port, _) = match util::parse_resolve(resolve) {
+                Ok(mapping) => mapping,
+                Err(detail) => {
+                    return Err(GooseError::InvalidOption {
+                        option: "--resolve".to_string(),
+                        value: resolve.to_string(),
+                        detail: Some(detail),
+                    });
+                }
+            };
+            // TLS would verify the certificate against the IP instead of the host name.
+            let https = port == 443
+                || self
+                    .configuration
+                    .host
+                    .iter()
+                    .filter_map(|configured| url::Url::parse(configured).ok())
+                    .any(|url| {
+                        url.scheme() == "https"
+                            && url.host_str() == Some(&host)
+                            && url.port_or_known_default() == Some(port)
+                    });
+            if https {
+                return Err(GooseError::InvalidOption {
+                    option: "--resolve".to_string(),
+                    value: resolve.to_string(),
+                    detail: Some("--resolve only supports http hosts, not https.".to_string()),
+                });
+            }
+            info!("resolve = {}", resolve);
+        }
+
+        // Configure number of user threads to launch, default to the number of CPU cores available.
+        self.users = match self.configuration.users {
+            Some(u) => {
+                if u == 0 {
+                    if self.configuration.worker {
+                        return Err(GooseError::InvalidOption {
+                            option: "--users".to_string(),
+                            value: self.users.to_string(),
+                            detail: Some("at least 1 user is required.".to_string()),
+                        });
+                    }
+                    0
+                } else {
+                    if self.configuration.worker {
+                        return Err(GooseError::InvalidOption {
+                            option: "--users".to_string(),
+                            value: self.users.to_string(),
+                            detail: Some(
+                                "--users option only available to manager process".to_string(),
+                            ),
+                        });
+                    }
+                    u
+                }
+            }
+            None => {
+                let u = self.number_of_cpus;
+                if !self.configuration.manager && !self.configuration.worker {
+                    info!("concurrent users defaulted to {} (number of CPUs)", u);
+                }
+                u
+            }
+        };
+
+        // Configure optional step load schedule, which determines how many users to launch.
+        if !self.configuration.step_load.is_empty() {
+            if self.configuration.manager || self.configuration.worker {
+                return Err(GooseError::InvalidOption {
+                    option: "--step-load".to_string(),
+                    value: self.configuration.step_load,
+                    detail: Some(
+                        "--step-load is only available when running in stand-alone mode."
+                            .to_string(),
+                    ),
+                });
+            }
+            if self.configuration.users.is_some() {
+                return Err(GooseError::InvalidOption {
+                    option: "--step-load".to_string(),
+                    value: self.configuration.step_load,
+                    detail: Some("--users must not be set when enabling --step-load.".to_string()),
+                });
+            }
+            if !self.configuration.run_time.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--step-load".to_string(),
+                    value: self.configuration.step_load,
+                    detail: Some(
+                        "--run-time must not be set when enabling --step-load.".to_string(),
+                    ),
+                });
+            }
+            self.step_load = match util::parse_step_load(&self.configuration.step_load) {
+                Ok(step_load) => step_load,
+                Err(detail) => {
+                    return Err(GooseError::InvalidOption {
+                        option: "--step-load".to_string(),
+                        value: self.configuration.step_load,
+                        detail: Some(detail),
+                    })
+                }
+            };
+            // The final step determines how many users are launched in total.
+            self.users = self.step_load.last().unwrap().0;
+            info!("step_load = {:?}", self.step_load);
+        }
+
+        if !self.configuration.manager && !self.configuration.worker {
+            debug!("users = {}", self.users);
+        }
+
+        Ok(self)
+    }
+
+    /// A load test must contain one or more `GooseTaskSet`s. Each task set must
+    /// be registered into Goose's global state with this method for it to run.
     ///
     /// # Example
     /// ```rust,no_run
@@ -763,18 +1796,211 @@ impl GooseAttack {
     ///     Ok(())
     /// }
     ///
-    /// async fn other_task(user: &GooseUser) -> GooseTaskResult {
-    ///     let _goose = user.get("/bar").await?;
+    /// async fn other_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/bar").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn register_taskset(mut self, mut taskset: GooseTaskSet) -> Self {
+        taskset.task_sets_index = self.task_sets.len();
+        self.task_sets.push(taskset);
+        self
+    }
+
+    /// Register a named feeder of test data, making it available to all users through
+    /// `GooseUser.get_feeder_row()` and `GooseUser.get_feeder_record()`. Rows are shared
+    /// by all users, so each row is handed out to one user at a time.
+    ///
+    /// When running in a distributed Gaggle, each Worker loads its own copy of the feeder.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_feeder("products", GooseFeeder::from_csv("products.csv")?)
+    ///         .register_taskset(taskset!("ExampleTasks")
+    ///             .register_task(task!(example_task))
+    ///         );
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let product = user.get_feeder_row("products").await?;
+    ///     let _goose = user.get(&format!("/product/{}", product["id"])).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn register_feeder(mut self, name: &str, feeder: GooseFeeder) -> Self {
+        self.feeders.insert(name.to_string(), feeder);
+        self
+    }
+
+    /// Add a rule to normalize request paths before they're used to group statistics,
+    /// so requests of dynamic paths such as `/product/12345` can be grouped together
+    /// without naming each request. All matches of the regular expression are replaced
+    /// with the replacement, which can refer to capture groups such as `$1`. Rules are
+    /// applied in the order they're added.
+    ///
+    /// Rules only apply to requests that aren't explicitly named, either with a named
+    /// task or a named request. Rules can be disabled for individual tasks with
+    /// [`set_stats_normalization(false)`](./goose/struct.GooseTask.html#method.set_stats_normalization).
+    /// Every rule is evaluated against every request as it is recorded, which adds some
+    /// overhead to the parent thread, so keep the number of rules small.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         // Group /product/12345 and /product/67890 under /product/:id.
+    ///         .add_stats_normalization(r"/\d+", "/:id")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_stats_normalization(
+        mut self,
+        regex: &str,
+        replacement: &str,
+    ) -> Result<Self, GooseError> {
+        let regex = Regex::new(regex).map_err(|e| GooseError::InvalidOption {
+            option: "add_stats_normalization".to_string(),
+            value: regex.to_string(),
+            detail: Some(e.to_string()),
+        })?;
+        self.stats_normalizations
+            .push((regex, replacement.to_string()));
+        Ok(self)
+    }
+
+    /// Use a custom Reqwest client builder for all users, for full control over how
+    /// each user's client is configured, for example to add client certificates, custom
+    /// root certificates or default headers. The function is called once for each user,
+    /// so each user still gets its own client and cookie jar.
+    ///
+    /// The custom builder replaces Goose's default client settings, including the
+    /// default user agent and cookie store, and the `--http2`, `--pool-max-idle`,
+    /// `--tcp-keepalive`, `--proxy` and `--accept-invalid-certs` options are ignored.
+    /// The `--timeout` and `--max-redirects` options are still applied.
+    /// Enable `cookie_store(true)` on the builder if users need to maintain a session.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .set_client_builder(|| {
+    ///             reqwest::Client::builder()
+    ///                 .user_agent("custom user agent")
+    ///                 .cookie_store(true)
+    ///         });
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_client_builder<F>(mut self, client_builder: F) -> Self
+    where
+        F: Fn() -> reqwest::ClientBuilder + Send + Sync + 'static,
+    {
+        self.client_builder = Some(Arc::new(client_builder));
+        self
+    }
+
+    /// Customize how requests are written to the `--log-requests` file. The function is
+    /// called with each logged request, and returns the line to write, or `None` to skip
+    /// the request. By default each request is written as a line of JSON.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .set_request_logger(|request| {
+    ///             // Only log failed requests.
+    ///             if request.success {
+    ///                 None
+    ///             } else {
+    ///                 Some(format!("{} {:?} {}", request.status_code, request.method, request.url))
+    ///             }
+    ///         });
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_request_logger<F>(mut self, request_logger: F) -> Self
+    where
+        F: Fn(&GooseRequestLog) -> Option<String> + Send + Sync + 'static,
+    {
+        self.request_logger = Some(Arc::new(request_logger));
+        self
+    }
+
+    /// Call a function with every request as it's recorded, for example for custom
+    /// aggregation, alerting, or forwarding results to another system. The function
+    /// receives each `GooseRawRequest` sent by the users, including failed attempts that
+    /// were retried, and updates made with `set_success()` or `set_failure()`.
+    ///
+    /// The function is called by the same thread that collects statistics, so it must
+    /// return quickly: a slow function delays processing of all statistics, and the
+    /// requests waiting to be processed queue up in memory. To do slow work, such as
+    /// making network calls, send the request to another thread over a channel instead.
+    /// Requests are only sent to the parent when statistics are enabled, so the function
+    /// is never called with `--no-stats`. In a Gaggle, it's called on each Worker.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///     use std::sync::atomic::{AtomicUsize, Ordering};
+    ///     use std::sync::Arc;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     let server_errors = Arc::new(AtomicUsize::new(0));
+    ///     let counter = server_errors.clone();
+    ///     GooseAttack::initialize()?
+    ///         .set_request_callback(move |request| {
+    ///             // Count server errors as they happen.
+    ///             if request.status_code >= 500 && !request.update {
+    ///                 counter.fetch_add(1, Ordering::SeqCst);
+    ///             }
+    ///         });
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn register_taskset(mut self, mut taskset: GooseTaskSet) -> Self {
-        taskset.task_sets_index = self.task_sets.len();
-        self.task_sets.push(taskset);
+    pub fn set_request_callback<F>(mut self, request_callback: F) -> Self
+    where
+        F: Fn(&GooseRawRequest) + Send + Sync + 'static,
+    {
+        self.request_callback = Some(Arc::new(request_callback));
         self
     }
 
+    /// The prefix of the name of a request, configured for the task set of the user that
+    /// made it with `GooseTaskSet.set_name_prefix()`.
+    fn name_prefix(&self, raw_request: &GooseRawRequest) -> String {
+        match self.weighted_users.get(raw_request.user) {
+            Some(user) => self.task_sets[user.task_sets_index].name_prefix.clone(),
+            None => String::new(),
+        }
+    }
+
+    /// Apply all stats normalization rules to a request name.
+    fn normalize_request_name(&self, name: &str) -> String {
+        let mut name = name.to_string();
+        for (regex, replacement) in &self.stats_normalizations {
+            name = regex.replace_all(&name, replacement.as_str()).to_string();
+        }
+        name
+    }
+
     /// Optionally define a task to run before users are started and all task sets
     /// start running. This is would generally be used to set up anything required
     /// for the load test.
@@ -833,6 +2059,66 @@ impl GooseAttack {
         self
     }
 
+    /// Optionally define a task to run once in each Worker process of a distributed
+    /// Gaggle, after the Worker receives its users from the Manager and before it starts
+    /// them. This would generally be used for per-node setup, such as warming a local
+    /// cache or registering the Worker with a service.
+    ///
+    /// Unlike `test_start`, which only runs one time on the Manager, this task runs on
+    /// every Worker. It doesn't run at all when the load test isn't running in a Gaggle,
+    /// so use `test_start` for setup that's always required.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .worker_start(task!(register_worker));
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn register_worker(user: &GooseUser) -> GooseTaskResult {
+    ///     // do stuff to set up this worker ...
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn worker_start(mut self, task: GooseTask) -> Self {
+        self.worker_start_task = Some(task);
+        self
+    }
+
+    /// Optionally define a task to run once in each Worker process of a distributed
+    /// Gaggle, after all of the Worker's users have stopped. This would generally be used
+    /// to clean up anything set up by `worker_start`.
+    ///
+    /// Unlike `test_stop`, which only runs one time on the Manager, this task runs on
+    /// every Worker. It doesn't run at all when the load test isn't running in a Gaggle.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .worker_stop(task!(unregister_worker));
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn unregister_worker(user: &GooseUser) -> GooseTaskResult {
+    ///     // do stuff to clean up this worker ...
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn worker_stop(mut self, task: GooseTask) -> Self {
+        self.worker_stop_task = Some(task);
+        self
+    }
+
     /// Optionally configure a default host for the load test. This is used if
     /// no per-GooseTaskSet host is defined, no `--host` CLI option is configurared,
     /// and if the GooseTask itself doesn't hard-code the host in its request. The
@@ -860,28 +2146,210 @@ impl GooseAttack {
         self
     }
 
-    /// Allocate a vector of weighted GooseUser.
-    fn weight_task_set_users(&mut self) -> Result<Vec<GooseUser>, GooseError> {
-        trace!("weight_task_set_users");
+    /// Optionally configure a weighted pool of default hosts for the load test, for
+    /// example to load test several backend nodes directly instead of through their load
+    /// balancer. Users are distributed across the hosts by weight, so a host with a
+    /// weight of 2 is assigned twice as many users as a host with a weight of 1. By
+    /// default each user sends all its requests to the same host, see
+    /// [`set_host_rotation`](#method.set_host_rotation) to instead rotate through the
+    /// hosts with each request.
+    ///
+    /// The pool replaces any host configured with `set_host()`, and like it is only used
+    /// if no per-GooseTaskSet host and no `--host` CLI option are configured. Every host
+    /// is validated when the load test starts.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .set_hosts(&[("http://node1.local.dev", 2), ("http://node2.local.dev", 1)]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_hosts(mut self, hosts: &[(&str, usize)]) -> Self {
+        trace!("set_hosts: {:?}", hosts);
+        // Host validation happens in main() at startup.
+        self.hosts = hosts
+            .iter()
+            .map(|(host, weight)| (host.to_string(), *weight))
+            .collect();
+        self
+    }
+
+    /// Rotate through the hosts configured with
+    /// [`set_hosts`](#method.set_hosts) with each request, instead of each user sending
+    /// all of its requests to a single host. Hosts are still used in proportion to their
+    /// weight. Disabled by default.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .set_hosts(&[("http://node1.local.dev", 1), ("http://node2.local.dev", 1)])
+    ///         .set_host_rotation(true);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_host_rotation(mut self, enabled: bool) -> Self {
+        trace!("set_host_rotation: {}", enabled);
+        self.rotate_hosts = enabled;
+        self
+    }
+
+    /// Optionally describe the population of users as the percentage of users that run
+    /// each task set, for example 70% browsing, 20% searching and 10% buying. The
+    /// percentages replace the weights of the task sets, and must add up to 100. Task sets
+    /// that aren't part of the population aren't allocated any users.
+    ///
+    /// Users are allocated as with weights, so the population is exact when the number of
+    /// users is a multiple of 100 divided by the greatest common divisor of the
+    /// percentages, for example 10 users for a population of 70%, 20% and 10%. The
+    /// population is validated when the load test starts.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("Browser").register_task(task!(browse)))
+    ///         .register_taskset(taskset!("Searcher").register_task(task!(search)))
+    ///         .register_taskset(taskset!("Purchaser").register_task(task!(purchase)))
+    ///         .set_population(&[("Browser", 70), ("Searcher", 20), ("Purchaser", 10)])
+    ///         .execute()?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn browse(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn search(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/search?q=goose").await?;
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn purchase(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.post("/cart", "product=1").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_population(mut self, population: &[(&str, usize)]) -> Self {
+        trace!("set_population: {:?}", population);
+        // Population validation happens in execute() at startup.
+        self.population = population
+            .iter()
+            .map(|(name, percent)| (name.to_string(), *percent))
+            .collect();
+        self
+    }
+
+    /// Validate the population configured with `set_population()`, and replace the
+    /// weight of each task set with its percentage of the population.
+    fn apply_population(&mut self) -> Result<(), GooseError> {
+        if self.population.is_empty() {
+            return Ok(());
+        }
+
+        let mut total = 0;
+        for (index, (name, percent)) in self.population.iter().enumerate() {
+            if *percent == 0 {
+                return Err(GooseError::InvalidWeight {
+                    weight: *percent,
+                    detail: Some(format!(
+                        "population of 0% not allowed for task set {}",
+                        name
+                    )),
+                });
+            }
+            if !self.task_sets.iter().any(|task_set| &task_set.name == name) {
+                return Err(GooseError::InvalidOption {
+                    option: "GooseAttack.set_population()".to_string(),
+                    value: name.to_string(),
+                    detail: Some(format!("no task set named {} is registered", name)),
+                });
+            }
+            if self.population[..index].iter().any(|(n, _)| n == name) {
+                return Err(GooseError::InvalidOption {
+                    option: "GooseAttack.set_population()".to_string(),
+                    value: name.to_string(),
+                    detail: Some(format!("task set {} is in the population twice", name)),
+                });
+            }
+            total += percent;
+        }
+        if total != 100 {
+            return Err(GooseError::InvalidWeight {
+                weight: total,
+                detail: Some(format!(
+                    "population percentages must add up to 100, not {}",
+                    total
+                )),
+            });
+        }
 
+        for task_set in &mut self.task_sets {
+            task_set.weight = match self.population.iter().find(|(n, _)| n == &task_set.name) {
+                Some((_, percent)) => *percent,
+                None => {
+                    info!(
+                        "{} is not part of the population, no users run it",
+                        task_set.name
+                    );
+                    0
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// Build a weighted list of the hosts configured with `set_hosts()`, reduced by the
+    /// greatest common divisor of their weights so the list is as short as possible.
+    fn weighted_hosts(&self) -> Vec<String> {
         let mut u: usize = 0;
-        let mut v: usize;
-        for task_set in &self.task_sets {
+        for (_, weight) in &self.hosts {
             if u == 0 {
-                u = task_set.weight;
+                u = *weight;
             } else {
-                v = task_set.weight;
-                trace!("calculating greatest common denominator of {} and {}", u, v);
-                u = util::gcd(u, v);
-                trace!("inner gcd: {}", u);
+                u = util::gcd(u, *weight);
             }
         }
-        // 'u' will always be the greatest common divisor
-        debug!("gcd: {}", u);
+        let mut weighted_hosts = Vec::new();
+        for (host, weight) in &self.hosts {
+            weighted_hosts.append(&mut vec![host.to_string(); weight / u]);
+        }
+        weighted_hosts
+    }
+
+    /// The default host of the load test, used when a task set doesn't define a host.
+    /// If a pool of hosts is configured, returns the first one.
+    fn get_default_host(&self) -> Option<String> {
+        match self.hosts.first() {
+            Some((host, _)) => Some(host.to_string()),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Allocate a vector of weighted GooseUser.
+    fn weight_task_set_users(&mut self) -> Result<Vec<GooseUser>, GooseError> {
+        trace!("weight_task_set_users");
+
+        let u = self.task_set_weights_gcd();
 
         // Build a weighted lists of task sets (identified by index)
         let mut weighted_task_sets = Vec::new();
         for (index, task_set) in self.task_sets.iter().enumerate() {
+            if task_set.weight == 0 || self.task_set_skipped(task_set) {
+                continue;
+            }
             // divide by greatest common divisor so vector is as short as possible
             let weight = task_set.weight / u;
             trace!(
@@ -895,32 +2363,152 @@ impl GooseAttack {
             weighted_task_sets.append(&mut weighted_sets);
         }
 
+        // Users are distributed across the optional pool of hosts by weight.
+        let weighted_hosts = self.weighted_hosts();
+        let mut rotate_hosts = Vec::new();
+        if self.rotate_hosts {
+            for host in &weighted_hosts {
+                rotate_hosts.push(goose::get_base_url(None, None, Some(host.to_string()))?);
+            }
+        }
+
         // Allocate a state for each user that will be spawned.
         info!("initializing user states...");
         let mut weighted_users = Vec::new();
         let mut user_count = 0;
-        loop {
+        // Track how many users are assigned each task set, to enforce optional maximums.
+        let mut task_set_users = vec![0; self.task_sets.len()];
+        'allocate: loop {
+            let mut allocated_user = false;
             for task_sets_index in &weighted_task_sets {
-                let base_url = goose::get_base_url(
-                    self.get_configuration_host(),
-                    self.task_sets[*task_sets_index].host.clone(),
-                    self.host.clone(),
-                )?;
-                weighted_users.push(GooseUser::new(
+                // Skip task sets that already have their maximum number of users, which
+                // redistributes their slots to the other task sets.
+                if let Some(max_users) = self.task_sets[*task_sets_index].max_users {
+                    if task_set_users[*task_sets_index] >= max_users {
+                        continue;
+                    }
+                }
+                let default_host = if weighted_hosts.is_empty() {
+                    self.host.clone()
+                } else {
+                    Some(weighted_hosts[user_count % weighted_hosts.len()].to_string())
+                };
+                let config_host = self.get_configuration_host(user_count);
+                let task_set_host = self.task_sets[*task_sets_index].host.clone();
+                // The pool of hosts is only used if no other host is configured.
+                let uses_hosts = config_host.is_none() && task_set_host.is_none();
+                let base_url = goose::get_base_url(config_host, task_set_host, default_host)?;
+                let mut user = GooseUser::new_with_client_builder(
                     self.task_sets[*task_sets_index].task_sets_index,
                     base_url,
                     self.task_sets[*task_sets_index].min_wait,
                     self.task_sets[*task_sets_index].max_wait,
                     &self.configuration,
                     self.stats.hash,
-                )?);
+                    self.client_builder.as_ref(),
+                )?;
+                if uses_hosts && !rotate_hosts.is_empty() {
+                    user.rotate_hosts = rotate_hosts.clone();
+                    // Users start rotating from the host they'd otherwise be assigned.
+                    user.rotate_hosts_position = Arc::new(AtomicUsize::new(user_count));
+                }
+                weighted_users.push(user);
+                task_set_users[*task_sets_index] += 1;
+                allocated_user = true;
                 user_count += 1;
                 if user_count >= self.users {
                     trace!("created {} weighted_users", user_count);
-                    return Ok(weighted_users);
+                    break 'allocate;
                 }
             }
+            // All task sets have their maximum number of users.
+            if !allocated_user {
+                warn!(
+                    "all task sets have their maximum number of users, only launching {} of {} users",
+                    user_count, self.users
+                );
+                break;
+            }
+        }
+
+        for task_set in &self.task_sets {
+            info!(
+                "{}: {}",
+                task_set.name,
+                self.task_set_weighting(task_set, &weighted_users)
+            );
+        }
+        Ok(weighted_users)
+    }
+
+    /// The greatest common divisor of all task set weights, which weights are reduced by
+    /// so the weighted list of task sets is as short as possible.
+    fn task_set_weights_gcd(&self) -> usize {
+        let mut u: usize = 0;
+        let mut v: usize;
+        // Task sets that aren't part of the population have a weight of 0.
+        for task_set in self
+            .task_sets
+            .iter()
+            .filter(|t| t.weight > 0 && !self.task_set_skipped(t))
+        {
+            if u == 0 {
+                u = task_set.weight;
+            } else {
+                v = task_set.weight;
+                trace!("calculating greatest common denominator of {} and {}", u, v);
+                u = util::gcd(u, v);
+                trace!("inner gcd: {}", u);
+            }
+        }
+        // 'u' will always be the greatest common divisor
+        debug!("gcd: {}", u);
+        u
+    }
+
+    /// Describe how users were allocated to a task set: its configured weight, its weight
+    /// reduced by the greatest common divisor, the share of users its weight would give it,
+    /// and the users actually allocated. Users are allocated by walking the reduced weights
+    /// in order, so when the number of users isn't a multiple of the sum of reduced weights,
+    /// task sets registered first get more users than their share.
+    fn task_set_weighting(&self, task_set: &GooseTaskSet, weighted_users: &[GooseUser]) -> String {
+        if self.task_set_skipped(task_set) {
+            return format!("weight: {}, skipped by tags", task_set.weight);
         }
+        if task_set.weight == 0 {
+            return "weight: 0, not part of the population".to_string();
+        }
+        let gcd = self.task_set_weights_gcd();
+        let total_weight: usize = self
+            .task_sets
+            .iter()
+            .filter(|t| !self.task_set_skipped(t))
+            .map(|t| t.weight)
+            .sum();
+        let users = weighted_users
+            .iter()
+            .filter(|user| user.task_sets_index == task_set.task_sets_index)
+            .count();
+        format!(
+            "weight: {}, reduced: {}, expected users: {:.1}, allocated users: {}",
+            task_set.weight,
+            task_set.weight / gcd,
+            (self.users * task_set.weight) as f64 / total_weight as f64,
+            users
+        )
+    }
+
+    /// Task sets with tasks, none of which are selected by `--tags` and `--exclude-tags`,
+    /// are skipped and not allocated any users.
+    fn task_set_skipped(&self, task_set: &GooseTaskSet) -> bool {
+        (!self.configuration.tags.is_empty() || !self.configuration.exclude_tags.is_empty())
+            && !task_set.tasks.is_empty()
+            && task_set
+                .weighted_on_start_tasks
+                .iter()
+                .chain(&task_set.weighted_tasks)
+                .chain(&task_set.weighted_on_stop_tasks)
+                .all(|tasks| tasks.is_empty())
     }
 
     /// Execute the load test.
@@ -960,18 +2548,61 @@ impl GooseAttack {
             });
         }
 
+        // Replace task set weights with the optional population.
+        self.apply_population()?;
+
         if self.configuration.list {
             // Display task sets and tasks, then exit.
             println!("Available tasks:");
             for task_set in self.task_sets {
                 println!(" - {} (weight: {})", task_set.name, task_set.weight);
                 for task in task_set.tasks {
-                    println!("    o {} (weight: {})", task.name, task.weight);
+                    if task.tags.is_empty() {
+                        println!("    o {} (weight: {})", task.name, task.weight);
+                    } else {
+                        println!(
+                            "    o {} (weight: {}, tags: {})",
+                            task.name,
+                            task.weight,
+                            task.tags.join(", ")
+                        );
+                    }
                 }
             }
             std::process::exit(0);
         }
 
+        if self.configuration.list_json {
+            // Display task sets and tasks as JSON, then exit.
+            serde_json::to_writer_pretty(io::stdout(), &self.task_sets).map_err(io::Error::from)?;
+            println!();
+            std::process::exit(0);
+        }
+
+        // Users only send requests to the parent when statistics are enabled.
+        if self.request_callback.is_some() && self.configuration.no_stats {
+            warn!("the request callback is never called when --no-stats is enabled");
+        }
+
+        // Client tuning options are ignored when a custom client builder is used.
+        if self.client_builder.is_some() {
+            if self.configuration.http2 {
+                warn!("--http2 is ignored when a custom client builder is set");
+            }
+            if self.configuration.pool_max_idle.is_some() {
+                warn!("--pool-max-idle is ignored when a custom client builder is set");
+            }
+            if !self.configuration.tcp_keepalive.is_empty() {
+                warn!("--tcp-keepalive is ignored when a custom client builder is set");
+            }
+            if !self.configuration.proxy.is_empty() {
+                warn!("--proxy is ignored when a custom client builder is set");
+            }
+            if self.configuration.accept_invalid_certs {
+                warn!("--accept-invalid-certs is ignored when a custom client builder is set");
+            }
+        }
+
         // Manager mode.
         if self.configuration.manager {
             // @TODO: support running in both manager and worker mode.
@@ -1009,6 +2640,28 @@ impl GooseAttack {
                 });
             }
 
+            if !self.configuration.pause_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--pause-file".to_string(),
+                    value: self.configuration.pause_file,
+                    detail: Some(
+                        "--pause-file can only be enabled in stand-alone or worker mode"
+                            .to_string(),
+                    ),
+                });
+            }
+
+            if !self.configuration.log_requests.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--log-requests".to_string(),
+                    value: self.configuration.log_requests,
+                    detail: Some(
+                        "--log-requests can only be enabled in stand-alone or worker mode"
+                            .to_string(),
+                    ),
+                });
+            }
+
             if self.configuration.throttle_requests.is_some() {
                 return Err(GooseError::InvalidOption {
                     option: "--throttle-requests".to_string(),
@@ -1065,7 +2718,7 @@ impl GooseAttack {
             if !self.configuration.host.is_empty() {
                 return Err(GooseError::InvalidOption {
                     option: "--host".to_string(),
-                    value: self.configuration.host,
+                    value: self.configuration.host.join(","),
                     detail: Some("--host is only available to the manager".to_string()),
                 });
             }
@@ -1107,24 +2760,79 @@ impl GooseAttack {
                 });
             }
 
-            if self.configuration.status_codes {
+            if self.configuration.status_codes {
+                return Err(GooseError::InvalidOption {
+                    option: "--status-codes".to_string(),
+                    value: self.configuration.status_codes.to_string(),
+                    detail: Some("--status-codes is only available to the manager".to_string()),
+                });
+            }
+
+            if self.configuration.running_percentiles {
+                return Err(GooseError::InvalidOption {
+                    option: "--running-percentiles".to_string(),
+                    value: self.configuration.running_percentiles.to_string(),
+                    detail: Some(
+                        "--running-percentiles is only available to the manager".to_string(),
+                    ),
+                });
+            }
+
+            if self.configuration.no_hash_check {
+                return Err(GooseError::InvalidOption {
+                    option: "--no-hash-check".to_string(),
+                    value: self.configuration.no_hash_check.to_string(),
+                    detail: Some("--no-hash-check is only available to the manager".to_string()),
+                });
+            }
+
+            if !self.configuration.report_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--report-file".to_string(),
+                    value: self.configuration.report_file,
+                    detail: Some("--report-file is only available to the manager".to_string()),
+                });
+            }
+
+            if !self.configuration.stats_summary_file.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--stats-summary-file".to_string(),
+                    value: self.configuration.stats_summary_file,
+                    detail: Some(
+                        "--stats-summary-file is only available to the manager".to_string(),
+                    ),
+                });
+            }
+
+            if !self.configuration.fail_if.is_empty() {
                 return Err(GooseError::InvalidOption {
-                    option: "--status-codes".to_string(),
-                    value: self.configuration.status_codes.to_string(),
-                    detail: Some("--status-codes is only available to the manager".to_string()),
+                    option: "--fail-if".to_string(),
+                    value: self.configuration.fail_if.join(", "),
+                    detail: Some("--fail-if is only available to the manager".to_string()),
                 });
             }
 
-            if self.configuration.no_hash_check {
+            if self.configuration.dry_run {
                 return Err(GooseError::InvalidOption {
-                    option: "--no-hash-check".to_string(),
-                    value: self.configuration.no_hash_check.to_string(),
-                    detail: Some("--no-hash-check is only available to the manager".to_string()),
+                    option: "--dry-run".to_string(),
+                    value: self.configuration.dry_run.to_string(),
+                    detail: Some("--dry-run is only available to the manager".to_string()),
                 });
             }
         }
 
         if !self.configuration.manager && !self.configuration.worker {
+            if !self.configuration.gaggle_secret.is_empty() {
+                return Err(GooseError::InvalidOption {
+                    option: "--gaggle-secret".to_string(),
+                    value: self.configuration.gaggle_secret,
+                    detail: Some(
+                        "--gaggle-secret is only available when running in manager or worker mode"
+                            .to_string(),
+                    ),
+                });
+            }
+
             if self.configuration.no_hash_check {
                 return Err(GooseError::InvalidOption {
                     option: "--no-hash-check".to_string(),
@@ -1167,18 +2875,37 @@ impl GooseAttack {
         }
         debug!("hatch_rate = {}", self.configuration.hatch_rate);
 
+        // Validate the optional pool of hosts.
+        for (host, weight) in &self.hosts {
+            is_valid_host(host)?;
+            if *weight == 0 {
+                return Err(GooseError::InvalidWeight {
+                    weight: *weight,
+                    detail: Some(format!("weight of 0 not allowed for host {}", host)),
+                });
+            }
+        }
+
         // Confirm there's either a global host, or each task set has a host defined.
         if self.configuration.host.is_empty() {
             for task_set in &self.task_sets {
                 match &task_set.host {
                     Some(h) => {
-                        if is_valid_host(h).is_ok() {
-                            info!("host for {} configured: {}", task_set.name, h);
-                        }
+                        is_valid_host(h)?;
+                        info!("host for {} configured: {}", task_set.name, h);
                     }
-                    None => match &self.host {
+                    None => match &self.get_default_host() {
                         Some(h) => {
-                            if is_valid_host(h).is_ok() {
+                            if !self.hosts.is_empty() {
+                                let hosts: Vec<&str> =
+                                    self.hosts.iter().map(|(host, _)| host.as_str()).collect();
+                                info!(
+                                    "hosts for {} configured: {}",
+                                    task_set.name,
+                                    hosts.join(", ")
+                                );
+                            } else {
+                                is_valid_host(h)?;
                                 info!("host for {} configured: {}", task_set.name, h);
                             }
                         }
@@ -1194,14 +2921,22 @@ impl GooseAttack {
                     },
                 }
             }
-        } else if is_valid_host(&self.configuration.host).is_ok() {
-            info!("global host configured: {}", self.configuration.host);
+        } else {
+            for host in &self.configuration.host {
+                is_valid_host(host)?;
+            }
+            info!(
+                "global host configured: {}",
+                self.configuration.host.join(", ")
+            );
         }
 
-        // Apply weights to tasks in each task set.
+        // Apply weights to tasks in each task set, skipping tasks not selected by tags.
+        let tags = parse_tags(&self.configuration.tags);
+        let exclude_tags = parse_tags(&self.configuration.exclude_tags);
         for task_set in &mut self.task_sets {
             let (weighted_on_start_tasks, weighted_tasks, weighted_on_stop_tasks) =
-                weight_tasks(&task_set);
+                weight_tasks(&task_set, &tags, &exclude_tags);
             task_set.weighted_on_start_tasks = weighted_on_start_tasks;
             task_set.weighted_tasks = weighted_tasks;
             task_set.weighted_on_stop_tasks = weighted_on_stop_tasks;
@@ -1213,6 +2948,32 @@ impl GooseAttack {
                 task_set.weighted_on_stop_tasks
             );
         }
+        if !tags.is_empty() || !exclude_tags.is_empty() {
+            for task_set in &self.task_sets {
+                if self.task_set_skipped(task_set) {
+                    warn!(
+                        "skipping {}, none of its tasks are selected by --tags or --exclude-tags",
+                        task_set.name
+                    );
+                }
+            }
+            if self
+                .task_sets
+                .iter()
+                .all(|task_set| self.task_set_skipped(task_set))
+            {
+                let (option, value) = if tags.is_empty() {
+                    ("--exclude-tags", self.configuration.exclude_tags)
+                } else {
+                    ("--tags", self.configuration.tags)
+                };
+                return Err(GooseError::InvalidOption {
+                    option: option.to_string(),
+                    value,
+                    detail: Some("no tasks are selected by --tags and --exclude-tags.".to_string()),
+                });
+            }
+        }
 
         // Allocate a state for each of the users we are about to start.
         if !self.configuration.worker {
@@ -1220,13 +2981,24 @@ impl GooseAttack {
         }
 
         // Calculate a unique hash for the current load test.
-        let mut s = DefaultHasher::new();
-        self.task_sets.hash(&mut s);
-        self.stats.hash = s.finish();
+        self.stats.hash = self.load_test_hash();
         debug!("hash: {}", self.stats.hash);
 
+        if self.configuration.print_hash {
+            // Display the hash so workers can be compared with the manager, then exit.
+            println!("{}", self.stats.hash);
+            std::process::exit(0);
+        }
+
+        if self.configuration.dry_run {
+            // Display how users and tasks are allocated, then exit.
+            print!("{}", self.dry_run_plan()?);
+            std::process::exit(0);
+        }
+
         // Our load test is officially starting.
         self.started = Some(time::Instant::now());
+        self.load_test_started = self.started;
         // Spawn users at hatch_rate per second, or one every 1 / hatch_rate fraction of a second.
         let sleep_float = 1.0 / self.configuration.hatch_rate as f32;
         let sleep_duration = time::Duration::from_secs_f32(sleep_float);
@@ -1269,53 +3041,538 @@ impl GooseAttack {
             self = rt.block_on(self.launch_users(sleep_duration, None))?;
         }
 
+        // Write a report of the load test statistics, if enabled.
+        if !self.configuration.report_file.is_empty() {
+            let report_file = Path::new(&self.configuration.report_file);
+            // Write a markdown report if the file name ends in .md, otherwise HTML.
+            match report_file.extension() {
+                Some(extension) if extension == "md" => {
+                    self.stats.write_markdown_report(report_file)?
+                }
+                _ => self.stats.write_html_report(report_file)?,
+            }
+        }
+
+        // Write a JSON summary of the load test statistics, if enabled.
+        if !self.configuration.stats_summary_file.is_empty() {
+            self.stats
+                .write_json_summary(Path::new(&self.configuration.stats_summary_file))?;
+        }
+
+        // Check thresholds before failing for any other reason, so they're all logged.
+        let failures: Vec<String> = self
+            .thresholds
+            .iter()
+            .filter_map(|threshold| threshold.check(&self.stats))
+            .collect();
+        for failure in &failures {
+            error!("threshold failed: {}", failure);
+        }
+
+        // The statistics aren't returned with an error, so display them before failing to
+        // explain why the load test failed.
+        if !self.configuration.no_stats
+            && (self.failed_request.is_some()
+                || self.stats_log_failed.is_some()
+                || self.hatch_aborted.is_some()
+                || !failures.is_empty())
+        {
+            self.stats.print();
+        }
+
+        // Fail the load test if it was stopped by a failed request.
+        if let Some(request) = self.failed_request {
+            return Err(GooseError::RequestFailed {
+                request: Box::new(request),
+            });
+        }
+
+        // Fail the load test if the required statistics log couldn't be written.
+        if let Some((kind, detail)) = self.stats_log_failed {
+            return Err(GooseError::Io(io::Error::new(kind, detail)));
+        }
+
+        // Fail the load test if launching users was aborted.
+        if let Some(detail) = self.hatch_aborted {
+            return Err(GooseError::HatchAborted { detail });
+        }
+
+        // Fail the load test if any thresholds were exceeded.
+        if !failures.is_empty() {
+            return Err(GooseError::ThresholdsFailed { failures });
+        }
+
         Ok(self.stats)
     }
 
+    /// Calculate a hash of the task sets and how their tasks are weighted, which Gaggle
+    /// workers must share with the manager, displayed by `--print-hash`.
+    fn load_test_hash(&self) -> u64 {
+        let mut s = DefaultHasher::new();
+        self.task_sets.hash(&mut s);
+        s.finish()
+    }
+
+    /// Describe how users and tasks are allocated, displayed by `--dry-run`.
+    fn dry_run_plan(&self) -> Result<String, GooseError> {
+        let mut plan = format!(
+            "Load test plan: {} users, launching {} per second",
+            self.weighted_users.len(),
+            self.configuration.hatch_rate
+        );
+        if self.run_time > 0 {
+            plan.push_str(&format!(", stopping after {} seconds", self.run_time));
+        } else if self.configuration.iterations > 0 {
+            plan.push_str(&format!(
+                ", stopping after {} iterations",
+                self.configuration.iterations
+            ));
+        }
+        plan.push('\n');
+
+        for task_set in &self.task_sets {
+            let host = if self.configuration.host.is_empty()
+                && task_set.host.is_none()
+                && !self.hosts.is_empty()
+            {
+                let hosts: Vec<String> = self
+                    .hosts
+                    .iter()
+                    .map(|(host, weight)| format!("{} (weight: {})", host, weight))
+                    .collect();
+                hosts.join(", ")
+            } else if self.configuration.host.len() > 1 {
+                self.configuration.host.join(", ")
+            } else {
+                goose::get_base_url(
+                    self.get_configuration_host(0),
+                    task_set.host.clone(),
+                    self.host.clone(),
+                )?
+                .to_string()
+            };
+            plan.push_str(&format!(
+                " - {} ({}), host: {}\n",
+                task_set.name,
+                self.task_set_weighting(task_set, &self.weighted_users),
+                host
+            ));
+            let weighted = [
+                ("on_start", &task_set.weighted_on_start_tasks),
+                ("tasks", &task_set.weighted_tasks),
+                ("on_stop", &task_set.weighted_on_stop_tasks),
+            ];
+            for (label, weighted_tasks) in &weighted {
+                // Unnamed tasks are identified by their index.
+                let names: Vec<String> = weighted_tasks
+                    .iter()
+                    .flatten()
+                    .map(|index| match task_set.tasks[*index].name.as_str() {
+                        "" => format!("task {}", index),
+                        name => name.to_string(),
+                    })
+                    .collect();
+                if !names.is_empty() {
+                    plan.push_str(&format!("    o {}: {}\n", label, names.join(", ")));
+                }
+            }
+        }
+        Ok(plan)
+    }
+
     /// Helper to wrap configured host in Option<> if set.
-    fn get_configuration_host(&self) -> Option<String> {
+    fn get_configuration_host(&self, user: usize) -> Option<String> {
         if self.configuration.host.is_empty() {
             None
         } else {
-            Some(self.configuration.host.to_string())
-        }
-    }
-
-    /// Helper to create CSV-formatted logs.
-    fn prepare_csv(raw_request: &GooseRawRequest, header: &mut bool) -> String {
-        let body = format!(
-            // Put quotes around name, url and final_url as they are strings.
-            "{},{:?},\"{}\",\"{}\",\"{}\",{},{},{},{},{},{}",
-            raw_request.elapsed,
-            raw_request.method,
-            raw_request.name,
-            raw_request.url,
-            raw_request.final_url,
-            raw_request.redirected,
-            raw_request.response_time,
-            raw_request.status_code,
-            raw_request.success,
-            raw_request.update,
-            raw_request.user
-        );
+            let hosts = &self.configuration.host;
+            Some(hosts[user % hosts.len()].to_string())
+        }
+    }
+
+    /// Describes why launching users should stop, if more than `--hatch-error-rate` percent
+    /// of the requests made so far failed.
+    fn hatch_error(&self, hatch_error_rate: f32) -> Option<String> {
+        let (requests, fails) =
+            self.stats
+                .requests
+                .values()
+                .fold((0, 0), |(requests, fails), request| {
+                    (
+                        requests + request.success_count + request.fail_count,
+                        fails + request.fail_count,
+                    )
+                });
+        if requests < HATCH_ERROR_MIN_REQUESTS {
+            return None;
+        }
+        let error_rate = fails as f32 / requests as f32 * 100.0;
+        if error_rate > hatch_error_rate {
+            Some(format!(
+                "{:.1}% of {} requests failed after launching {} of {} users, more than --hatch-error-rate {}%",
+                error_rate, requests, self.stats.users, self.users, hatch_error_rate
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Pause users when the `--pause-file` is created, and resume them when it's removed,
+    /// logging how long they were paused.
+    fn update_paused(&mut self, paused: &AtomicBool, pause_started: &mut Option<time::Instant>) {
+        if self.configuration.pause_file.is_empty() {
+            return;
+        }
+        let pause = Path::new(&self.configuration.pause_file).exists();
+        let elapsed = self.load_test_elapsed();
+        if pause == paused.load(Ordering::SeqCst) {
+            // Mark each throughput bucket users are paused during.
+            if pause {
+                self.stats.throughput_bucket(elapsed).paused = true;
+            }
+            return;
+        }
+
+        if pause {
+            info!(
+                "pausing users after {} seconds, remove {} to resume...",
+                elapsed, self.configuration.pause_file
+            );
+            *pause_started = Some(time::Instant::now());
+            self.stats.pauses.push(GoosePause {
+                started: elapsed,
+                resumed: None,
+            });
+        } else {
+            let pause_time = pause_started.take().map_or(0, |p| p.elapsed().as_secs());
+            info!(
+                "resuming users after {} seconds, paused for {} seconds...",
+                elapsed, pause_time
+            );
+            if let Some(pause) = self.stats.pauses.last_mut() {
+                pause.resumed = Some(elapsed);
+            }
+        }
+        // Users were paused for at least part of the current bucket.
+        self.stats.throughput_bucket(elapsed).paused = true;
+        paused.store(pause, Ordering::SeqCst);
+    }
+
+    /// Load requests from user threads until the receiver queue is empty, logging them
+    /// if enabled. Returns true if any requests were received.
+    async fn receive_requests(
+        &mut self,
+        parent_receiver: &mut mpsc::UnboundedReceiver<GooseRawRequest>,
+        stats_log_file: &mut Option<BufWriter<File>>,
+        header: &mut bool,
+    ) -> bool {
+        let mut received_message = false;
+        let mut message = parent_receiver.try_recv();
+        while message.is_ok() {
+            received_message = true;
+            let mut raw_request = message.unwrap();
+            // Custom metrics aren't requests, and the bytes of a streamed response body
+            // belong to a request that was already logged, so neither are logged.
+            if raw_request.custom_metric.is_some() || raw_request.streamed {
+                self.record_request(&raw_request);
+                message = parent_receiver.try_recv();
+                continue;
+            }
+            if let Some(user) = self.weighted_users.get(raw_request.user) {
+                raw_request.task_set = self.task_sets[user.task_sets_index].name.clone();
+            }
+
+            // Options should appear above, search for formatted_log.
+            let formatted_log = match self.configuration.stats_log_format.as_str() {
+                // Use serde_json to create JSON.
+                "json" => json!(raw_request).to_string(),
+                // Manually create CSV, library doesn't support single-row string conversion.
+                "csv" => GooseAttack::prepare_csv(
+                    &raw_request,
+                    &self.configuration.stats_log_columns,
+                    header,
+                ),
+                // Raw format is Debug output for GooseRawRequest structure.
+                "raw" => format!("{:?}", raw_request).to_string(),
+                _ => unreachable!(),
+            };
+
+            if let Some(file) = stats_log_file.as_mut() {
+                match file.write(format!("{}\n", formatted_log).as_ref()).await {
+                    Ok(_) => (),
+                    Err(e) => {
+                        self.stats_log_write_failures += 1;
+                        // Only warn once, instead of for every request that isn't logged.
+                        if self.stats_log_write_failures == 1 {
+                            warn!(
+                                "failed to write statistics to {}: {}",
+                                &self.configuration.stats_log_file, e
+                            );
+                        }
+                        // Writes keep failing, for example because the disk is full.
+                        if self.stats_log_write_failures >= STATS_LOG_MAX_WRITE_FAILURES {
+                            let detail = format!(
+                                "failed to write statistics to {} {} times: {}",
+                                &self.configuration.stats_log_file,
+                                self.stats_log_write_failures,
+                                e
+                            );
+                            if self.configuration.stats_log_required {
+                                error!("{}, stopping the load test", detail);
+                                self.stats_log_failed = Some((e.kind(), detail));
+                            } else {
+                                error!("{}, no longer logging statistics", detail);
+                            }
+                            *stats_log_file = None;
+                        }
+                    }
+                }
+            }
+
+            self.record_request(&raw_request);
+            message = parent_receiver.try_recv();
+        }
+        received_message
+    }
+
+    /// Merge a request received from a user thread into the load test statistics.
+    fn record_request(&mut self, raw_request: &GooseRawRequest) {
+        // Custom metrics are sent like requests, but only recorded as custom metrics.
+        if let Some(value) = raw_request.custom_metric {
+            self.stats.record_custom_metric(&raw_request.name, value);
+            return;
+        }
+        // The request callback already received the request a streamed body belongs to.
+        if let Some(request_callback) = &self.request_callback {
+            if !raw_request.streamed {
+                request_callback(raw_request);
+            }
+        }
+        let name = if self.configuration.aggregate_only {
+            AGGREGATE_ONLY_NAME.to_string()
+        } else if raw_request.normalize_name && !self.stats_normalizations.is_empty() {
+            self.name_prefix(raw_request) + &self.normalize_request_name(&raw_request.name)
+        } else {
+            self.name_prefix(raw_request) + &raw_request.name
+        };
+        // Compare hosts when load testing more than one, counting each request once.
+        if self.configuration.host.len() > 1 && !raw_request.streamed && !raw_request.retried {
+            self.stats.record_host_request(
+                raw_request,
+                self.configuration.stats_max_response_time,
+                self.configuration.stats_significant_figures,
+            );
+        }
+        let key = format!("{:?} {}", raw_request.method, name);
+        // Statistics were reset since the updated request was counted, so there's nothing
+        // to update.
+        if raw_request.update && !self.stats.requests.contains_key(&key) {
+            return;
+        }
+        let configuration = &self.configuration;
+        let merge_request = self.stats.requests.entry(key).or_insert_with(|| {
+            GooseRequest::new(
+                &name,
+                raw_request.method.clone(),
+                0,
+                GooseHistogram::new(
+                    configuration.stats_max_response_time,
+                    configuration.stats_significant_figures,
+                ),
+            )
+        });
+        // Count the bytes received by every attempt, including retried attempts.
+        if !raw_request.update {
+            merge_request.bytes += raw_request.bytes;
+            merge_request.bytes_sent += raw_request.bytes_sent;
+        }
+        // The bytes of a streamed response body are recorded after the request itself.
+        if raw_request.streamed {
+            return;
+        }
+        // With --exit-on-error, remember the first failure so the load test stops. Failed
+        // attempts that are retried aren't failures yet.
+        if self.configuration.exit_on_error
+            && !raw_request.success
+            && !raw_request.retried
+            && self.failed_request.is_none()
+        {
+            error!(
+                "exiting on failed request: {:?} {} ({}), status code {}",
+                raw_request.method, raw_request.name, raw_request.url, raw_request.status_code
+            );
+            self.failed_request = Some(raw_request.clone());
+        }
+        // Count failed attempts that were retried separately from the final outcome.
+        if raw_request.retried {
+            merge_request.retry_count += 1;
+        }
+        // Handle a statistics update, unless the request it updates was counted before the
+        // statistics were reset.
+        else if raw_request.update {
+            if !merge_request.record_outcome(raw_request) {
+                return;
+            }
+            if raw_request.over_budget {
+                merge_request.over_budget_count += 1;
+            }
+            if raw_request.too_fast {
+                merge_request.too_fast_count += 1;
+            }
+            // Updates are applied to the current throughput bucket, as the bucket the
+            // original request was counted in isn't known.
+            let throughput = self.stats.throughput_bucket(self.load_test_elapsed());
+            if raw_request.success {
+                throughput.fails = throughput.fails.saturating_sub(1);
+            } else {
+                throughput.fails += 1;
+            }
+        }
+        // Store a new statistic.
+        else {
+            merge_request.set_response_time(raw_request.response_time);
+            if let Some(server_time) = raw_request.server_time {
+                merge_request.set_server_time(server_time, raw_request.response_time);
+            }
+            if configuration.co_correction {
+                if let Some(user) = self.weighted_users.get(raw_request.user) {
+                    // Users pause for a random wait time between min_wait and max_wait
+                    // seconds after each task, use the average as the expected interval.
+                    let expected_interval = (user.min_wait + user.max_wait) as u64 * 1_000 / 2;
+                    stats::correct_coordinated_omission(
+                        &mut merge_request.response_time_histogram,
+                        raw_request.response_time,
+                        expected_interval,
+                    );
+                }
+            }
+            if configuration.status_codes {
+                merge_request.set_status_code(raw_request.status_code);
+                merge_request.set_status_class_response_time(
+                    raw_request.status_code,
+                    raw_request.response_time,
+                );
+            }
+            merge_request.record_outcome(raw_request);
+            let elapsed = self.load_test_elapsed();
+            let throughput = self.stats.throughput_bucket(elapsed);
+            throughput.requests += 1;
+            if !raw_request.success {
+                throughput.fails += 1;
+            }
+            if self.stats.slowest_count > 0 {
+                self.stats.record_slow_request(GooseSlowRequest {
+                    method: raw_request.method.clone(),
+                    url: if raw_request.final_url.is_empty() {
+                        raw_request.url.clone()
+                    } else {
+                        raw_request.final_url.clone()
+                    },
+                    status_code: raw_request.status_code,
+                    response_time: raw_request.response_time,
+                    elapsed,
+                });
+            }
+        }
+    }
+
+    /// Record the most users running during the current throughput bucket, and write all
+    /// completed buckets to the `--throughput-log` (if enabled). Once the load test has
+    /// finished, all remaining buckets are written.
+    async fn update_throughput(
+        &mut self,
+        throughput_log: &mut Option<ThroughputLog>,
+        active_users: usize,
+        finished: bool,
+    ) {
+        let throughput = self.stats.throughput_bucket(self.load_test_elapsed());
+        throughput.users = throughput.users.max(active_users);
+
+        if let Some(log) = throughput_log.as_mut() {
+            // The last bucket is still being counted until the load test finishes.
+            let completed = if finished {
+                self.stats.throughput.len()
+            } else {
+                self.stats.throughput.len() - 1
+            };
+            for bucket in &self.stats.throughput[log.written.min(completed)..completed] {
+                let row = format!(
+                    "{},{},{},{},{},{}\n",
+                    log.started + bucket.second as u64,
+                    bucket.second,
+                    bucket.requests,
+                    bucket.fails,
+                    bucket.users,
+                    bucket.paused
+                );
+                if let Err(e) = log.file.write_all(row.as_ref()).await {
+                    warn!(
+                        "failed to write throughput to {}: {}",
+                        &self.configuration.throughput_log, e
+                    );
+                }
+            }
+            log.written = log.written.max(completed);
+        }
+    }
+
+    /// How many seconds have passed since the load test started.
+    fn load_test_elapsed(&self) -> usize {
+        self.load_test_started
+            .map_or(0, |started| started.elapsed().as_secs() as usize)
+    }
+
+    /// Helper to create CSV-formatted logs, with the columns configured by
+    /// `--stats-log-columns`.
+    fn prepare_csv(raw_request: &GooseRawRequest, columns: &str, header: &mut bool) -> String {
+        let columns: Vec<&str> = if columns.is_empty() {
+            DEFAULT_STATS_LOG_CSV_COLUMNS.split(',').collect()
+        } else {
+            columns.split(',').map(|column| column.trim()).collect()
+        };
+        let body = columns
+            .iter()
+            .map(|column| match *column {
+                "elapsed" => raw_request.elapsed.to_string(),
+                "timestamp" => raw_request.timestamp.to_string(),
+                "method" => format!("{:?}", raw_request.method),
+                // Put quotes around strings, which may contain commas.
+                "name" => csv_quote(&raw_request.name),
+                "url" => csv_quote(&raw_request.url),
+                "final_url" => csv_quote(&raw_request.final_url),
+                "redirected" => raw_request.redirected.to_string(),
+                "response_time" => raw_request.response_time.to_string(),
+                "status_code" => raw_request.status_code.to_string(),
+                "success" => raw_request.success.to_string(),
+                "update" => raw_request.update.to_string(),
+                "user" => raw_request.user.to_string(),
+                "retried" => raw_request.retried.to_string(),
+                "bytes" => raw_request.bytes.to_string(),
+                "bytes_sent" => raw_request.bytes_sent.to_string(),
+                "over_budget" => raw_request.over_budget.to_string(),
+                "too_fast" => raw_request.too_fast.to_string(),
+                "server_time" => raw_request
+                    .server_time
+                    .map_or_else(String::new, |server_time| server_time.to_string()),
+                "streamed" => raw_request.streamed.to_string(),
+                "task_set" => csv_quote(&raw_request.task_set),
+                "error_category" => raw_request
+                    .error_category
+                    .map_or_else(String::new, |category| csv_quote(&category.to_string())),
+                "failure_tag" => raw_request
+                    .failure_tag
+                    .as_ref()
+                    .map_or_else(String::new, |tag| csv_quote(tag)),
+                // Columns are validated in setup.
+                _ => unreachable!(),
+            })
+            .collect::<Vec<String>>()
+            .join(",");
         // Concatenate the header before the body one time.
         if *header {
             *header = false;
-            format!(
-                // No quotes needed in header.
-                "{},{},{},{},{},{},{},{},{},{},{}\n",
-                "elapsed",
-                "method",
-                "name",
-                "url",
-                "final_url",
-                "redirected",
-                "response_time",
-                "status_code",
-                "success",
-                "update",
-                "user"
-            ) + &body
+            // No quotes needed in header.
+            format!("{}\n", columns.join(",")) + &body
         } else {
             body
         }
@@ -1328,72 +3585,223 @@ impl GooseAttack {
         // A handle to later rejoin the logger thread.
         Option<tokio::task::JoinHandle<()>>,
         // A channel used by GooseClients to send logs.
-        Option<mpsc::UnboundedSender<Option<GooseDebug>>>,
+        Option<mpsc::UnboundedSender<Option<GooseLog>>>,
     ) {
         // If the logger isn't configured, return immediately.
-        if self.configuration.debug_log_file.is_empty() {
+        if self.configuration.debug_log_file.is_empty()
+            && self.configuration.log_requests.is_empty()
+        {
             return (None, None);
         }
 
         // Create an unbounded channel allowing GooseUser threads to log errors.
         let (all_threads_logger, logger_receiver): (
-            mpsc::UnboundedSender<Option<GooseDebug>>,
-            mpsc::UnboundedReceiver<Option<GooseDebug>>,
+            mpsc::UnboundedSender<Option<GooseLog>>,
+            mpsc::UnboundedReceiver<Option<GooseLog>>,
         ) = mpsc::unbounded_channel();
         // Launch a new thread for logging.
         let logger_thread = tokio::spawn(logger::logger_main(
             self.configuration.clone(),
             logger_receiver,
+            self.request_logger.clone(),
         ));
         (Some(logger_thread), Some(all_threads_logger))
     }
 
-    // Helper to spawn a throttle thread if configured.
+    // Helper to spawn a Prometheus thread if configured, returns the metrics it serves.
+    async fn setup_prometheus(&self) -> Result<Option<Arc<RwLock<String>>>, GooseError> {
+        // If the Prometheus endpoint isn't configured, return immediately.
+        let port = match self.configuration.prometheus_port {
+            Some(port) => port,
+            None => return Ok(None),
+        };
+
+        let listener =
+            tokio::net::TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+        info!("serving prometheus metrics on port {}", port);
+
+        let metrics = Arc::new(RwLock::new(prometheus::format_metrics(&self.stats)));
+
+        // Launch a new thread for serving metrics, no need to rejoin it.
+        let _ = Some(tokio::spawn(prometheus::prometheus_main(
+            listener,
+            metrics.clone(),
+        )));
+
+        Ok(Some(metrics))
+    }
+
+    // Helper to spawn a control thread if configured, returns the channel it forwards
+    // commands on.
+    async fn setup_control(&self) -> Result<Option<mpsc::UnboundedReceiver<usize>>, GooseError> {
+        // If the control port isn't configured, return immediately.
+        let port = match self.configuration.control_port {
+            Some(port) => port,
+            None => return Ok(None),
+        };
+
+        // Only accept commands from the local host.
+        let listener =
+            tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+        info!("accepting control commands on port {}", port);
+
+        let (control_sender, control_receiver) = mpsc::unbounded_channel();
+
+        // Launch a new thread for accepting commands, no need to rejoin it.
+        let _ = Some(tokio::spawn(control::control_main(
+            listener,
+            control_sender,
+        )));
+
+        Ok(Some(control_receiver))
+    }
+
+    // Helper to spawn throttle threads if configured, one for `--throttle-requests` and one
+    // for each task set with its own throttle.
     async fn setup_throttle(
         &self,
     ) -> (
-        // A channel used by GooseClients to throttle requests.
-        Option<mpsc::Sender<bool>>,
-        // A channel used by parent to tell throttle the load test is complete.
-        Option<mpsc::Sender<bool>>,
+        // A channel used by GooseClients to throttle requests, for each task set.
+        Vec<Option<mpsc::Sender<bool>>>,
+        // Channels used by parent to tell throttles the load test is complete.
+        Vec<mpsc::Sender<bool>>,
     ) {
-        // If the throttle isn't configured, return immediately.
-        if self.configuration.throttle_requests.is_none() {
-            return (None, None);
+        let mut parent_to_throttle_txs = Vec::new();
+
+        // Users of task sets without their own throttle share the global throttle.
+        let global_throttle = match self.configuration.throttle_requests {
+            Some(throttle_requests) => {
+                let (all_threads_throttle, parent_to_throttle_tx) =
+                    throttle::spawn_throttle(throttle_requests).await;
+                parent_to_throttle_txs.push(parent_to_throttle_tx);
+                Some(all_threads_throttle)
+            }
+            None => None,
+        };
+
+        let mut task_set_throttles = Vec::new();
+        for task_set in &self.task_sets {
+            match task_set.throttle_requests {
+                Some(throttle_requests) => {
+                    debug!("throttling task set {}", task_set.name);
+                    let (task_set_throttle, parent_to_throttle_tx) =
+                        throttle::spawn_throttle(throttle_requests).await;
+                    parent_to_throttle_txs.push(parent_to_throttle_tx);
+                    task_set_throttles.push(Some(task_set_throttle));
+                }
+                None => task_set_throttles.push(global_throttle.clone()),
+            }
         }
 
-        // Unwrap is safe here as we exit early if the throttle isn't configured.
-        let throttle_requests = self.configuration.throttle_requests.unwrap();
+        (task_set_throttles, parent_to_throttle_txs)
+    }
 
-        // Create a bounded channel allowing single-sender multi-receiver to throttle
-        // GooseUser threads.
-        let (all_threads_throttle, throttle_receiver): (mpsc::Sender<bool>, mpsc::Receiver<bool>) =
-            mpsc::channel(throttle_requests);
+    /// Launch a user thread running its task set, returning a handle to later rejoin the
+    /// thread and a channel to control it.
+    fn launch_user(
+        &mut self,
+        mut thread_user: GooseUser,
+        launch: &GooseUserLaunch,
+    ) -> GooseUserThread {
+        // Copy weighted tasks and weighted on start tasks into the user thread.
+        thread_user.weighted_tasks = self.task_sets[thread_user.task_sets_index]
+            .weighted_tasks
+            .clone();
+        thread_user.weighted_on_start_tasks = self.task_sets[thread_user.task_sets_index]
+            .weighted_on_start_tasks
+            .clone();
+        thread_user.weighted_on_stop_tasks = self.task_sets[thread_user.task_sets_index]
+            .weighted_on_stop_tasks
+            .clone();
+        // Remember which task group this user is using.
+        thread_user.weighted_users_index = self.stats.users;
+        // Tell the user when the load test started.
+        thread_user.load_test_started = self.started.unwrap();
+
+        // Create a per-thread channel allowing parent thread to control child threads.
+        let (parent_sender, thread_receiver): (
+            mpsc::UnboundedSender<GooseUserCommand>,
+            mpsc::UnboundedReceiver<GooseUserCommand>,
+        ) = mpsc::unbounded_channel();
 
-        // Create a channel allowing the parent to inform the throttle thread when the
-        // load test is finished. Even though we only send one message, we can't use a
-        // oneshot channel as we don't want to block waiting for a message.
-        let (parent_to_throttle_tx, throttle_rx) = mpsc::channel(1);
+        // Copy the GooseUser-to-logger sender channel, used by all threads.
+        thread_user.logger = launch.logger.clone();
+        thread_user.log_requests = !self.configuration.log_requests.is_empty();
+
+        // Copy the GooseUser-throttle sender channel, shared by all threads running the
+        // same task set, or all threads if only the global throttle is enabled.
+        thread_user.throttle = launch.throttles[thread_user.task_sets_index].clone();
+
+        // Copy the feeders of test data, shared by all threads.
+        thread_user.feeders = self.feeders.clone();
+
+        // Copy the GooseUser-to-parent sender channel, used by all threads.
+        thread_user.parent = Some(launch.parent.clone());
+
+        // Keep a flag to tell the user it's exiting, even in the middle of a task.
+        let exiting = thread_user.exiting.clone();
+
+        // Copy the appropriate task_set into the thread.
+        let thread_task_set = self.task_sets[thread_user.task_sets_index].clone();
+
+        // We number threads from 1 as they're human-visible (in the logs), whereas
+        // stats.users starts at 0.
+        let thread_number = self.stats.users + 1;
+
+        let is_worker = self.configuration.worker;
+
+        // Launch a new user.
+        let thread_finished_users = launch.finished_users.clone();
+        let thread_canceled = launch.canceled.clone();
+        let thread_paused = launch.paused.clone();
+        // Each user makes its random choices with its own, optionally seeded, generator.
+        let thread_rng = data::user_rng(self.configuration.seed, thread_user.weighted_users_index);
+        let user = tokio::spawn(async move {
+            data::with_user_rng(
+                thread_rng,
+                user::user_main(
+                    thread_number,
+                    thread_task_set,
+                    thread_user,
+                    thread_receiver,
+                    is_worker,
+                    thread_canceled,
+                    thread_paused,
+                ),
+            )
+            .await;
+            thread_finished_users.fetch_add(1, Ordering::SeqCst);
+        });
 
-        // Launch a new thread for throttling, no need to rejoin it.
-        let _ = Some(tokio::spawn(throttle::throttle_main(
-            throttle_requests,
-            throttle_receiver,
-            throttle_rx,
-        )));
+        self.stats.users += 1;
+        (user, parent_sender, exiting)
+    }
 
-        let mut sender = all_threads_throttle.clone();
-        // We start from 1 instead of 0 to intentionally fill all but one slot in the
-        // channel to avoid a burst of traffic during startup. The channel then provides
-        // an implementation of the leaky bucket algorithm as a queue. Requests have to
-        // add a token to the bucket before making a request, and are blocked until this
-        // throttle thread "leaks out" a token thereby creating space. More information
-        // can be found at: https://en.wikipedia.org/wiki/Leaky_bucket
-        for _ in 1..throttle_requests {
-            let _ = sender.send(true).await;
+    /// Launch more users while the load test runs, as requested with `--control-port`.
+    /// Users are allocated to task sets as if the load test had been started with the
+    /// larger number of users, respecting weights and optional maximums.
+    fn add_users(
+        &mut self,
+        users: usize,
+        launch: &GooseUserLaunch,
+    ) -> Result<Vec<GooseUserThread>, GooseError> {
+        let launched = self.weighted_users.len();
+        self.users += users;
+        self.weighted_users = self.weight_task_set_users()?;
+
+        // Only the users allocated beyond those already running are new.
+        let new_users = self
+            .weighted_users
+            .get(launched..)
+            .unwrap_or_default()
+            .to_vec();
+        if new_users.is_empty() {
+            warn!("no users added, all task sets have their maximum number of users");
         }
-
-        (Some(all_threads_throttle), Some(parent_to_throttle_tx))
+        Ok(new_users
+            .into_iter()
+            .map(|user| self.launch_user(user, launch))
+            .collect())
     }
 
     /// Called internally in local-mode and gaggle-mode.
@@ -1416,11 +3824,16 @@ impl GooseAttack {
                     info!("running test_start_task");
                     // Create a one-time-use User to run the test_start_task.
                     let base_url = goose::get_base_url(
-                        self.get_configuration_host(),
+                        self.get_configuration_host(0),
                         None,
-                        self.host.clone(),
+                        self.get_default_host(),
                     )?;
-                    let user = GooseUser::single(base_url, &self.configuration)?;
+                    let mut user = GooseUser::single_with_client_builder(
+                        base_url,
+                        &self.configuration,
+                        self.client_builder.as_ref(),
+                    )?;
+                    user.feeders = self.feeders.clone();
                     let function = t.function;
                     let _ = function(&user).await;
                 }
@@ -1432,11 +3845,27 @@ impl GooseAttack {
         // If enabled, spawn a logger thread.
         let (logger_thread, all_threads_logger) = self.setup_logger();
 
-        // If enabled, spawn a throttle thread.
-        let (all_threads_throttle, parent_to_throttle_tx) = self.setup_throttle().await;
+        // If enabled, spawn throttle threads.
+        let (task_set_throttles, parent_to_throttle_txs) = self.setup_throttle().await;
+
+        // If enabled, spawn a Prometheus thread.
+        let prometheus_metrics = self.setup_prometheus().await?;
+
+        // If enabled, spawn a control thread.
+        let mut control_receiver = self.setup_control().await?;
+
+        // Catch ctrl-c and SIGTERM to allow clean shutdown to display statistics. Users can
+        // also cancel the load test, by returning GooseTaskError::StopLoadTest from a task.
+        let canceled = Arc::new(AtomicBool::new(false));
+        util::setup_ctrlc_handler(&canceled);
+        // Users stop running tasks while paused with --pause-file.
+        let paused = Arc::new(AtomicBool::new(false));
+        let mut pause_started = None;
 
         // Collect user threads in a vector for when we want to stop them later.
         let mut users = vec![];
+        // Count user threads that have exited on their own, ie after running all iterations.
+        let finished_users = Arc::new(AtomicUsize::new(0));
         // Collect user thread channels in a vector so we can talk to the user threads.
         let mut user_channels = vec![];
         // Create a single channel allowing all Goose child threads to sync state back to parent
@@ -1444,74 +3873,182 @@ impl GooseAttack {
             mpsc::UnboundedSender<GooseRawRequest>,
             mpsc::UnboundedReceiver<GooseRawRequest>,
         ) = mpsc::unbounded_channel();
+        // Everything user threads share, used to launch them.
+        let launch = GooseUserLaunch {
+            logger: all_threads_logger.clone(),
+            throttles: task_set_throttles,
+            parent: all_threads_sender,
+            finished_users: finished_users.clone(),
+            canceled: canceled.clone(),
+            paused: paused.clone(),
+        };
+
+        // Determine when to display running statistics (if enabled).
+        let mut statistics_timer = time::Instant::now();
+
+        // If logging stats to CSV, use this flag to write header; otherwise it's ignored.
+        let mut header = true;
+
+        // Prepare an asynchronous buffered file writer for stats_log_file (if enabled).
+        let mut stats_log_file = None;
+        if !self.configuration.no_stats && !self.configuration.stats_log_file.is_empty() {
+            info!(
+                "opening file to log statistics: {}",
+                self.configuration.stats_log_file
+            );
+            let file = if self.configuration.stats_log_append {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.configuration.stats_log_file)
+                    .await?;
+                // Don't write the CSV header in the middle of an existing log.
+                header = file.metadata().await?.len() == 0;
+                file
+            } else {
+                File::create(&self.configuration.stats_log_file).await?
+            };
+            stats_log_file = Some(BufWriter::new(file));
+        }
+
+        // Prepare an asynchronous buffered file writer for throughput_log (if enabled).
+        let mut throughput_log = None;
+        if !self.configuration.throughput_log.is_empty() {
+            info!(
+                "opening file to log throughput: {}",
+                self.configuration.throughput_log
+            );
+            let mut file = BufWriter::new(File::create(&self.configuration.throughput_log).await?);
+            file.write_all(format!("{}\n", THROUGHPUT_LOG_CSV_HEADER).as_ref())
+                .await?;
+            let now = time::SystemTime::now()
+                .duration_since(time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            throughput_log = Some(ThroughputLog {
+                file,
+                started: now.saturating_sub(self.load_test_elapsed() as u64),
+                written: 0,
+            });
+        }
+
+        // Track which step of the optional step load schedule is being launched.
+        let mut step_index = 0;
+
         // Spawn users, each with their own weighted task_set.
-        for mut thread_user in self.weighted_users.clone() {
+        for thread_user in self.weighted_users.clone() {
             // Stop launching threads if the run_timer has expired, unwrap is safe as we only get here if we started.
             if util::timer_expired(self.started.unwrap(), self.run_time) {
                 break;
             }
-
-            // Copy weighted tasks and weighted on start tasks into the user thread.
-            thread_user.weighted_tasks = self.task_sets[thread_user.task_sets_index]
-                .weighted_tasks
-                .clone();
-            thread_user.weighted_on_start_tasks = self.task_sets[thread_user.task_sets_index]
-                .weighted_on_start_tasks
-                .clone();
-            thread_user.weighted_on_stop_tasks = self.task_sets[thread_user.task_sets_index]
-                .weighted_on_stop_tasks
-                .clone();
-            // Remember which task group this user is using.
-            thread_user.weighted_users_index = self.stats.users;
-
-            // Create a per-thread channel allowing parent thread to control child threads.
-            let (parent_sender, thread_receiver): (
-                mpsc::UnboundedSender<GooseUserCommand>,
-                mpsc::UnboundedReceiver<GooseUserCommand>,
-            ) = mpsc::unbounded_channel();
-            user_channels.push(parent_sender);
-
-            if !self.configuration.debug_log_file.is_empty() {
-                // Copy the GooseUser-to-logger sender channel, used by all threads.
-                thread_user.logger = Some(all_threads_logger.clone().unwrap());
-            } else {
-                thread_user.logger = None;
+            // Stop launching threads if the load test was canceled.
+            if canceled.load(Ordering::SeqCst) {
+                break;
             }
-
-            // Copy the GooseUser-throttle receiver channel, used by all threads.
-            match self.configuration.throttle_requests {
-                Some(_) => thread_user.throttle = Some(all_threads_throttle.clone().unwrap()),
-                None => thread_user.throttle = None,
+            // Stop launching threads if the required statistics log can't be written.
+            if self.stats_log_failed.is_some() {
+                break;
+            }
+            // Users launched while paused wait to run their tasks until resumed.
+            self.update_paused(&paused, &mut pause_started);
+            // Stop launching threads if a request failed with --exit-on-error.
+            if self.configuration.exit_on_error {
+                self.receive_requests(&mut parent_receiver, &mut stats_log_file, &mut header)
+                    .await;
+                if self.failed_request.is_some() {
+                    break;
+                }
+            }
+            // Stop launching threads if too many requests failed with --hatch-error-rate.
+            if let Some(hatch_error_rate) = self.configuration.hatch_error_rate {
+                self.receive_requests(&mut parent_receiver, &mut stats_log_file, &mut header)
+                    .await;
+                if let Some(reason) = self.hatch_error(hatch_error_rate) {
+                    if self.configuration.hatch_error_exit {
+                        error!("stopping the load test: {}", reason);
+                        self.hatch_aborted = Some(reason);
+                    } else {
+                        error!("stopped launching users: {}", reason);
+                    }
+                    break;
+                }
             }
 
-            // Copy the GooseUser-to-parent sender channel, used by all threads.
-            thread_user.parent = Some(all_threads_sender.clone());
-
-            // Copy the appropriate task_set into the thread.
-            let thread_task_set = self.task_sets[thread_user.task_sets_index].clone();
-
-            // We number threads from 1 as they're human-visible (in the logs), whereas
-            // stats.users starts at 0.
-            let thread_number = self.stats.users + 1;
-
-            let is_worker = self.configuration.worker;
+            // Once all of a step's users are launched, hold the step before launching more.
+            if let Some(&(step_users, step_hold)) = self.step_load.get(step_index) {
+                if self.stats.users == step_users {
+                    info!(
+                        "launched {} users, holding for {} seconds...",
+                        step_users, step_hold
+                    );
+                    let step_started = time::Instant::now();
+                    let mut interrupted = false;
+                    while !util::timer_expired(step_started, step_hold) {
+                        // Stop holding if the load test is stopping, the checks above then
+                        // stop launching users.
+                        if canceled.load(Ordering::SeqCst)
+                            || self.stats_log_failed.is_some()
+                            || self.failed_request.is_some()
+                            || self.hatch_aborted.is_some()
+                            || self
+                                .configuration
+                                .hatch_error_rate
+                                .and_then(|rate| self.hatch_error(rate))
+                                .is_some()
+                        {
+                            interrupted = true;
+                            break;
+                        }
+                        self.update_paused(&paused, &mut pause_started);
+                        // Keep statistics running while holding the step.
+                        if !self.configuration.no_stats {
+                            self.receive_requests(
+                                &mut parent_receiver,
+                                &mut stats_log_file,
+                                &mut header,
+                            )
+                            .await;
+                            if !self.configuration.only_summary
+                                && !self.configuration.quiet
+                                && util::timer_expired(
+                                    statistics_timer,
+                                    self.configuration.running_stats_interval,
+                                )
+                            {
+                                statistics_timer = time::Instant::now();
+                                self.stats.duration =
+                                    self.started.unwrap().elapsed().as_secs() as usize;
+                                self.stats.print_running();
+                            }
+                        }
+                        tokio::time::delay_for(time::Duration::from_secs(1)).await;
+                    }
+                    step_index += 1;
+                    if interrupted {
+                        continue;
+                    }
+                }
+            }
 
             // Launch a new user.
-            let user = tokio::spawn(user::user_main(
-                thread_number,
-                thread_task_set,
-                thread_user,
-                thread_receiver,
-                is_worker,
-            ));
-
+            let (user, parent_sender, exiting) = self.launch_user(thread_user, &launch);
+            user_channels.push((parent_sender, exiting));
             users.push(user);
-            self.stats.users += 1;
+            if !self.configuration.no_stats {
+                let active_users = self.stats.users - finished_users.load(Ordering::SeqCst);
+                self.update_throughput(&mut throughput_log, active_users, false)
+                    .await;
+            }
             debug!("sleeping {:?} milliseconds...", sleep_duration);
             tokio::time::delay_for(sleep_duration).await;
         }
-        // Restart the timer now that all threads are launched.
-        self.started = Some(time::Instant::now());
+        if let Some(&(_, final_hold)) = self.step_load.last() {
+            // Statistics span all steps, so don't restart the timer. Instead stop once the
+            // final step has been held.
+            self.run_time = self.started.unwrap().elapsed().as_secs() as usize + final_hold;
+        } else {
+            // Restart the timer now that all threads are launched.
+            self.started = Some(time::Instant::now());
+        }
         if self.configuration.worker {
             info!(
                 "[{}] launched {} users...",
@@ -1528,101 +4065,69 @@ impl GooseAttack {
         // Track whether or not we've (optionally) reset the statistics after all users started.
         let mut statistics_reset: bool = false;
 
-        // Catch ctrl-c to allow clean shutdown to display statistics.
-        let canceled = Arc::new(AtomicBool::new(false));
-        util::setup_ctrlc_handler(&canceled);
+        // Track whether or not we've (optionally) reset the statistics after the warmup.
+        let warmup = util::parse_timespan(&self.configuration.warmup);
+        let mut warmup_completed = warmup == 0;
 
-        // Determine when to display running statistics (if enabled).
-        let mut statistics_timer = time::Instant::now();
         let mut display_running_statistics = false;
 
-        // Prepare an asynchronous buffered file writer for stats_log_file (if enabled).
-        let mut stats_log_file = None;
-        if !self.configuration.no_stats && !self.configuration.stats_log_file.is_empty() {
-            info!(
-                "opening file to log statistics: {}",
-                self.configuration.stats_log_file
-            );
-            let file = File::create(&self.configuration.stats_log_file).await?;
-            stats_log_file = Some(BufWriter::new(file));
-        }
+        // Track when the current window of statistics started, used by --reset-interval.
+        let reset_interval = util::parse_timespan(&self.configuration.reset_interval);
+        let mut statistics_started = self.started.unwrap();
+        // Track when the manager was last contacted, so workers send heartbeats when idle.
+        #[cfg(feature = "gaggle")]
+        let mut heartbeat_timer = time::Instant::now();
 
-        // If logging stats to CSV, use this flag to write header; otherwise it's ignored.
-        let mut header = true;
         loop {
+            // Pause or resume users if the --pause-file was created or removed.
+            self.update_paused(&paused, &mut pause_started);
+
+            // Add users if requested through the --control-port.
+            if let Some(receiver) = control_receiver.as_mut() {
+                while let Ok(users_to_add) = receiver.try_recv() {
+                    match self.add_users(users_to_add, &launch) {
+                        Ok(added) => {
+                            for (user, parent_sender, exiting) in added {
+                                user_channels.push((parent_sender, exiting));
+                                users.push(user);
+                            }
+                            info!("launched {} users...", self.stats.users);
+                        }
+                        Err(e) => warn!("failed to add {} users: {}", users_to_add, e),
+                    }
+                }
+            }
+
             // Regularly sync data from user threads first.
             if !self.configuration.no_stats {
                 // Check if we're displaying running statistics.
                 if !self.configuration.only_summary
+                    && !self.configuration.quiet
                     && !self.configuration.worker
-                    && util::timer_expired(statistics_timer, RUNNING_STATS_EVERY)
+                    && util::timer_expired(
+                        statistics_timer,
+                        self.configuration.running_stats_interval,
+                    )
                 {
                     statistics_timer = time::Instant::now();
                     display_running_statistics = true;
                 }
 
                 // Load messages from user threads until the receiver queue is empty.
-                let mut received_message = false;
-                let mut message = parent_receiver.try_recv();
-                while message.is_ok() {
-                    received_message = true;
-                    let raw_request = message.unwrap();
-
-                    // Options should appear above, search for formatted_log.
-                    let formatted_log = match self.configuration.stats_log_format.as_str() {
-                        // Use serde_json to create JSON.
-                        "json" => json!(raw_request).to_string(),
-                        // Manually create CSV, library doesn't support single-row string conversion.
-                        "csv" => GooseAttack::prepare_csv(&raw_request, &mut header),
-                        // Raw format is Debug output for GooseRawRequest structure.
-                        "raw" => format!("{:?}", raw_request).to_string(),
-                        _ => unreachable!(),
-                    };
-
-                    if let Some(file) = stats_log_file.as_mut() {
-                        match file.write(format!("{}\n", formatted_log).as_ref()).await {
-                            Ok(_) => (),
-                            Err(e) => {
-                                warn!(
-                                    "failed to write statistics to {}: {}",
-                                    &self.configuration.stats_log_file, e
-                                );
-                            }
-                        }
-                    }
+                let received_message = self
+                    .receive_requests(&mut parent_receiver, &mut stats_log_file, &mut header)
+                    .await;
 
-                    let key = format!("{:?} {}", raw_request.method, raw_request.name);
-                    let mut merge_request = match self.stats.requests.get(&key) {
-                        Some(m) => m.clone(),
-                        None => GooseRequest::new(&raw_request.name, raw_request.method, 0),
-                    };
-                    // Handle a statistics update.
-                    if raw_request.update {
-                        if raw_request.success {
-                            merge_request.success_count += 1;
-                            merge_request.fail_count -= 1;
-                        } else {
-                            merge_request.success_count -= 1;
-                            merge_request.fail_count += 1;
-                        }
-                    }
-                    // Store a new statistic.
-                    else {
-                        merge_request.set_response_time(raw_request.response_time);
-                        if self.configuration.status_codes {
-                            merge_request.set_status_code(raw_request.status_code);
-                        }
-                        if raw_request.success {
-                            merge_request.success_count += 1;
-                        } else {
-                            merge_request.fail_count += 1;
-                        }
-                    }
-
-                    self.stats.requests.insert(key.to_string(), merge_request);
-                    message = parent_receiver.try_recv();
+                // Update the metrics served by the Prometheus thread (if enabled).
+                if let Some(metrics) = &prometheus_metrics {
+                    *metrics.write().await = prometheus::format_metrics(&self.stats);
                 }
 
+                // Track how many users are running, and log completed throughput buckets.
+                let active_users = self.stats.users - finished_users.load(Ordering::SeqCst);
+                self.update_throughput(&mut throughput_log, active_users, false)
+                    .await;
+
                 // As worker, push request statistics up to manager.
                 if self.configuration.worker && received_message {
                     #[cfg(feature = "gaggle")]
@@ -1630,7 +4135,8 @@ impl GooseAttack {
                         // Push request statistics to manager process.
                         if !worker::push_stats_to_manager(
                             &socket.clone().unwrap(),
-                            &self.stats.requests.clone(),
+                            &self.stats.requests,
+                            &self.stats.custom_metrics,
                             true,
                         ) {
                             // EXIT received, cancel.
@@ -1638,19 +4144,61 @@ impl GooseAttack {
                         }
                         // The manager has all our request statistics, reset locally.
                         self.stats.requests = HashMap::new();
+                        self.stats.custom_metrics.clear();
+                        heartbeat_timer = time::Instant::now();
+                    }
+                }
+                // As worker without request statistics to push, let the manager know
+                // we're still alive.
+                else if self.configuration.worker {
+                    #[cfg(feature = "gaggle")]
+                    {
+                        if util::timer_expired(heartbeat_timer, worker::HEARTBEAT_INTERVAL) {
+                            if !worker::send_heartbeat_to_manager(&socket.clone().unwrap()) {
+                                // EXIT received, cancel.
+                                canceled.store(true, Ordering::SeqCst);
+                            }
+                            heartbeat_timer = time::Instant::now();
+                        }
                     }
                 }
 
                 // Flush request statistics collected prior to all user threads running
                 if self.configuration.reset_stats && !statistics_reset {
                     info!("statistics reset...");
-                    self.stats.requests = HashMap::new();
+                    self.stats.reset_requests();
                     statistics_reset = true;
                 }
+
+                // Discard request statistics collected during the warmup.
+                if !warmup_completed && util::timer_expired(self.started.unwrap(), warmup) {
+                    info!("warmup completed, statistics reset...");
+                    self.stats.reset_requests();
+                    warmup_completed = true;
+                }
+
+                // Print and reset statistics at the end of each window. Workers push their
+                // statistics to the manager, where windows are tracked instead.
+                if !self.configuration.worker
+                    && util::timer_expired(statistics_started, reset_interval)
+                {
+                    self.stats
+                        .print_window_and_reset(statistics_started.elapsed().as_secs() as usize);
+                    statistics_started = time::Instant::now();
+                    // There's no need to also display running statistics for the empty window.
+                    display_running_statistics = false;
+                }
             }
 
+            // With --iterations, the load test is complete once all users have exited.
+            let iterations_completed = self.configuration.iterations > 0
+                && finished_users.load(Ordering::SeqCst) == self.stats.users;
             if util::timer_expired(self.started.unwrap(), self.run_time)
                 || canceled.load(Ordering::SeqCst)
+                || iterations_completed
+                || self.failed_request.is_some()
+                || self.hatch_aborted.is_some()
+                || self.stats_log_failed.is_some()
             {
                 if self.configuration.worker {
                     info!(
@@ -1664,7 +4212,34 @@ impl GooseAttack {
                         self.started.unwrap().elapsed().as_secs()
                     );
                 }
-                for (index, send_to_user) in user_channels.iter().enumerate() {
+                // Let the caller know if the load test was interrupted.
+                self.stats.canceled = canceled.load(Ordering::SeqCst);
+                // Unless canceled or a request failed, optionally spread stopping users over
+                // the shutdown time.
+                let shutdown_time = util::parse_timespan(&self.configuration.shutdown_time);
+                let shutdown_interval = if shutdown_time > 0
+                    && !canceled.load(Ordering::SeqCst)
+                    && self.failed_request.is_none()
+                    && self.hatch_aborted.is_none()
+                    && self.stats_log_failed.is_none()
+                    && !user_channels.is_empty()
+                {
+                    info!(
+                        "stopping {} users over {} seconds...",
+                        user_channels.len(),
+                        shutdown_time
+                    );
+                    Some(
+                        time::Duration::from_secs(shutdown_time as u64)
+                            / user_channels.len() as u32,
+                    )
+                } else {
+                    None
+                };
+                // Stop users in the reverse order they were launched.
+                for (index, (send_to_user, exiting)) in user_channels.iter().enumerate().rev() {
+                    // Stop retrying failed requests, the user exits after its current task.
+                    exiting.store(true, Ordering::SeqCst);
                     match send_to_user.send(GooseUserCommand::EXIT) {
                         Ok(_) => {
                             debug!("telling user {} to exit", index);
@@ -1673,6 +4248,21 @@ impl GooseAttack {
                             info!("failed to tell user {} to exit: {}", index, e);
                         }
                     }
+                    if let Some(interval) = shutdown_interval {
+                        tokio::time::delay_for(interval).await;
+                        // Keep collecting statistics from the users that are still running.
+                        if !self.configuration.no_stats {
+                            self.receive_requests(
+                                &mut parent_receiver,
+                                &mut stats_log_file,
+                                &mut header,
+                            )
+                            .await;
+                            if let Some(metrics) = &prometheus_metrics {
+                                *metrics.write().await = prometheus::format_metrics(&self.stats);
+                            }
+                        }
+                    }
                 }
                 if self.configuration.worker {
                     info!("[{}] waiting for users to exit", get_worker_id());
@@ -1680,17 +4270,17 @@ impl GooseAttack {
                     info!("waiting for users to exit");
                 }
 
-                // If throttle is enabled, tell throttle thread the load test is over.
-                if let Some(mut tx) = parent_to_throttle_tx {
+                // If throttles are enabled, tell throttle threads the load test is over.
+                for mut tx in parent_to_throttle_txs {
                     let _ = tx.send(false).await;
                 }
 
                 futures::future::join_all(users).await;
                 debug!("all users exited");
 
-                if !self.configuration.debug_log_file.is_empty() {
+                if let Some(all_threads_logger) = all_threads_logger {
                     // Tell logger thread to flush and exit.
-                    if let Err(e) = all_threads_logger.unwrap().send(None) {
+                    if let Err(e) = all_threads_logger.send(None) {
                         warn!("unexpected error telling logger thread to exit: {}", e);
                     };
                     // Wait for logger thread to flush and exit.
@@ -1699,27 +4289,10 @@ impl GooseAttack {
 
                 // If we're printing statistics, collect the final messages received from users.
                 if !self.configuration.no_stats {
-                    let mut message = parent_receiver.try_recv();
-                    while message.is_ok() {
-                        let raw_request = message.unwrap();
-                        let key = format!("{:?} {}", raw_request.method, raw_request.name);
-                        let mut merge_request = match self.stats.requests.get(&key) {
-                            Some(m) => m.clone(),
-                            None => GooseRequest::new(&raw_request.name, raw_request.method, 0),
-                        };
-                        merge_request.set_response_time(raw_request.response_time);
-                        if self.configuration.status_codes {
-                            merge_request.set_status_code(raw_request.status_code);
-                        }
-                        if raw_request.success {
-                            merge_request.success_count += 1;
-                        } else {
-                            merge_request.fail_count += 1;
-                        }
-
-                        self.stats.requests.insert(key.to_string(), merge_request);
-                        message = parent_receiver.try_recv();
-                    }
+                    self.receive_requests(&mut parent_receiver, &mut stats_log_file, &mut header)
+                        .await;
+                    // Log the remaining throughput buckets, including the final partial one.
+                    self.update_throughput(&mut throughput_log, 0, true).await;
                 }
 
                 #[cfg(feature = "gaggle")]
@@ -1729,7 +4302,8 @@ impl GooseAttack {
                         // Push request statistics to manager process.
                         worker::push_stats_to_manager(
                             &socket.clone().unwrap(),
-                            &self.stats.requests.clone(),
+                            &self.stats.requests,
+                            &self.stats.custom_metrics,
                             true,
                         );
                         // No need to reset local stats, the worker is exiting.
@@ -1743,14 +4317,14 @@ impl GooseAttack {
             // If enabled, display running statistics after sync
             if display_running_statistics {
                 display_running_statistics = false;
-                self.stats.duration = self.started.unwrap().elapsed().as_secs() as usize;
+                self.stats.duration = statistics_started.elapsed().as_secs() as usize;
                 self.stats.print_running();
             }
 
             let one_second = time::Duration::from_secs(1);
             tokio::time::delay_for(one_second).await;
         }
-        self.stats.duration = self.started.unwrap().elapsed().as_secs() as usize;
+        self.stats.duration = statistics_started.elapsed().as_secs() as usize;
 
         if !self.configuration.worker {
             // Run global test_stop_task, if defined.
@@ -1758,12 +4332,17 @@ impl GooseAttack {
                 Some(t) => {
                     info!("running test_stop_task");
                     let base_url = goose::get_base_url(
-                        self.get_configuration_host(),
+                        self.get_configuration_host(0),
                         None,
-                        self.host.clone(),
+                        self.get_default_host(),
                     )?;
                     // Create a one-time-use user to run the test_stop_task.
-                    let user = GooseUser::single(base_url, &self.configuration)?;
+                    let mut user = GooseUser::single_with_client_builder(
+                        base_url,
+                        &self.configuration,
+                        self.client_builder.as_ref(),
+                    )?;
+                    user.feeders = self.feeders.clone();
                     let function = t.function;
                     let _ = function(&user).await;
                 }
@@ -1780,6 +4359,13 @@ impl GooseAttack {
             );
             let _ = file.flush().await;
         };
+        if let Some(log) = throughput_log.as_mut() {
+            info!(
+                "flushing throughput_log: {}",
+                &self.configuration.throughput_log
+            );
+            let _ = log.file.flush().await;
+        };
         // Only display percentile once the load test is finished.
         self.stats.display_percentile = true;
 
@@ -1791,22 +4377,63 @@ impl GooseAttack {
 #[derive(StructOpt, Debug, Default, Clone, Serialize, Deserialize)]
 #[structopt(name = "Goose")]
 pub struct GooseConfiguration {
-    /// Host to load test, for example: http://10.21.32.33
-    #[structopt(short = "H", long, required = false, default_value = "")]
-    pub host: String,
+    /// Loads options from a TOML or YAML file, overridden by command line options
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_CONFIG_FILE")]
+    pub config_file: String,
+
+    /// Host to load test, for example: http://10.21.32.33, can be set more than once
+    #[structopt(short = "H", long, number_of_values = 1)]
+    pub host: Vec<String>,
 
     /// Number of concurrent Goose users (defaults to available CPUs).
-    #[structopt(short, long)]
+    #[structopt(short, long, env = "GOOSE_USERS")]
     pub users: Option<usize>,
 
     /// How many users to spawn per second.
-    #[structopt(short = "r", long, required = false, default_value = "1")]
+    #[structopt(
+        short = "r",
+        long,
+        required = false,
+        default_value = "1",
+        env = "GOOSE_HATCH_RATE"
+    )]
     pub hatch_rate: usize,
 
     /// Stop after e.g. (300s, 20m, 3h, 1h30m, etc.).
-    #[structopt(short = "t", long, required = false, default_value = "")]
+    #[structopt(
+        short = "t",
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_RUN_TIME"
+    )]
     pub run_time: String,
 
+    /// Stops users gradually over e.g. (30s, 5m, etc.) when the load test ends
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_SHUTDOWN_TIME"
+    )]
+    pub shutdown_time: String,
+
+    /// Pauses users while this file exists, resuming when it's removed
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_PAUSE_FILE")]
+    pub pause_file: String,
+
+    /// Stop after each user runs through its task set this many times
+    #[structopt(long, required = false, default_value = "0", env = "GOOSE_ITERATIONS")]
+    pub iterations: usize,
+
+    /// Seeds random task order, wait times and test data so runs can be reproduced
+    #[structopt(long, env = "GOOSE_SEED")]
+    pub seed: Option<u64>,
+
+    /// Launches users in steps of users@hold time, e.g. (10@30s,20@30s,40@60s)
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_STEP_LOAD")]
+    pub step_load: String,
+
     /// Don't print stats in the console
     #[structopt(long)]
     pub no_stats: bool,
@@ -1815,18 +4442,120 @@ pub struct GooseConfiguration {
     #[structopt(long)]
     pub status_codes: bool,
 
+    /// Parses Server-Timing response headers to compare server and client response times
+    #[structopt(long)]
+    pub server_timing: bool,
+
     /// Only prints summary stats
     #[structopt(long)]
     pub only_summary: bool,
 
+    /// Only prints summary stats, and only logs errors to the console
+    #[structopt(short = "q", long)]
+    pub quiet: bool,
+
+    /// How often to print running statistics, in seconds
+    #[structopt(
+        long,
+        required = false,
+        default_value = "15",
+        env = "GOOSE_RUNNING_STATS_INTERVAL"
+    )]
+    pub running_stats_interval: usize,
+
+    /// Adds 50th, 95th and 99th percentiles to running statistics
+    #[structopt(long)]
+    pub running_percentiles: bool,
+
     /// Resets statistics once hatching has been completed
     #[structopt(long)]
     pub reset_stats: bool,
 
+    /// Discards statistics for e.g. (30s, 5m, etc.) after all users are launched
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_WARMUP")]
+    pub warmup: String,
+
+    /// Sends this many unrecorded requests per user before it starts, opening connections
+    #[structopt(
+        long,
+        required = false,
+        default_value = "0",
+        env = "GOOSE_WARMUP_REQUESTS"
+    )]
+    pub warmup_requests: usize,
+
+    /// Path requested by --warmup-requests, defaults to the base URL of each user
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_WARMUP_PATH")]
+    pub warmup_path: String,
+
+    /// How many seconds each bucket of the throughput time series spans
+    #[structopt(
+        long,
+        required = false,
+        default_value = "1",
+        env = "GOOSE_THROUGHPUT_INTERVAL"
+    )]
+    pub throughput_interval: usize,
+
+    /// Throughput log file name, writes requests, fails and users per throughput interval as CSV
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_THROUGHPUT_LOG"
+    )]
+    pub throughput_log: String,
+
+    /// Shows the slowest N individual requests when the load test finishes
+    #[structopt(
+        long,
+        required = false,
+        default_value = "0",
+        env = "GOOSE_SHOW_SLOWEST"
+    )]
+    pub show_slowest: usize,
+
+    /// Prints and resets statistics every e.g. (10m, 1h, etc.)
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_RESET_INTERVAL"
+    )]
+    pub reset_interval: String,
+
+    /// Corrects percentiles for coordinated omission, based on task set wait times
+    #[structopt(long)]
+    pub co_correction: bool,
+
+    /// Tracks statistics per method instead of per request, to save memory
+    #[structopt(long)]
+    pub aggregate_only: bool,
+
     /// Shows list of all possible Goose tasks and exits
     #[structopt(short, long)]
     pub list: bool,
 
+    /// Shows list of all possible Goose tasks as JSON and exits
+    #[structopt(long)]
+    pub list_json: bool,
+
+    /// Shows how users and tasks will be allocated and exits, without sending requests
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Prints the load test hash that Gaggle workers must match and exits
+    #[structopt(long)]
+    pub print_hash: bool,
+
+    /// Only runs tasks with any of these tags, e.g. (smoke,checkout)
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_TAGS")]
+    pub tags: String,
+
+    /// Doesn't run tasks with any of these tags, e.g. (slow,admin)
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_EXCLUDE_TAGS")]
+    pub exclude_tags: String,
+
     // The number of occurrences of the `v/verbose` flag
     /// Debug level (-v, -vv, -vvv, etc.)
     #[structopt(short = "v", long, parse(from_occurrences))]
@@ -1838,33 +4567,181 @@ pub struct GooseConfiguration {
     pub log_level: u8,
 
     /// Log file name
-    #[structopt(long, default_value = "goose.log")]
+    #[structopt(long, default_value = "goose.log", env = "GOOSE_LOG_FILE")]
     pub log_file: String,
 
     /// Statistics log file name
-    #[structopt(short = "s", long, default_value = "")]
+    #[structopt(short = "s", long, default_value = "", env = "GOOSE_STATS_LOG_FILE")]
     pub stats_log_file: String,
 
     /// Statistics log format ('csv', 'json', or 'raw')
-    #[structopt(long, default_value = "json")]
+    #[structopt(long, default_value = "json", env = "GOOSE_STATS_LOG_FORMAT")]
     pub stats_log_format: String,
 
+    /// Comma-separated list of columns logged with --stats-log-format csv
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_STATS_LOG_COLUMNS"
+    )]
+    pub stats_log_columns: String,
+
+    /// Appends to the statistics log instead of overwriting it
+    #[structopt(long)]
+    pub stats_log_append: bool,
+
+    /// Stops the load test and fails if the statistics log can't be written
+    #[structopt(long)]
+    pub stats_log_required: bool,
+
+    /// Comma-separated list of percentiles to display, e.g. (50,90,95,99)
+    #[structopt(
+        long,
+        default_value = "50,75,98,99,99.9,99.99",
+        env = "GOOSE_STATS_PERCENTILES"
+    )]
+    pub stats_percentiles: String,
+
+    /// Highest response time in ms tracked accurately for percentiles
+    #[structopt(long, default_value = "60000", env = "GOOSE_STATS_MAX_RESPONSE_TIME")]
+    pub stats_max_response_time: u64,
+
+    /// Significant figures of precision tracked for percentiles (1-5)
+    #[structopt(long, default_value = "3", env = "GOOSE_STATS_SIGNIFICANT_FIGURES")]
+    pub stats_significant_figures: u8,
+
+    /// Report file name, writes an HTML or markdown (.md) report when the load test finishes
+    #[structopt(long, default_value = "", env = "GOOSE_REPORT_FILE")]
+    pub report_file: String,
+
+    /// Summary file name, writes final statistics as JSON when the load test finishes
+    #[structopt(long, default_value = "", env = "GOOSE_STATS_SUMMARY_FILE")]
+    pub stats_summary_file: String,
+
+    /// Fails the load test if a threshold is exceeded, e.g. (p95>500ms, error-rate>1%)
+    #[structopt(long, number_of_values = 1)]
+    pub fail_if: Vec<String>,
+
+    /// Stops the load test and fails when the first request fails
+    #[structopt(long)]
+    pub exit_on_error: bool,
+
+    /// Stops launching users if more than this percent of requests fail, e.g. (25.0)
+    #[structopt(long, env = "GOOSE_HATCH_ERROR_RATE")]
+    pub hatch_error_rate: Option<f32>,
+
+    /// Also stops the load test and fails when --hatch-error-rate is exceeded
+    #[structopt(long)]
+    pub hatch_error_exit: bool,
+
     /// Debug log file name
-    #[structopt(short = "d", long, default_value = "")]
+    #[structopt(short = "d", long, default_value = "", env = "GOOSE_DEBUG_LOG_FILE")]
     pub debug_log_file: String,
 
     /// Debug log format ('json' or 'raw')
-    #[structopt(long, default_value = "json")]
+    #[structopt(long, default_value = "json", env = "GOOSE_DEBUG_LOG_FORMAT")]
     pub debug_log_format: String,
 
-    /// Throttle (max) requests per second
+    /// Debug log sample rate, e.g. (0.01 logs 1% of messages)
+    #[structopt(long, default_value = "1", env = "GOOSE_DEBUG_LOG_SAMPLE")]
+    pub debug_log_sample: f32,
+
+    /// Request log file name, logs the method, URL and status of requests
+    #[structopt(long, default_value = "", env = "GOOSE_LOG_REQUESTS")]
+    pub log_requests: String,
+
+    /// Includes response bodies in the request log
     #[structopt(long)]
+    pub log_requests_body: bool,
+
+    /// Percentage of requests to log
+    #[structopt(long, default_value = "100", env = "GOOSE_LOG_REQUESTS_SAMPLE")]
+    pub log_requests_sample: f32,
+
+    /// Throttle (max) requests per second
+    #[structopt(long, env = "GOOSE_THROTTLE_REQUESTS")]
     pub throttle_requests: Option<usize>,
 
+    /// Maximum requests each user makes at once with get_all()
+    #[structopt(
+        long,
+        required = false,
+        default_value = "6",
+        env = "GOOSE_MAX_IN_FLIGHT"
+    )]
+    pub max_in_flight: usize,
+
+    /// Exposes Prometheus metrics on this port while the load test runs
+    #[structopt(long, env = "GOOSE_PROMETHEUS_PORT")]
+    pub prometheus_port: Option<u16>,
+
+    /// Accepts commands such as 'add N' on this localhost port while the load test runs
+    #[structopt(long, env = "GOOSE_CONTROL_PORT")]
+    pub control_port: Option<u16>,
+
     /// User follows redirect of base_url with subsequent requests
     #[structopt(long)]
     pub sticky_follow: bool,
 
+    /// Identifies the user making each request in a header, see --user-id-header
+    #[structopt(long)]
+    pub user_id: bool,
+
+    /// Name of the header set by --user-id
+    #[structopt(long, default_value = "X-Goose-User", env = "GOOSE_USER_ID_HEADER")]
+    pub user_id_header: String,
+
+    /// Follows at most this many redirects, 0 disables following redirects [default: 10]
+    #[structopt(long, env = "GOOSE_MAX_REDIRECTS")]
+    pub max_redirects: Option<usize>,
+
+    /// Request timeout e.g. (30s, 2m, etc.)
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_TIMEOUT")]
+    pub timeout: String,
+
+    /// Connection timeout e.g. (5s, 1m, etc.)
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_CONNECT_TIMEOUT"
+    )]
+    pub connect_timeout: String,
+
+    /// Uses HTTP/2 for all requests without negotiating (prior knowledge)
+    #[structopt(long)]
+    pub http2: bool,
+
+    /// Maximum idle connections kept open per host, unlimited by default
+    #[structopt(long, env = "GOOSE_POOL_MAX_IDLE")]
+    pub pool_max_idle: Option<usize>,
+
+    /// Sends TCP keepalive probes on idle connections every e.g. (30s, 1m, etc.)
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_TCP_KEEPALIVE"
+    )]
+    pub tcp_keepalive: String,
+
+    /// Accepts invalid TLS certificates, only use for testing staging environments
+    #[structopt(long)]
+    pub accept_invalid_certs: bool,
+
+    /// Sends all requests through a proxy, e.g. (http://proxy:3128)
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_PROXY")]
+    pub proxy: String,
+
+    /// Authenticates with the proxy as username:password
+    #[structopt(long, required = false, default_value = "", env = "GOOSE_PROXY_USER")]
+    pub proxy_user: String,
+
+    /// Connects to an IP instead of resolving an http host, e.g. (www.example.com:80:10.0.0.5)
+    #[structopt(long, number_of_values = 1)]
+    pub resolve: Vec<String>,
+
     /// Enables manager mode
     #[structopt(long)]
     pub manager: bool,
@@ -1874,15 +4751,29 @@ pub struct GooseConfiguration {
     pub no_hash_check: bool,
 
     /// Required when in manager mode, how many workers to expect
-    #[structopt(long, required = false, default_value = "0")]
+    #[structopt(
+        long,
+        required = false,
+        default_value = "0",
+        env = "GOOSE_EXPECT_WORKERS"
+    )]
     pub expect_workers: u16,
 
+    /// Continues without a worker that stops responding for e.g. (30s, 2m, etc.)
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_WORKER_TIMEOUT"
+    )]
+    pub worker_timeout: String,
+
     /// Define host manager listens on, formatted x.x.x.x
-    #[structopt(long, default_value = "0.0.0.0")]
+    #[structopt(long, default_value = "0.0.0.0", env = "GOOSE_MANAGER_BIND_HOST")]
     pub manager_bind_host: String,
 
     /// Define port manager listens on
-    #[structopt(long, default_value=DEFAULT_PORT)]
+    #[structopt(long, default_value=DEFAULT_PORT, env = "GOOSE_MANAGER_BIND_PORT")]
     pub manager_bind_port: u16,
 
     /// Enables worker mode
@@ -1890,17 +4781,217 @@ pub struct GooseConfiguration {
     pub worker: bool,
 
     /// Host manager is running on
-    #[structopt(long, default_value = "127.0.0.1")]
+    #[structopt(long, default_value = "127.0.0.1", env = "GOOSE_MANAGER_HOST")]
     pub manager_host: String,
 
     /// Port manager is listening on
-    #[structopt(long, default_value=DEFAULT_PORT)]
+    #[structopt(long, default_value=DEFAULT_PORT, env = "GOOSE_MANAGER_PORT")]
     pub manager_port: u16,
+
+    /// Shared secret workers must present to the manager
+    #[structopt(
+        long,
+        required = false,
+        default_value = "",
+        env = "GOOSE_GAGGLE_SECRET"
+    )]
+    pub gaggle_secret: String,
+}
+
+impl GooseConfiguration {
+    /// Load configuration from a TOML or YAML file, using defaults for any options not
+    /// set in the file. Options set with environment variables take precedence over the
+    /// file.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::{GooseAttack, GooseConfiguration};
+    ///
+    ///     let configuration = GooseConfiguration::from_config_file("goose.toml").unwrap();
+    ///     let mut goose_attack = GooseAttack::initialize_with_config(configuration);
+    /// ```
+    pub fn from_config_file(path: &str) -> Result<GooseConfiguration, GooseError> {
+        let matches = GooseConfiguration::clap().get_matches_from(vec!["goose"]);
+        let configuration =
+            merge_config_file(GooseConfiguration::from_clap(&matches), &matches, path)?;
+        merge_env(configuration, &matches, env_vars())
+    }
+
+    /// Load configuration from the command line, and if a configuration file is passed in
+    /// or set with `--config-file` merge in options from the file that weren't set on the
+    /// command line. Finally merge in options set with environment variables, which take
+    /// precedence over the file.
+    fn from_args_and_config_file(path: Option<&str>) -> Result<GooseConfiguration, GooseError> {
+        let matches = GooseConfiguration::clap().get_matches();
+        let configuration = GooseConfiguration::from_clap(&matches);
+        let path = match path {
+            Some(p) => p.to_string(),
+            None => configuration.config_file.clone(),
+        };
+        let configuration = if path.is_empty() {
+            configuration
+        } else {
+            merge_config_file(configuration, &matches, &path)?
+        };
+        merge_env(configuration, &matches, env_vars())
+    }
+}
+
+/// Returns all environment variables, ignoring those that aren't valid unicode as they
+/// can't be options.
+fn env_vars() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// Prefix of the environment variables that set options, for example `GOOSE_RUN_TIME`.
+const ENV_PREFIX: &str = "GOOSE_";
+
+/// Options counted by how many times they're set on the command line, which clap can't
+/// read from the environment.
+const COUNTED_OPTIONS: [&str; 2] = ["verbose", "log_level"];
+
+/// Returns true if the option was set with an environment variable named after it with
+/// the `GOOSE_` prefix, for example `--run-time` is set by `GOOSE_RUN_TIME`.
+fn set_by_env(key: &str) -> bool {
+    std::env::var_os(format!("{}{}", ENV_PREFIX, key.to_uppercase())).is_some()
+}
+
+/// Merge flags and options that can be set more than once from environment variables
+/// into a configuration, skipping any that were set on the command line. Options that
+/// take a single value are read from the environment by clap, as declared with `env` on
+/// each field, but clap requires a value for any option with an environment variable,
+/// which flags don't have, and would add the values of repeated options to those set on
+/// the command line.
+fn merge_env<I>(
+    mut configuration: GooseConfiguration,
+    matches: &structopt::clap::ArgMatches,
+    vars: I,
+) -> Result<GooseConfiguration, GooseError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut options = match serde_json::to_value(&configuration) {
+        Ok(serde_json::Value::Object(options)) => options,
+        _ => unreachable!(),
+    };
+    for (name, value) in vars {
+        // Other variables with the prefix are ignored, they may be used by the load test.
+        let key = match name.strip_prefix(ENV_PREFIX) {
+            Some(key) => key.to_lowercase(),
+            None => continue,
+        };
+        let option = match options.get(&key) {
+            Some(option @ serde_json::Value::Bool(_))
+            | Some(option @ serde_json::Value::Array(_)) => option,
+            Some(option) if COUNTED_OPTIONS.contains(&key.as_str()) => option,
+            _ => continue,
+        };
+        // Options set on the command line take precedence.
+        if matches.occurrences_of(key.replace('_', "-")) > 0 {
+            continue;
+        }
+
+        let invalid_env = |detail: String| GooseError::InvalidOption {
+            option: name.to_string(),
+            value: value.to_string(),
+            detail: Some(detail),
+        };
+        // Parse the value as the type of the option it sets.
+        let value = match option {
+            serde_json::Value::Bool(_) => match value.as_str() {
+                "true" | "1" => serde_json::Value::Bool(true),
+                "false" | "0" | "" => serde_json::Value::Bool(false),
+                _ => return Err(invalid_env("expected true or false".to_string())),
+            },
+            // Options that can be set multiple times are separated by commas.
+            serde_json::Value::Array(_) => serde_json::Value::Array(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| serde_json::Value::String(v.to_string()))
+                    .collect(),
+            ),
+            // Counted options are set to how many times they'd be repeated.
+            _ => serde_json::from_str(&value)
+                .map_err(|_| invalid_env("expected a number".to_string()))?,
+        };
+        options.insert(key, value);
+        // Merge each option separately, so an invalid value is reported with its variable.
+        configuration = serde_json::from_value(serde_json::Value::Object(options.clone()))
+            .map_err(|e| invalid_env(e.to_string()))?;
+    }
+    Ok(configuration)
+}
+
+/// Merge options from a TOML or YAML configuration file into a configuration, skipping
+/// any options that were set on the command line or with environment variables.
+fn merge_config_file(
+    configuration: GooseConfiguration,
+    matches: &structopt::clap::ArgMatches,
+    path: &str,
+) -> Result<GooseConfiguration, GooseError> {
+    let invalid_config_file = |detail: String| GooseError::InvalidOption {
+        option: "--config-file".to_string(),
+        value: path.to_string(),
+        detail: Some(detail),
+    };
+
+    let contents = std::fs::read_to_string(path)?;
+    let file_options: serde_json::Map<String, serde_json::Value> =
+        if path.ends_with(".yaml") || path.ends_with(".yml") {
+            serde_yaml::from_str(&contents).map_err(|e| invalid_config_file(e.to_string()))?
+        } else {
+            toml::from_str(&contents).map_err(|e| invalid_config_file(e.to_string()))?
+        };
+
+    let mut options = match serde_json::to_value(configuration) {
+        Ok(serde_json::Value::Object(options)) => options,
+        _ => unreachable!(),
+    };
+    for (key, value) in file_options {
+        // Options can be named as on the command line, or as in GooseConfiguration.
+        let key = key.replace('-', "_");
+        if key == "config_file" || !options.contains_key(&key) {
+            return Err(invalid_config_file(format!("unknown option: {}", key)));
+        }
+        // Options that can be set more than once can also be set to a single value.
+        let value = match (&options[&key], value) {
+            (serde_json::Value::Array(_), serde_json::Value::String(value)) => {
+                serde_json::Value::Array(vec![serde_json::Value::String(value)])
+            }
+            (_, value) => value,
+        };
+        // Options set on the command line or with environment variables take precedence.
+        if matches.occurrences_of(key.replace('_', "-")) == 0 && !set_by_env(&key) {
+            options.insert(key, value);
+        }
+    }
+    serde_json::from_value(serde_json::Value::Object(options))
+        .map_err(|e| invalid_config_file(e.to_string()))
+}
+
+/// Split a comma-separated list of `--tags` or `--exclude-tags`.
+fn parse_tags(tags: &str) -> Vec<&str> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// A task is selected if it has any of the `tags`, or if no `tags` are set, unless it
+/// also has any of the `exclude_tags`.
+fn task_selected(task: &GooseTask, tags: &[&str], exclude_tags: &[&str]) -> bool {
+    let has_any = |tags: &[&str]| task.tags.iter().any(|tag| tags.contains(&tag.as_str()));
+    (tags.is_empty() || has_any(tags)) && !has_any(exclude_tags)
 }
 
 /// Returns a sequenced bucket of weighted usize pointers to Goose Tasks
 fn weight_tasks(
     task_set: &GooseTaskSet,
+    tags: &[&str],
+    exclude_tags: &[&str],
 ) -> (WeightedGooseTasks, WeightedGooseTasks, WeightedGooseTasks) {
     trace!("weight_tasks for {}", task_set.name);
 
@@ -1915,6 +5006,10 @@ fn weight_tasks(
     let mut v: usize;
     // Handle ordering of tasks.
     for task in &task_set.tasks {
+        if !task_selected(task, tags, exclude_tags) {
+            debug!("{} not selected by tags", task.name);
+            continue;
+        }
         if task.sequence > 0 {
             if task.on_start {
                 if let Some(sequence) = sequenced_on_start_tasks.get_mut(&task.sequence) {
@@ -1967,6 +5062,8 @@ fn weight_tasks(
     }
     // 'u' will always be the greatest common divisor
     debug!("gcd: {}", u);
+    // Serial task sets run each task once per iteration, ignoring weights.
+    let serial = task_set.scheduler == GooseScheduler::Serial;
 
     // Apply weight to sequenced tasks.
     let mut weighted_tasks: WeightedGooseTasks = Vec::new();
@@ -1974,7 +5071,7 @@ fn weight_tasks(
         let mut sequence_weighted_tasks = Vec::new();
         for task in tasks {
             // divide by greatest common divisor so bucket is as small as possible
-            let weight = task.weight / u;
+            let weight = if serial { 1 } else { task.weight / u };
             trace!(
                 "{}: {} has weight of {} (reduced with gcd to {})",
                 task.tasks_index,
@@ -1992,7 +5089,7 @@ fn weight_tasks(
     let mut weighted_unsequenced_tasks = Vec::new();
     for task in unsequenced_tasks {
         // divide by greatest common divisor so bucket is as small as possible
-        let weight = task.weight / u;
+        let weight = if serial { 1 } else { task.weight / u };
         trace!(
             "{}: {} has weight of {} (reduced with gcd to {})",
             task.tasks_index,
@@ -2014,7 +5111,7 @@ fn weight_tasks(
         let mut sequence_on_start_weighted_tasks = Vec::new();
         for task in tasks {
             // divide by greatest common divisor so bucket is as small as possible
-            let weight = task.weight / u;
+            let weight = if serial { 1 } else { task.weight / u };
             trace!(
                 "{}: {} has weight of {} (reduced with gcd to {})",
                 task.tasks_index,
@@ -2032,7 +5129,7 @@ fn weight_tasks(
     let mut weighted_on_start_unsequenced_tasks = Vec::new();
     for task in unsequenced_on_start_tasks {
         // divide by greatest common divisor so bucket is as small as possible
-        let weight = task.weight / u;
+        let weight = if serial { 1 } else { task.weight / u };
         trace!(
             "{}: {} has weight of {} (reduced with gcd to {})",
             task.tasks_index,
@@ -2052,7 +5149,7 @@ fn weight_tasks(
         let mut sequence_on_stop_weighted_tasks = Vec::new();
         for task in tasks {
             // divide by greatest common divisor so bucket is as small as possible
-            let weight = task.weight / u;
+            let weight = if serial { 1 } else { task.weight / u };
             trace!(
                 "{}: {} has weight of {} (reduced with gcd to {})",
                 task.tasks_index,
@@ -2070,7 +5167,7 @@ fn weight_tasks(
     let mut weighted_on_stop_unsequenced_tasks = Vec::new();
     for task in unsequenced_on_stop_tasks {
         // divide by greatest common divisor so bucket is as small as possible
-        let weight = task.weight / u;
+        let weight = if serial { 1 } else { task.weight / u };
         trace!(
             "{}: {} has weight of {} (reduced with gcd to {})",
             task.tasks_index,
@@ -2084,6 +5181,23 @@ fn weight_tasks(
     // Unsequenced tasks come last.
     weighted_on_stop_tasks.push(weighted_on_stop_unsequenced_tasks);
 
+    // Round robin task sets take turns running tasks instead of shuffling them.
+    if task_set.scheduler == GooseScheduler::RoundRobin {
+        weighted_on_start_tasks = weighted_on_start_tasks
+            .iter()
+            .map(|bucket| round_robin(bucket))
+            .collect();
+        weighted_tasks = weighted_tasks
+            .iter()
+            .map(|bucket| round_robin(bucket))
+            .collect();
+        weighted_on_stop_tasks = weighted_on_stop_tasks
+            .iter()
+            .map(|bucket| round_robin(bucket))
+            .collect();
+        trace!("created round robin weighted_tasks: {:?}", weighted_tasks);
+    }
+
     (
         weighted_on_start_tasks,
         weighted_tasks,
@@ -2091,19 +5205,373 @@ fn weight_tasks(
     )
 }
 
+/// Reorders a bucket of weighted tasks so tasks take turns in the order they were
+/// registered, spreading out the turns of tasks with higher weights as evenly as
+/// possible (smooth weighted round robin). For example `[a, a, b]` becomes `[a, b, a]`.
+fn round_robin(bucket: &[usize]) -> Vec<usize> {
+    // Count the weight of each task, in the order tasks first appear.
+    let mut weights: Vec<(usize, usize)> = Vec::new();
+    for task_index in bucket {
+        match weights.iter_mut().find(|(index, _)| index == task_index) {
+            Some((_, weight)) => *weight += 1,
+            None => weights.push((*task_index, 1)),
+        }
+    }
+
+    // Each turn, every task gains its weight in credit and the task with the most credit
+    // runs, spending the total weight of all tasks.
+    let mut credits = vec![0; weights.len()];
+    let mut ordered = Vec::with_capacity(bucket.len());
+    for _ in 0..bucket.len() {
+        let mut next = 0;
+        for (position, (_, weight)) in weights.iter().enumerate() {
+            credits[position] += *weight as isize;
+            if credits[position] > credits[next] {
+                next = position;
+            }
+        }
+        credits[next] -= bucket.len() as isize;
+        ordered.push(weights[next].0);
+    }
+    ordered
+}
+
 fn is_valid_host(host: &str) -> Result<bool, GooseError> {
-    Url::parse(host).map_err(|parse_error| GooseError::InvalidHost {
-        host: host.to_string(),
-        detail: None,
-        parse_error,
-    })?;
+    goose::parse_host(host, "failure parsing host")?;
     Ok(true)
 }
 
+/// Quote a string column of the CSV stats log, doubling any quotes it contains so
+/// names and URLs with quotes or commas stay in one column.
+fn csv_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn config_file() {
+        let path = std::env::temp_dir().join("goose-config-file-test.toml");
+        let path = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "host = \"http://example.com\"\nusers = 5\nrun-time = \"10m\"\nstatus_codes = true\n",
+        )
+        .unwrap();
+
+        // Options not set in the file use the defaults.
+        let configuration = GooseConfiguration::from_config_file(path).unwrap();
+        assert_eq!(configuration.host, vec!["http://example.com"]);
+        assert_eq!(configuration.users, Some(5));
+        assert_eq!(configuration.run_time, "10m");
+        assert!(configuration.status_codes);
+        assert_eq!(configuration.hatch_rate, 1);
+
+        // Options set on the command line override the file.
+        let matches =
+            GooseConfiguration::clap().get_matches_from(vec!["goose", "--run-time", "30s"]);
+        let configuration =
+            merge_config_file(GooseConfiguration::from_clap(&matches), &matches, path).unwrap();
+        assert_eq!(configuration.run_time, "30s");
+        assert_eq!(configuration.users, Some(5));
+
+        // Unknown options and invalid values are errors.
+        std::fs::write(path, "not_an_option = 1\n").unwrap();
+        assert!(GooseConfiguration::from_config_file(path).is_err());
+        std::fs::write(path, "users = \"five\"\n").unwrap();
+        assert!(GooseConfiguration::from_config_file(path).is_err());
+        std::fs::remove_file(path).unwrap();
+
+        // YAML files are also supported.
+        let path = std::env::temp_dir().join("goose-config-file-test.yaml");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "users: 3\nhatch-rate: 3\n").unwrap();
+        let configuration = GooseConfiguration::from_config_file(path).unwrap();
+        assert_eq!(configuration.users, Some(3));
+        assert_eq!(configuration.hatch_rate, 3);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn env() {
+        let vars = |vars: &[(&str, &str)]| -> Vec<(String, String)> {
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+        // Options that take a single value are read from the environment by clap. Only
+        // options that other tests don't check are set, as tests share the environment.
+        std::env::set_var("GOOSE_THROTTLE_REQUESTS", "20");
+        std::env::set_var("GOOSE_MAX_IN_FLIGHT", "3");
+        std::env::set_var("GOOSE_REPORT_FILE", "env.html");
+        let matches = GooseConfiguration::clap().get_matches_from(vec![
+            "goose",
+            "--report-file",
+            "cli.html",
+            "--exit-on-error",
+        ]);
+        let path = std::env::temp_dir().join("goose-env-test.toml");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "throttle_requests = 5\nshow_slowest = 4\n").unwrap();
+        let configuration =
+            merge_config_file(GooseConfiguration::from_clap(&matches), &matches, path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let configuration = merge_env(
+            configuration,
+            &matches,
+            vars(&[
+                ("GOOSE_HOST", "http://example.com"),
+                ("GOOSE_STATUS_CODES", "1"),
+                ("GOOSE_EXIT_ON_ERROR", "0"),
+                ("GOOSE_VERBOSE", "2"),
+                ("GOOSE_THROTTLE_REQUESTS", "20"),
+                ("GOOSE_FAIL_IF", "p95>500ms, error-rate>1%"),
+                ("GOOSE_NOT_AN_OPTION", "ignored"),
+                ("PATH", "ignored"),
+            ]),
+        )
+        .unwrap();
+        std::env::remove_var("GOOSE_THROTTLE_REQUESTS");
+        std::env::remove_var("GOOSE_MAX_IN_FLIGHT");
+        std::env::remove_var("GOOSE_REPORT_FILE");
+        assert_eq!(configuration.host, vec!["http://example.com"]);
+        assert!(configuration.status_codes);
+        assert_eq!(configuration.verbose, 2);
+        assert_eq!(configuration.max_in_flight, 3);
+        assert_eq!(configuration.fail_if, vec!["p95>500ms", "error-rate>1%"]);
+        // The environment overrides the configuration file.
+        assert_eq!(configuration.throttle_requests, Some(20));
+        assert_eq!(configuration.show_slowest, 4);
+        // Options set on the command line override the environment.
+        assert_eq!(configuration.report_file, "cli.html");
+        assert!(configuration.exit_on_error);
+        // Options not set use the defaults.
+        assert_eq!(configuration.hatch_rate, 1);
+
+        // Invalid values are errors naming the variable.
+        for invalid in &[("GOOSE_VERBOSE", "many"), ("GOOSE_NO_STATS", "yes")] {
+            match merge_env(
+                GooseConfiguration::from_clap(&matches),
+                &matches,
+                vars(&[*invalid]),
+            ) {
+                Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, invalid.0),
+                _ => panic!("expected {} to be invalid", invalid.0),
+            }
+        }
+    }
+
+    async fn plan_task(_user: &GooseUser) -> goose::GooseTaskResult {
+        Ok(())
+    }
+
+    #[test]
+    fn load_test_hash() {
+        let goose_attack = |weight| {
+            GooseAttack::initialize_with_config(GooseConfiguration::default()).register_taskset(
+                taskset!("Browse")
+                    .register_task(task!(plan_task).set_weight(weight).unwrap())
+                    .register_task(task!(plan_task).set_name("view")),
+            )
+        };
+
+        // The same load test always has the same hash, so workers can be compared.
+        assert_eq!(
+            goose_attack(2).load_test_hash(),
+            goose_attack(2).load_test_hash()
+        );
+        // Changing how tasks are weighted changes the hash.
+        assert_ne!(
+            goose_attack(2).load_test_hash(),
+            goose_attack(3).load_test_hash()
+        );
+    }
+
+    #[test]
+    fn dry_run_plan() {
+        let configuration = GooseConfiguration {
+            hatch_rate: 2,
+            ..Default::default()
+        };
+        let mut goose_attack = GooseAttack::initialize_with_config(configuration)
+            .set_host("http://127.0.0.1")
+            .register_taskset(
+                taskset!("Browse")
+                    .set_weight(3)
+                    .unwrap()
+                    .register_task(task!(plan_task).set_name("login").set_on_start())
+                    .register_task(task!(plan_task).set_name("view").set_weight(2).unwrap())
+                    .register_task(task!(plan_task)),
+            )
+            .register_taskset(
+                taskset!("Admin")
+                    .set_host("http://admin.example.com")
+                    .register_task(task!(plan_task).set_name("edit")),
+            );
+        goose_attack.users = 4;
+        for task_set in &mut goose_attack.task_sets {
+            let (on_start, tasks, on_stop) = weight_tasks(task_set, &[], &[]);
+            task_set.weighted_on_start_tasks = on_start;
+            task_set.weighted_tasks = tasks;
+            task_set.weighted_on_stop_tasks = on_stop;
+        }
+        goose_attack.weighted_users = goose_attack.weight_task_set_users().unwrap();
+
+        assert_eq!(
+            goose_attack.dry_run_plan().unwrap(),
+            "Load test plan: 4 users, launching 2 per second
+ - Browse (weight: 3, reduced: 3, expected users: 3.0, allocated users: 3), host: http://127.0.0.1/
+    o on_start: login
+    o tasks: view, view, task 2
+ - Admin (weight: 1, reduced: 1, expected users: 1.0, allocated users: 1), host: http://admin.example.com/
+    o tasks: edit
+"
+        );
+    }
+
+    #[test]
+    fn task_set_weighting() {
+        let mut goose_attack = GooseAttack::initialize_with_config(GooseConfiguration::default())
+            .set_host("http://127.0.0.1")
+            .register_taskset(
+                taskset!("Browse")
+                    .set_weight(4)
+                    .unwrap()
+                    .register_task(task!(plan_task)),
+            )
+            .register_taskset(
+                taskset!("Admin")
+                    .set_weight(2)
+                    .unwrap()
+                    .register_task(task!(plan_task)),
+            );
+        goose_attack.users = 2;
+        assert_eq!(goose_attack.task_set_weights_gcd(), 2);
+        let weighted_users = goose_attack.weight_task_set_users().unwrap();
+
+        // Weights reduce to 2 and 1, so both users are allocated to the first task set.
+        assert_eq!(
+            goose_attack.task_set_weighting(&goose_attack.task_sets[0], &weighted_users),
+            "weight: 4, reduced: 2, expected users: 1.3, allocated users: 2"
+        );
+        assert_eq!(
+            goose_attack.task_set_weighting(&goose_attack.task_sets[1], &weighted_users),
+            "weight: 2, reduced: 1, expected users: 0.7, allocated users: 0"
+        );
+    }
+
+    #[test]
+    fn population() {
+        let population_attack = || {
+            let mut goose_attack =
+                GooseAttack::initialize_with_config(GooseConfiguration::default())
+                    .set_host("http://127.0.0.1")
+                    .register_taskset(taskset!("Browser").register_task(task!(plan_task)))
+                    .register_taskset(taskset!("Searcher").register_task(task!(plan_task)))
+                    .register_taskset(taskset!("Purchaser").register_task(task!(plan_task)))
+                    .register_taskset(taskset!("Admin").register_task(task!(plan_task)));
+            goose_attack.users = 10;
+            for task_set in &mut goose_attack.task_sets {
+                let (on_start, tasks, on_stop) = weight_tasks(task_set, &[], &[]);
+                task_set.weighted_on_start_tasks = on_start;
+                task_set.weighted_tasks = tasks;
+                task_set.weighted_on_stop_tasks = on_stop;
+            }
+            goose_attack
+        };
+
+        // Users are allocated by percentage, and task sets not in the population get none.
+        let mut goose_attack = population_attack().set_population(&[
+            ("Browser", 70),
+            ("Searcher", 20),
+            ("Purchaser", 10),
+        ]);
+        goose_attack.apply_population().unwrap();
+        let weighted_users = goose_attack.weight_task_set_users().unwrap();
+        let users = |index| {
+            weighted_users
+                .iter()
+                .filter(|user| user.task_sets_index == index)
+                .count()
+        };
+        assert_eq!((users(0), users(1), users(2), users(3)), (7, 2, 1, 0));
+        assert_eq!(
+            goose_attack.task_set_weighting(&goose_attack.task_sets[0], &weighted_users),
+            "weight: 70, reduced: 7, expected users: 7.0, allocated users: 7"
+        );
+        assert_eq!(
+            goose_attack.task_set_weighting(&goose_attack.task_sets[3], &weighted_users),
+            "weight: 0, not part of the population"
+        );
+
+        // Percentages must add up to 100.
+        let mut goose_attack =
+            population_attack().set_population(&[("Browser", 70), ("Searcher", 20)]);
+        match goose_attack.apply_population() {
+            Err(GooseError::InvalidWeight { weight, .. }) => assert_eq!(weight, 90),
+            _ => panic!("population adding up to 90 was accepted"),
+        }
+
+        // Every task set must exist, only be included once, and have a percentage.
+        for population in &[
+            vec![("Browser", 70), ("Seeker", 30)],
+            vec![("Browser", 70), ("Browser", 30)],
+            vec![("Browser", 100), ("Admin", 0)],
+        ] {
+            let mut goose_attack = population_attack().set_population(population);
+            assert!(goose_attack.apply_population().is_err());
+        }
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(parse_tags(""), Vec::<&str>::new());
+        assert_eq!(parse_tags("smoke, checkout,"), vec!["smoke", "checkout"]);
+
+        let untagged = task!(plan_task);
+        let smoke = task!(plan_task).set_tags(&["smoke"]);
+        let smoke_slow = task!(plan_task).set_tags(&["smoke", "slow"]);
+
+        // Without tags, all tasks are selected.
+        assert!(task_selected(&untagged, &[], &[]));
+        assert!(task_selected(&smoke, &[], &[]));
+        // Tasks with any of the tags are selected.
+        assert!(!task_selected(&untagged, &["smoke", "checkout"], &[]));
+        assert!(task_selected(&smoke, &["smoke", "checkout"], &[]));
+        assert!(task_selected(&smoke_slow, &["slow"], &[]));
+        // Tasks with any of the excluded tags aren't, even if they have a selected tag.
+        assert!(task_selected(&untagged, &[], &["slow"]));
+        assert!(task_selected(&smoke, &["smoke"], &["slow"]));
+        assert!(!task_selected(&smoke_slow, &["smoke"], &["slow"]));
+        assert!(!task_selected(&smoke_slow, &[], &["admin", "slow"]));
+    }
+
+    #[test]
+    fn round_robin_tasks() {
+        // Turns of heavier tasks are spread out evenly, in the order tasks first appear.
+        assert_eq!(round_robin(&[0, 0, 1]), vec![0, 1, 0]);
+        assert_eq!(round_robin(&[2, 2, 2, 1]), vec![2, 2, 1, 2]);
+        assert_eq!(
+            round_robin(&[0, 0, 0, 0, 1, 1, 2]),
+            vec![0, 1, 0, 2, 0, 1, 0]
+        );
+        // Unweighted tasks keep their order.
+        assert_eq!(round_robin(&[3, 1, 2]), vec![3, 1, 2]);
+        assert!(round_robin(&[]).is_empty());
+    }
+
+    #[test]
+    fn quote_csv() {
+        assert_eq!(csv_quote("GET /"), "\"GET /\"");
+        assert_eq!(csv_quote("a,b"), "\"a,b\"");
+        assert_eq!(
+            csv_quote("/search?q=\"goose\""),
+            "\"/search?q=\"\"goose\"\"\""
+        );
+    }
+
     #[test]
     fn valid_host() {
         assert_eq!(is_valid_host("http://example.com").is_ok(), true);
@@ -2125,5 +5593,36 @@ mod test {
         assert_eq!(is_valid_host("http://foo").is_ok(), true);
         assert_eq!(is_valid_host("http:///example.com").is_ok(), true);
         assert_eq!(is_valid_host("http:// example.com").is_ok(), false);
+        // Host names followed by a port would otherwise be parsed as a scheme.
+        assert!(is_valid_host("localhost:8080").is_err());
+        assert!(is_valid_host("http://localhost:8080").is_ok());
+
+        // Missing schemes are explained, suggesting what to add.
+        match is_valid_host("example.com") {
+            Err(GooseError::InvalidHost {
+                host,
+                detail,
+                parse_error,
+            }) => {
+                assert_eq!(host, "example.com");
+                assert!(detail.unwrap().contains("for example http://example.com"));
+                assert_eq!(parse_error, url::ParseError::RelativeUrlWithoutBase);
+            }
+            _ => panic!("host without a scheme must be invalid"),
+        }
+        match is_valid_host("localhost:8080") {
+            Err(GooseError::InvalidHost { detail, .. }) => {
+                assert!(detail
+                    .unwrap()
+                    .contains("for example http://localhost:8080"));
+            }
+            _ => panic!("host without a scheme must be invalid"),
+        }
+        match is_valid_host("http://") {
+            Err(GooseError::InvalidHost { detail, .. }) => {
+                assert_eq!(detail.unwrap(), "failure parsing host");
+            }
+            _ => panic!("host without a host name must be invalid"),
+        }
     }
 }
@@ -4,14 +4,15 @@ use tokio::io::BufWriter;
 use tokio::prelude::*;
 use tokio::sync::mpsc;
 
-use crate::goose::GooseDebug;
+use crate::goose::{GooseLog, GooseRequestLogger};
 use crate::GooseConfiguration;
 
 /// Logger thread, opens a log file (if configured) and waits for messages from
 /// GooseUser threads.
 pub async fn logger_main(
     configuration: GooseConfiguration,
-    mut log_receiver: mpsc::UnboundedReceiver<Option<GooseDebug>>,
+    mut log_receiver: mpsc::UnboundedReceiver<Option<GooseLog>>,
+    request_logger: Option<GooseRequestLogger>,
 ) {
     // Prepare an asynchronous buffered file writer for stats_log_file (if enabled).
     let mut debug_log_file = None;
@@ -33,9 +34,42 @@ pub async fn logger_main(
         }
     }
 
+    // Prepare an asynchronous buffered file writer for log_requests (if enabled).
+    let mut request_log_file = None;
+    if !configuration.log_requests.is_empty() {
+        request_log_file = match File::create(&configuration.log_requests).await {
+            Ok(f) => {
+                info!(
+                    "writing requests to log_requests: {}",
+                    &configuration.log_requests
+                );
+                Some(BufWriter::new(f))
+            }
+            Err(e) => {
+                panic!(
+                    "failed to create log_requests ({}): {}",
+                    configuration.log_requests, e
+                );
+            }
+        }
+    }
+
     // Loop waiting for and writing error logs from GooseUser threads.
     while let Some(message) = log_receiver.recv().await {
-        if let Some(goose_debug) = message {
+        if let Some(GooseLog::Request(request_log)) = message {
+            if let Some(file) = request_log_file.as_mut() {
+                // A custom request logger can format the line, or skip it by returning None.
+                let formatted_log = match request_logger.as_ref() {
+                    Some(request_logger) => request_logger(&request_log),
+                    None => Some(json!(request_log).to_string()),
+                };
+                if let Some(formatted_log) = formatted_log {
+                    if let Err(e) = file.write(format!("{}\n", formatted_log).as_ref()).await {
+                        warn!("failed to write to {}: {}", &configuration.log_requests, e);
+                    }
+                }
+            }
+        } else if let Some(GooseLog::Debug(goose_debug)) = message {
             // All Options are defined above, search for formatted_log.
             if let Some(file) = debug_log_file.as_mut() {
                 let formatted_log = match configuration.debug_log_format.as_str() {
@@ -67,4 +101,8 @@ pub async fn logger_main(
         info!("flushing debug_log_file: {}", &configuration.debug_log_file);
         let _ = file.flush().await;
     };
+    if let Some(file) = request_log_file.as_mut() {
+        info!("flushing log_requests: {}", &configuration.log_requests);
+        let _ = file.flush().await;
+    };
 }
@@ -1,13 +1,13 @@
 use lazy_static::lazy_static;
 use nng::*;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::{thread, time};
 
 use crate::goose::GooseRequest;
-use crate::stats;
+use crate::stats::{self, GooseCustomMetric};
 use crate::util;
 use crate::{GooseAttack, GooseConfiguration, GooseUserCommand};
 
@@ -21,6 +21,9 @@ pub struct GooseUserInitializer {
     pub task_sets_index: usize,
     /// The base_url for this user thread.
     pub base_url: String,
+    /// Hosts this user rotates through with each request, if host rotation is enabled.
+    #[serde(default)]
+    pub rotate_hosts: Vec<String>,
     /// Minimum amount of time to sleep after running a task.
     pub min_wait: usize,
     /// Maximum amount of time to sleep after running a task.
@@ -31,6 +34,19 @@ pub struct GooseUserInitializer {
     pub worker_id: usize,
 }
 
+/// The statistics a worker process pushes to the manager.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GooseWorkerStats {
+    /// Request statistics collected since the last push.
+    pub requests: HashMap<String, GooseRequest>,
+    /// Custom metrics recorded since the last push.
+    #[serde(default)]
+    pub custom_metrics: BTreeMap<String, GooseCustomMetric>,
+    /// The worker's `--gaggle-secret`, only sent when it first connects.
+    #[serde(default)]
+    pub gaggle_secret: String,
+}
+
 // Mutable singleton globally tracking how many workers are currently being managed.
 lazy_static! {
     static ref ACTIVE_WORKERS: AtomicUsize = AtomicUsize::new(0);
@@ -65,6 +81,43 @@ fn pipe_closed(_pipe: Pipe, event: PipeEvent) {
     }
 }
 
+/// Returns true if the worker presented the manager's --gaggle-secret, or none is required.
+/// The secrets are compared in constant time, so the comparison doesn't reveal how much
+/// of the secret a worker guessed correctly.
+fn valid_gaggle_secret(worker_secret: &str, secret: &str) -> bool {
+    if secret.is_empty() {
+        return true;
+    }
+    if worker_secret.len() != secret.len() {
+        return false;
+    }
+    worker_secret
+        .bytes()
+        .zip(secret.bytes())
+        .fold(0, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
+/// Reply to the worker that sent the last message with a command. Returns false if all
+/// workers have exited.
+fn reply_to_worker(server: &Socket, command: &GooseUserCommand) -> bool {
+    let mut message = Message::new().unwrap();
+    serde_cbor::to_writer(&mut message, command)
+        .map_err(|error| eprintln!("{:?}", error))
+        .expect("failed to serialize user command");
+    match server.try_send(message) {
+        Ok(_) => true,
+        Err((_, Error::TryAgain)) => {
+            if ACTIVE_WORKERS.load(Ordering::SeqCst) == 0 {
+                info!("all workers have exited");
+                return false;
+            }
+            true
+        }
+        Err((_, e)) => panic!("communication failure: {:?}", e),
+    }
+}
+
 /// Merge per-user-statistics from user thread into global parent statistics
 fn merge_from_worker(
     parent_request: &GooseRequest,
@@ -73,11 +126,10 @@ fn merge_from_worker(
 ) -> GooseRequest {
     // Make a mutable copy where we can merge things
     let mut merged_request = parent_request.clone();
-    // Iterate over user response times, and merge into global response time
-    merged_request.response_times = stats::merge_response_times(
-        merged_request.response_times,
-        user_request.response_times.clone(),
-    );
+    // Merge user response time histogram into global response time histogram.
+    merged_request
+        .response_time_histogram
+        .merge(&user_request.response_time_histogram);
     // Increment total response time counter.
     merged_request.total_response_time += &user_request.total_response_time;
     // Increment count of how many response counters we've seen.
@@ -96,6 +148,34 @@ fn merge_from_worker(
     merged_request.success_count += &user_request.success_count;
     // Increment total fail counter.
     merged_request.fail_count += &user_request.fail_count;
+    // Increment total retry counter.
+    merged_request.retry_count += &user_request.retry_count;
+    // Increment total over latency budget counter.
+    merged_request.over_budget_count += &user_request.over_budget_count;
+    // Increment total faster than minimum response time counter.
+    merged_request.too_fast_count += &user_request.too_fast_count;
+    // Increment total bytes received.
+    merged_request.bytes += &user_request.bytes;
+    // Increment total bytes sent.
+    merged_request.bytes_sent += &user_request.bytes_sent;
+    // Increment server timings.
+    merged_request.server_timing_counter += &user_request.server_timing_counter;
+    merged_request.total_server_time += &user_request.total_server_time;
+    merged_request.total_server_timed_response_time +=
+        &user_request.total_server_timed_response_time;
+    // Increment why requests failed.
+    for (category, count) in &user_request.error_categories {
+        *merged_request
+            .error_categories
+            .entry(*category)
+            .or_insert(0) += count;
+    }
+    for (tag, count) in &user_request.failure_tags {
+        *merged_request
+            .failure_tags
+            .entry(tag.to_string())
+            .or_insert(0) += count;
+    }
     // Only accrue overhead of merging status_code_counts if we're going to display the results
     if config.status_codes {
         for (status_code, count) in &user_request.status_code_counts {
@@ -114,6 +194,13 @@ fn merge_from_worker(
                 .status_code_counts
                 .insert(*status_code, new_count);
         }
+        for (status_class, histogram) in &user_request.status_class_histograms {
+            merged_request
+                .status_class_histograms
+                .entry(*status_class)
+                .or_insert_with(|| histogram.new_with_same_bounds())
+                .merge(histogram);
+        }
     }
     merged_request
 }
@@ -157,9 +244,23 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
     // Track how many workers we've seen.
     let mut workers: HashSet<Pipe> = HashSet::new();
 
+    // Optionally continue without workers that stop sending statistics, tracking the id of
+    // each worker and when statistics were last received from it.
+    let worker_timeout = util::parse_timespan(&goose_attack.configuration.worker_timeout);
+    let mut last_seen: HashMap<Pipe, (usize, time::Instant)> = HashMap::new();
+    let mut lost_workers: HashSet<Pipe> = HashSet::new();
+    // Track the users sent to each worker, so they can be handed to a replacement worker.
+    let mut worker_users: HashMap<Pipe, Vec<GooseUserInitializer>> = HashMap::new();
+    // Lost workers that were replaced, they already presented the --gaggle-secret.
+    let mut replaced_workers: HashSet<Pipe> = HashSet::new();
+    let mut disconnected_workers = 0;
+
     // Track start time, we'll reset this when the test actually starts.
     let mut started = time::Instant::now();
     let mut running_statistics_timer = time::Instant::now();
+    // Track when the current window of statistics started, used by --reset-interval.
+    let reset_interval = util::parse_timespan(&goose_attack.configuration.reset_interval);
+    let mut statistics_started = time::Instant::now();
     let mut exit_timer = time::Instant::now();
     let mut load_test_running = false;
     let mut load_test_finished = false;
@@ -174,8 +275,17 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
         if !load_test_finished {
             // If ACTIVE_WORKERS is less than the total workers seen, a worker went away.
             if ACTIVE_WORKERS.load(Ordering::SeqCst) < workers.len() {
+                // With --worker-timeout the load test continues with the remaining workers,
+                // the worker is dropped once it times out.
+                if load_test_running && worker_timeout > 0 {
+                    let disconnected = workers.len() - ACTIVE_WORKERS.load(Ordering::SeqCst);
+                    if disconnected > disconnected_workers {
+                        warn!("worker went away, continuing with remaining workers...");
+                        disconnected_workers = disconnected;
+                    }
+                }
                 // If worked goes away during load test, exit gracefully.
-                if load_test_running {
+                else if load_test_running {
                     info!(
                         "worker went away, stopping gracefully afer {} seconds...",
                         started.elapsed().as_secs()
@@ -191,12 +301,34 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
             }
         }
         if load_test_running {
+            // Drop workers that haven't sent statistics within --worker-timeout.
+            if !load_test_finished && worker_timeout > 0 {
+                for (pipe, (worker_id, seen)) in &last_seen {
+                    if !lost_workers.contains(pipe) && util::timer_expired(*seen, worker_timeout) {
+                        lost_workers.insert(*pipe);
+                        warn!(
+                            "worker {} stopped responding for {} seconds, continuing with {} of {} workers",
+                            worker_id,
+                            seen.elapsed().as_secs(),
+                            workers.len() - lost_workers.len(),
+                            workers.len()
+                        );
+                        // Flag the current statistics window as incomplete.
+                        goose_attack.stats.lost_workers += 1;
+                    }
+                }
+                if lost_workers.len() == workers.len() {
+                    warn!("all workers stopped responding, exiting...");
+                    break;
+                }
+            }
             if !load_test_finished {
                 // Test ran to completion or was canceled with ctrl-c.
                 if util::timer_expired(started, goose_attack.run_time)
                     || canceled.load(Ordering::SeqCst)
                 {
                     info!("stopping after {} seconds...", started.elapsed().as_secs());
+                    goose_attack.stats.canceled = canceled.load(Ordering::SeqCst);
                     load_test_finished = true;
                     exit_timer = time::Instant::now();
                 }
@@ -210,17 +342,38 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
 
             // When displaying running statistics, sync data from user threads first.
             if !goose_attack.configuration.only_summary
-                && util::timer_expired(running_statistics_timer, crate::RUNNING_STATS_EVERY)
+                && util::timer_expired(
+                    running_statistics_timer,
+                    goose_attack.configuration.running_stats_interval,
+                )
             {
                 // Reset timer each time we display statistics.
                 running_statistics_timer = time::Instant::now();
-                goose_attack.stats.duration =
-                    goose_attack.started.unwrap().elapsed().as_secs() as usize;
+                info!(
+                    "{} of {} workers connected",
+                    ACTIVE_WORKERS
+                        .load(Ordering::SeqCst)
+                        .min(workers.len() - lost_workers.len()),
+                    workers.len()
+                );
+                goose_attack.stats.duration = statistics_started.elapsed().as_secs() as usize;
                 goose_attack.stats.print_running();
             }
+
+            // Print and reset statistics at the end of each window. Worker statistics are
+            // merged one complete message at a time below, so a reset never splits a
+            // worker's flush.
+            if !load_test_finished && util::timer_expired(statistics_started, reset_interval) {
+                goose_attack
+                    .stats
+                    .print_window_and_reset(statistics_started.elapsed().as_secs() as usize);
+                statistics_started = time::Instant::now();
+            }
         } else if canceled.load(Ordering::SeqCst) {
+            // Return the (empty) statistics, flagged as canceled, instead of exiting.
             info!("load test canceled, exiting");
-            std::process::exit(1);
+            goose_attack.stats.canceled = true;
+            break;
         }
 
         // Check for messages from workers.
@@ -229,13 +382,36 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                 // Message received, grab the pipe to determine which worker it is.
                 let pipe = msg.pipe().expect("fatal error getting worker pipe");
 
-                // Workers always send a HashMap<String, GooseRequest>.
-                let requests: HashMap<String, GooseRequest> =
-                    serde_cbor::from_reader(msg.as_slice()).unwrap();
+                // Workers send GooseWorkerStats, or a HEARTBEAT when there are no
+                // statistics to push. Both are replied to the same way.
+                let worker_stats: GooseWorkerStats = match serde_cbor::from_reader(msg.as_slice()) {
+                    Ok(worker_stats) => worker_stats,
+                    Err(_) => match serde_cbor::from_reader(msg.as_slice()) {
+                        Ok(GooseUserCommand::HEARTBEAT) => {
+                            trace!("heartbeat received");
+                            GooseWorkerStats::default()
+                        }
+                        // Anything can connect to the manager, don't let it crash the manager.
+                        _ => {
+                            warn!("rejecting invalid message received from worker");
+                            if !reply_to_worker(&server, &GooseUserCommand::REJECT) {
+                                break;
+                            }
+                            continue;
+                        }
+                    },
+                };
+                let requests = worker_stats.requests;
                 debug!("requests statistics received: {:?}", requests.len());
 
                 // If workers already contains this pipe, we've seen this worker before.
                 if workers.contains(&pipe) {
+                    if let Some((worker_id, seen)) = last_seen.get_mut(&pipe) {
+                        *seen = time::Instant::now();
+                        if lost_workers.remove(&pipe) {
+                            info!("worker {} is responding again", worker_id);
+                        }
+                    }
                     let mut message = Message::new().unwrap();
                     // All workers are running load test, sending statistics.
                     if workers.len() == goose_attack.configuration.expect_workers as usize {
@@ -244,6 +420,13 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             debug!("requests statistics received: {:?}", requests.len());
                             for (request_key, request) in requests {
                                 trace!("request_key: {}", request_key);
+                                // Workers push their statistics frequently, so throughput is
+                                // tracked by when the manager receives them.
+                                let throughput = goose_attack
+                                    .stats
+                                    .throughput_bucket(started.elapsed().as_secs() as usize);
+                                throughput.requests += request.success_count + request.fail_count;
+                                throughput.fails += request.fail_count;
                                 let merged_request;
                                 if let Some(parent_request) =
                                     goose_attack.stats.requests.get(&request_key)
@@ -263,6 +446,15 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                                     .insert(request_key.to_string(), merged_request);
                             }
                         }
+                        // Custom metrics received, merge them into our local copy.
+                        for (name, metric) in &worker_stats.custom_metrics {
+                            goose_attack
+                                .stats
+                                .custom_metrics
+                                .entry(name.to_string())
+                                .or_insert_with(|| GooseCustomMetric::new(name))
+                                .merge(metric);
+                        }
                         // Notify the worker that the load test is over and to exit.
                         if load_test_finished {
                             debug!("telling worker to exit");
@@ -303,9 +495,48 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                 }
                 // This is the first time we've seen this worker.
                 else {
+                    // Reject new workers that don't share our --gaggle-secret, before
+                    // looking at anything else they sent.
+                    if !replaced_workers.contains(&pipe)
+                        && !valid_gaggle_secret(
+                            &worker_stats.gaggle_secret,
+                            &goose_attack.configuration.gaggle_secret,
+                        )
+                    {
+                        warn!("rejecting worker with an invalid --gaggle-secret");
+                        if !reply_to_worker(&server, &GooseUserCommand::REJECT) {
+                            break;
+                        }
+                        continue;
+                    }
+                    // A new worker connecting while the load test is running replaces a lost
+                    // worker. Workers first connect with their load test hash, a lost worker that
+                    // comes back after being replaced doesn't and is told to EXIT.
+                    let replacing = if load_test_running
+                        && !load_test_finished
+                        && requests.contains_key("load_test_hash")
+                    {
+                        lost_workers.iter().next().copied()
+                    } else {
+                        None
+                    };
                     // Make sure we're not already connected to all of our workers.
-                    if workers.len() >= goose_attack.configuration.expect_workers as usize {
+                    if replacing.is_none()
+                        && workers.len() >= goose_attack.configuration.expect_workers as usize
+                    {
                         // We already have enough workers, tell this extra one to EXIT.
+                        if load_test_running && !requests.contains_key("load_test_hash") {
+                            warn!("rejecting worker, it was replaced after it stopped responding");
+                        } else if load_test_running && !load_test_finished && worker_timeout == 0 {
+                            warn!("rejecting new worker, lost workers are only replaced with --worker-timeout");
+                        } else if load_test_running && !load_test_finished {
+                            warn!("rejecting new worker, no worker has been lost");
+                        } else {
+                            warn!(
+                                "rejecting new worker, all {} workers are already connected",
+                                goose_attack.configuration.expect_workers
+                            );
+                        }
                         let mut message = Message::new().unwrap();
                         serde_cbor::to_writer(&mut message, &GooseUserCommand::EXIT)
                             .map_err(|error| eprintln!("{:?}", error))
@@ -333,9 +564,15 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             Some(r) => {
                                 if r.load_test_hash != goose_attack.stats.hash {
                                     if goose_attack.configuration.no_hash_check {
-                                        warn!("worker is running a different load test, ignoring")
+                                        warn!(
+                                            "worker is running a different load test (hash {}, expected {}), ignoring",
+                                            r.load_test_hash, goose_attack.stats.hash
+                                        )
                                     } else {
-                                        panic!("worker is running a different load test, set --no-hash-check to ignore");
+                                        panic!(
+                                            "worker is running a different load test (hash {}, expected {}), compare --print-hash or set --no-hash-check to ignore",
+                                            r.load_test_hash, goose_attack.stats.hash
+                                        );
                                     }
                                 }
                             }
@@ -348,40 +585,63 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             }
                         };
 
-                        workers.insert(pipe);
-                        info!(
-                            "worker {} of {} connected",
-                            workers.len(),
-                            goose_attack.configuration.expect_workers
-                        );
-
-                        // Send new worker a batch of users.
-                        let mut user_batch = users_per_worker;
-                        // If remainder, put extra user in this batch.
-                        if users_remainder > 0 {
-                            users_remainder -= 1;
-                            user_batch += 1;
-                        }
-                        let mut users = Vec::new();
-
-                        // Pop users from available_users vector and build worker initializer.
-                        for _ in 1..=user_batch {
-                            let user = match available_users.pop() {
-                                Some(u) => u,
-                                None => {
-                                    panic!("not enough available users!?");
-                                }
-                            };
-                            // Build a vector of GooseUser initializers for next worker.
-                            users.push(GooseUserInitializer {
-                                task_sets_index: user.task_sets_index,
-                                base_url: user.base_url.read().await.to_string(),
-                                min_wait: user.min_wait,
-                                max_wait: user.max_wait,
-                                config: user.config.clone(),
-                                worker_id: workers.len(),
-                            });
-                        }
+                        let users = if let Some(lost_pipe) = replacing {
+                            // Hand the lost worker's id and users to the new worker.
+                            let (worker_id, _) = last_seen.remove(&lost_pipe).unwrap();
+                            workers.remove(&lost_pipe);
+                            lost_workers.remove(&lost_pipe);
+                            replaced_workers.insert(lost_pipe);
+                            workers.insert(pipe);
+                            last_seen.insert(pipe, (worker_id, time::Instant::now()));
+                            disconnected_workers = workers
+                                .len()
+                                .saturating_sub(ACTIVE_WORKERS.load(Ordering::SeqCst));
+                            info!("new worker connected, replacing worker {}", worker_id);
+                            worker_users.remove(&lost_pipe).unwrap_or_default()
+                        } else {
+                            workers.insert(pipe);
+                            last_seen.insert(pipe, (workers.len(), time::Instant::now()));
+                            info!(
+                                "worker {} of {} connected",
+                                workers.len(),
+                                goose_attack.configuration.expect_workers
+                            );
+
+                            // Send new worker a batch of users.
+                            let mut user_batch = users_per_worker;
+                            // If remainder, put extra user in this batch.
+                            if users_remainder > 0 {
+                                users_remainder -= 1;
+                                user_batch += 1;
+                            }
+                            let mut users = Vec::new();
+
+                            // Pop users from available_users vector and build worker initializer.
+                            for _ in 1..=user_batch {
+                                let user = match available_users.pop() {
+                                    Some(u) => u,
+                                    None => {
+                                        panic!("not enough available users!?");
+                                    }
+                                };
+                                // Build a vector of GooseUser initializers for next worker.
+                                users.push(GooseUserInitializer {
+                                    task_sets_index: user.task_sets_index,
+                                    base_url: user.base_url.read().await.to_string(),
+                                    rotate_hosts: user
+                                        .rotate_hosts
+                                        .iter()
+                                        .map(|host| host.to_string())
+                                        .collect(),
+                                    min_wait: user.min_wait,
+                                    max_wait: user.max_wait,
+                                    config: user.config.clone(),
+                                    worker_id: workers.len(),
+                                });
+                            }
+                            users
+                        };
+                        worker_users.insert(pipe, users.clone());
 
                         // Send vector of user initializers to worker.
                         let mut message = Message::new().unwrap();
@@ -389,7 +649,11 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             .map_err(|error| eprintln!("{:?}", error))
                             .expect("failed to serialize user initializers");
 
-                        info!("sending {} users to worker {}", users.len(), workers.len());
+                        info!(
+                            "sending {} users to worker {}",
+                            users.len(),
+                            last_seen[&pipe].0
+                        );
                         match server.try_send(message) {
                             Ok(_) => (),
                             Err((_, e)) => match e {
@@ -405,11 +669,18 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             },
                         }
 
-                        if workers.len() == goose_attack.configuration.expect_workers as usize {
+                        if !load_test_running
+                            && workers.len() == goose_attack.configuration.expect_workers as usize
+                        {
                             info!("gaggle distributed load test started");
                             // Reset start time, the distributed load test is truly starting now.
                             started = time::Instant::now();
                             running_statistics_timer = time::Instant::now();
+                            statistics_started = time::Instant::now();
+                            // Statistics timeouts are measured from when the load test starts.
+                            for (_, seen) in last_seen.values_mut() {
+                                *seen = time::Instant::now();
+                            }
                             load_test_running = true;
                         }
                     }
@@ -461,4 +732,17 @@ mod tests {
         assert_eq!(users_per_process, 4);
         assert_eq!(users_remainder, 16);
     }
+
+    #[test]
+    fn test_valid_gaggle_secret() {
+        // Without a secret, all workers are accepted.
+        assert!(valid_gaggle_secret("", ""));
+        assert!(!valid_gaggle_secret("", "secret"));
+
+        assert!(valid_gaggle_secret("secret", "secret"));
+        assert!(valid_gaggle_secret("secret", ""));
+        assert!(!valid_gaggle_secret("other", "secret"));
+        assert!(!valid_gaggle_secret("secreT", "secret"));
+        assert!(!valid_gaggle_secret("secret2", "secret"));
+    }
 }
@@ -0,0 +1,198 @@
+//! Optional generation of skeleton load tests from OpenAPI specifications.
+//!
+//! Requires the `openapi` compile-time feature. [`tasks_from_spec`](./fn.tasks_from_spec.html)
+//! reads an OpenAPI 3 or Swagger 2 specification in JSON format, and returns a
+//! `GooseTask` for each operation it documents. Each task is named after the path
+//! template of its operation, for example `/pets/{petId}`, so statistics are grouped by
+//! operation. Tasks can be renamed with `set_name()`, and still request their own path.
+//!
+//! Path parameters are left as placeholders, filled in from a row of test data each time
+//! the task runs. To fill them in, register a feeder named `openapi` with a column named
+//! after each path parameter. Placeholders without a matching column are requested as-is.
+//!
+//! Tasks send requests without a body, and are registered with the default weight of 1.
+//! Register them into a task set and adjust their weights to build the load test.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use goose::prelude::*;
+//!
+//! fn main() -> Result<(), GooseError> {
+//!     let mut taskset = taskset!("ApiUser");
+//!     for task in goose::openapi::tasks_from_spec("openapi.json")? {
+//!         taskset = taskset.register_task(task);
+//!     }
+//!
+//!     GooseAttack::initialize()?
+//!         .register_feeder("openapi", GooseFeeder::from_csv("pets.csv")?)
+//!         .register_taskset(taskset)
+//!         .execute()?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use serde_json::Value;
+use std::fs;
+use std::sync::Arc;
+
+use crate::goose::{GooseMethod, GooseTask, GooseTaskFunction, GooseTaskResult, GooseUser};
+use crate::GooseError;
+
+/// The name of the feeder that path parameters are filled in from.
+pub const OPENAPI_FEEDER: &str = "openapi";
+
+/// Load an OpenAPI 3 or Swagger 2 specification in JSON format, and return a `GooseTask`
+/// for each operation, sorted by path. Swagger 2 `basePath` is prepended to each path.
+/// Operations with methods Goose can't make, such as `OPTIONS`, are skipped.
+pub fn tasks_from_spec(path: &str) -> Result<Vec<GooseTask>, GooseError> {
+    let spec: Value = serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        GooseError::InvalidOption {
+            option: "openapi::tasks_from_spec".to_string(),
+            value: path.to_string(),
+            detail: Some(format!("failed to parse specification: {}", e)),
+        }
+    })?;
+    operations_from_spec(&spec)
+        .map_err(|detail| GooseError::InvalidOption {
+            option: "openapi::tasks_from_spec".to_string(),
+            value: path.to_string(),
+            detail: Some(detail),
+        })
+        .map(|operations| {
+            operations
+                .into_iter()
+                .map(|(function, path)| {
+                    let mut task = GooseTask::new(function).set_name(&path);
+                    task.data = Some(Arc::new(path));
+                    task
+                })
+                .collect()
+        })
+}
+
+/// Extract the task function and path template of each operation in a specification.
+fn operations_from_spec(spec: &Value) -> Result<Vec<(GooseTaskFunction, String)>, String> {
+    let paths = match spec.get("paths").and_then(Value::as_object) {
+        Some(p) => p,
+        None => return Err("specification has no paths".to_string()),
+    };
+    let base_path = spec
+        .get("basePath")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .trim_end_matches('/');
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = match item.as_object() {
+            Some(i) => i,
+            None => return Err(format!("path {} is not an object", path)),
+        };
+        // Keys of a path item that aren't methods, such as `parameters`, are ignored.
+        for method in item.keys() {
+            if let Some(function) = task_function(method) {
+                operations.push((function, format!("{}{}", base_path, path)));
+            }
+        }
+    }
+    if operations.is_empty() {
+        return Err("specification has no operations".to_string());
+    }
+    Ok(operations)
+}
+
+/// The task function that makes requests with an OpenAPI method, if Goose can make them.
+fn task_function(method: &str) -> Option<GooseTaskFunction> {
+    let function: GooseTaskFunction = match method {
+        "delete" => |user| Box::pin(operation(user, GooseMethod::DELETE)),
+        "get" => |user| Box::pin(operation(user, GooseMethod::GET)),
+        "head" => |user| Box::pin(operation(user, GooseMethod::HEAD)),
+        "patch" => |user| Box::pin(operation(user, GooseMethod::PATCH)),
+        "post" => |user| Box::pin(operation(user, GooseMethod::POST)),
+        "put" => |user| Box::pin(operation(user, GooseMethod::PUT)),
+        _ => return None,
+    };
+    Some(function)
+}
+
+/// Request the path template of the running task's operation, filling in path
+/// parameters from the `openapi` feeder.
+async fn operation(user: &GooseUser, method: GooseMethod) -> GooseTaskResult {
+    let mut path = user.get_task_data::<String>()?.to_string();
+    if path.contains('{') {
+        for (name, value) in user.get_feeder_row(OPENAPI_FEEDER).await? {
+            path = path.replace(&format!("{{{}}}", name), &value);
+        }
+    }
+
+    let request_builder = match method {
+        GooseMethod::DELETE => user.goose_delete(&path).await?,
+        GooseMethod::HEAD => user.goose_head(&path).await?,
+        GooseMethod::PATCH => user.goose_patch(&path).await?,
+        GooseMethod::POST => user.goose_post(&path).await?,
+        GooseMethod::PUT => user.goose_put(&path).await?,
+        _ => user.goose_get(&path).await?,
+    };
+    let _goose = user.goose_send(request_builder, None).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn operations() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "paths": {
+                "/pets": {
+                    "get": {"operationId": "listPets"},
+                    "post": {"operationId": "createPet"},
+                },
+                "/pets/{petId}": {
+                    "parameters": [{"name": "petId", "in": "path"}],
+                    "delete": {},
+                    "get": {},
+                    "options": {},
+                },
+            },
+        });
+        let paths: Vec<String> = operations_from_spec(&spec)
+            .unwrap()
+            .into_iter()
+            .map(|(_, path)| path)
+            .collect();
+        // Operations are sorted by path and method, skipping non-methods and OPTIONS.
+        assert_eq!(
+            paths,
+            vec!["/pets", "/pets", "/pets/{petId}", "/pets/{petId}"]
+        );
+
+        // Swagger 2 base paths are prepended.
+        let spec = json!({
+            "swagger": "2.0",
+            "basePath": "/v1/",
+            "paths": {"/pets": {"get": {}}},
+        });
+        let operations = operations_from_spec(&spec).unwrap();
+        assert_eq!(operations[0].1, "/v1/pets");
+
+        assert!(operations_from_spec(&json!({"openapi": "3.0.0"})).is_err());
+        assert!(operations_from_spec(&json!({"paths": {"/pets": {}}})).is_err());
+        assert!(operations_from_spec(&json!({"paths": {"/pets": []}})).is_err());
+    }
+
+    #[test]
+    fn methods() {
+        for method in &["delete", "get", "head", "patch", "post", "put"] {
+            assert!(task_function(method).is_some());
+        }
+        assert!(task_function("options").is_none());
+        assert!(task_function("trace").is_none());
+        assert!(task_function("parameters").is_none());
+    }
+}
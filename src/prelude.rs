@@ -1,5 +1,11 @@
+pub use crate::feeder::{GooseFeeder, GooseFeederOrder};
 pub use crate::goose::{
-    GooseMethod, GooseTask, GooseTaskError, GooseTaskResult, GooseTaskSet, GooseUser,
+    GooseClientBuilder, GooseCompression, GooseErrorCategory, GooseMethod, GooseMultipart,
+    GooseRequestCallback, GooseRequestLog, GooseRequestLogger, GooseScheduler, GooseTask,
+    GooseTaskError, GooseTaskFunction, GooseTaskResult, GooseTaskSet, GooseUser,
+    GooseWaitDistribution,
+};
+pub use crate::stats::{
+    GooseCustomMetric, GooseRequestStats, GooseRequestSummary, GooseStats, GooseStatsSummary,
 };
-pub use crate::stats::{GooseRequestStats, GooseStats};
 pub use crate::{task, taskset, GooseAttack, GooseError};
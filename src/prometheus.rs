@@ -0,0 +1,203 @@
+use itertools::Itertools;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+use tokio::time;
+
+use crate::goose::GooseMethod;
+use crate::stats::GooseStats;
+
+/// Quantiles of response times included in the response time summary.
+const QUANTILES: [f32; 3] = [0.5, 0.9, 0.99];
+
+/// How long to wait for a scraper to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// This Prometheus thread serves the most recent metrics in the Prometheus text
+/// exposition format, so they can be scraped while the load test runs. The parent
+/// thread renders updated metrics each time it syncs statistics from the GooseUser
+/// threads. Every request is answered with the metrics, regardless of path.
+pub async fn prometheus_main(mut listener: TcpListener, metrics: Arc<RwLock<String>>) {
+    loop {
+        let (stream, address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("failed to accept prometheus connection: {}", e);
+                continue;
+            }
+        };
+        debug!("serving prometheus metrics to {}", address);
+        // Serve each connection separately, so a slow client can't block the others.
+        tokio::spawn(prometheus_connection(stream, address, metrics.clone()));
+    }
+}
+
+/// Answer a single request on a Prometheus connection with the metrics.
+async fn prometheus_connection(
+    mut stream: TcpStream,
+    address: SocketAddr,
+    metrics: Arc<RwLock<String>>,
+) {
+    // Read the request, all requests are answered the same. Clients that don't send
+    // a request in time are answered anyway.
+    let mut buffer = [0; 1024];
+    let _ = time::timeout(READ_TIMEOUT, stream.read(&mut buffer)).await;
+
+    let body = metrics.read().await.clone();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        debug!("failed to write prometheus metrics to {}: {}", address, e);
+    }
+}
+
+/// Render request statistics in the Prometheus text exposition format.
+pub fn format_metrics(stats: &GooseStats) -> String {
+    let mut metrics = String::new();
+    // Writing to a String can't fail.
+    let _ = write_metrics(&mut metrics, stats);
+    metrics
+}
+
+fn write_metrics(metrics: &mut String, stats: &GooseStats) -> std::fmt::Result {
+    writeln!(metrics, "# HELP goose_users Number of users launched.")?;
+    writeln!(metrics, "# TYPE goose_users gauge")?;
+    writeln!(metrics, "goose_users {}", stats.users)?;
+
+    let requests: Vec<_> = stats.requests.iter().sorted().map(|(_, r)| r).collect();
+
+    writeln!(
+        metrics,
+        "# HELP goose_requests_total Number of requests made."
+    )?;
+    writeln!(metrics, "# TYPE goose_requests_total counter")?;
+    for request in &requests {
+        writeln!(
+            metrics,
+            "goose_requests_total{{{}}} {}",
+            labels(&request.method, &request.path),
+            request.success_count + request.fail_count
+        )?;
+    }
+
+    writeln!(
+        metrics,
+        "# HELP goose_requests_failed_total Number of failed requests."
+    )?;
+    writeln!(metrics, "# TYPE goose_requests_failed_total counter")?;
+    for request in &requests {
+        writeln!(
+            metrics,
+            "goose_requests_failed_total{{{}}} {}",
+            labels(&request.method, &request.path),
+            request.fail_count
+        )?;
+    }
+
+    writeln!(
+        metrics,
+        "# HELP goose_response_time_milliseconds Response times in milliseconds."
+    )?;
+    writeln!(metrics, "# TYPE goose_response_time_milliseconds summary")?;
+    for request in &requests {
+        let labels = labels(&request.method, &request.path);
+        for quantile in QUANTILES.iter() {
+            let value = if request.response_time_histogram.is_empty() {
+                0
+            } else {
+                request
+                    .response_time_histogram
+                    .value_at_percentile(*quantile)
+            };
+            writeln!(
+                metrics,
+                "goose_response_time_milliseconds{{{},quantile=\"{}\"}} {}",
+                labels, quantile, value
+            )?;
+        }
+        writeln!(
+            metrics,
+            "goose_response_time_milliseconds_sum{{{}}} {}",
+            labels, request.total_response_time
+        )?;
+        writeln!(
+            metrics,
+            "goose_response_time_milliseconds_count{{{}}} {}",
+            labels, request.response_time_counter
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Format the labels identifying a request.
+fn labels(method: &GooseMethod, name: &str) -> String {
+    format!(
+        "method=\"{:?}\",name=\"{}\"",
+        method,
+        escape_label_value(name)
+    )
+}
+
+/// Escape backslashes, double quotes and line feeds in label values.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::{GooseHistogram, GooseRequest};
+
+    #[test]
+    fn metrics() {
+        let mut stats = GooseStats {
+            users: 2,
+            ..Default::default()
+        };
+        let mut request = GooseRequest::new("/", GooseMethod::GET, 0, GooseHistogram::default());
+        request.set_response_time(10);
+        request.set_response_time(30);
+        request.success_count = 1;
+        request.fail_count = 1;
+        stats.requests.insert("GET /".to_string(), request);
+        let request = GooseRequest::new(
+            "say \"hi\"",
+            GooseMethod::POST,
+            0,
+            GooseHistogram::default(),
+        );
+        stats
+            .requests
+            .insert("POST say \"hi\"".to_string(), request);
+
+        let metrics = format_metrics(&stats);
+        assert!(metrics.contains("goose_users 2\n"));
+        assert!(metrics.contains("goose_requests_total{method=\"GET\",name=\"/\"} 2\n"));
+        assert!(metrics.contains("goose_requests_failed_total{method=\"GET\",name=\"/\"} 1\n"));
+        assert!(metrics.contains(
+            "goose_response_time_milliseconds{method=\"GET\",name=\"/\",quantile=\"0.5\"} 10\n"
+        ));
+        assert!(metrics.contains(
+            "goose_response_time_milliseconds{method=\"GET\",name=\"/\",quantile=\"0.99\"} 30\n"
+        ));
+        assert!(metrics
+            .contains("goose_response_time_milliseconds_sum{method=\"GET\",name=\"/\"} 40\n"));
+        assert!(metrics
+            .contains("goose_response_time_milliseconds_count{method=\"GET\",name=\"/\"} 2\n"));
+        // Label values are escaped.
+        assert!(
+            metrics.contains("goose_requests_total{method=\"POST\",name=\"say \\\"hi\\\"\"} 0\n")
+        );
+    }
+}
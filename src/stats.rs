@@ -1,14 +1,312 @@
 use itertools::Itertools;
 use num_format::{Locale, ToFormattedString};
-use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
 use std::{f32, fmt};
 
-use crate::goose::GooseRequest;
+use crate::goose::{
+    GooseErrorCategory, GooseHistogram, GooseMethod, GooseRawRequest, GooseRequest,
+};
 use crate::util;
 
 /// Goose optionally tracks statistics about requests made during a load test.
 pub type GooseRequestStats = HashMap<String, GooseRequest>;
 
+/// Percentiles displayed by default, can be overridden with `--stats-percentiles`.
+const DEFAULT_PERCENTILES: [f32; 6] = [0.5, 0.75, 0.98, 0.99, 0.999, 0.9999];
+/// Percentiles added to running statistics with `--running-percentiles`.
+const RUNNING_PERCENTILES: [f32; 3] = [0.5, 0.95, 0.99];
+
+/// Version of the `GooseStatsSummary` schema, incremented whenever fields are changed or
+/// removed so tooling can detect summaries it doesn't understand.
+const STATS_SUMMARY_VERSION: u32 = 1;
+
+/// A machine-readable summary of the statistics from a completed load test, written to
+/// JSON with `--stats-summary-file`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseStatsSummary {
+    /// Version of the summary schema, currently 1.
+    pub version: u32,
+    /// A hash of the load test, useful to verify if summaries are from the same load test.
+    pub hash: u64,
+    /// How many seconds the load test ran.
+    pub duration: usize,
+    /// Total number of users simulated during the load test.
+    pub users: usize,
+    /// Whether the load test was canceled before it completed.
+    pub canceled: bool,
+    /// How many Gaggle workers stopped responding, making the statistics incomplete.
+    #[serde(default)]
+    pub lost_workers: usize,
+    /// How many seconds each bucket of the throughput time series spans.
+    #[serde(default)]
+    pub throughput_interval: usize,
+    /// Requests completed in each bucket of the load test, see `GooseStats::throughput()`.
+    #[serde(default)]
+    pub throughput: Vec<GooseThroughput>,
+    /// Times users were paused with `--pause-file`, see `GooseStats::pauses`.
+    #[serde(default)]
+    pub pauses: Vec<GoosePause>,
+    /// The slowest individual requests, slowest first, tracked with `--show-slowest`.
+    #[serde(default)]
+    pub slowest: Vec<GooseSlowRequest>,
+    /// Summary of each request, sorted by method and name.
+    pub requests: Vec<GooseRequestSummary>,
+    /// Summary of all requests combined.
+    pub aggregated: GooseRequestSummary,
+    /// Summary of the requests to each host, sorted by host, when load testing more than
+    /// one `--host`.
+    #[serde(default)]
+    pub hosts: Vec<GooseRequestSummary>,
+}
+
+/// A summary of the statistics collected for one request, or all requests combined.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GooseRequestSummary {
+    /// The method of the request, or `None` for all requests combined.
+    pub method: Option<GooseMethod>,
+    /// The name of the request, or `Aggregated` for all requests combined.
+    pub name: String,
+    /// Number of successful requests.
+    pub success_count: usize,
+    /// Number of failed requests.
+    pub fail_count: usize,
+    /// Number of failed attempts that were retried, not included in the other counts.
+    #[serde(default)]
+    pub retry_count: usize,
+    /// Number of failed requests that exceeded the latency budget of their task.
+    #[serde(default)]
+    pub over_budget_count: usize,
+    /// Number of failed requests that were faster than the minimum response time of their
+    /// task.
+    #[serde(default)]
+    pub too_fast_count: usize,
+    /// Total bytes of response bodies received.
+    #[serde(default)]
+    pub bytes: u64,
+    /// Total bytes of request bodies sent.
+    #[serde(default)]
+    pub bytes_sent: u64,
+    /// Number of responses with a `Server-Timing` header, parsed with `--server-timing`.
+    #[serde(default)]
+    pub server_timing_count: usize,
+    /// Average server time in milliseconds reported in `Server-Timing` headers.
+    #[serde(default)]
+    pub average_server_time: f32,
+    /// Why requests failed, counting failed requests by error category.
+    #[serde(default)]
+    pub error_categories: BTreeMap<GooseErrorCategory, usize>,
+    /// Requests tasks marked as failures, counted by the tag passed to
+    /// `GooseUser::set_failure()`.
+    #[serde(default)]
+    pub failure_tags: BTreeMap<String, usize>,
+    /// Fastest response time in milliseconds.
+    pub min_response_time: usize,
+    /// Slowest response time in milliseconds.
+    pub max_response_time: usize,
+    /// Average response time in milliseconds.
+    pub average_response_time: f32,
+    /// Response times in milliseconds within each percentile, keyed by percentile (for
+    /// example `p99.9`).
+    pub percentiles: BTreeMap<String, usize>,
+    /// How often each status code was returned.
+    pub status_codes: BTreeMap<u16, usize>,
+}
+impl GooseRequestSummary {
+    fn new(
+        method: Option<GooseMethod>,
+        name: &str,
+        request: &GooseRequest,
+        percentiles: &[f32],
+    ) -> Self {
+        GooseRequestSummary {
+            method,
+            name: name.to_string(),
+            success_count: request.success_count,
+            fail_count: request.fail_count,
+            retry_count: request.retry_count,
+            over_budget_count: request.over_budget_count,
+            too_fast_count: request.too_fast_count,
+            bytes: request.bytes,
+            bytes_sent: request.bytes_sent,
+            server_timing_count: request.server_timing_counter,
+            average_server_time: if request.server_timing_counter > 0 {
+                request.total_server_time as f32 / request.server_timing_counter as f32
+            } else {
+                0.0
+            },
+            error_categories: request.error_categories.clone(),
+            failure_tags: request.failure_tags.clone(),
+            min_response_time: request.min_response_time,
+            max_response_time: request.max_response_time,
+            average_response_time: if request.response_time_counter > 0 {
+                request.total_response_time as f32 / request.response_time_counter as f32
+            } else {
+                0.0
+            },
+            percentiles: percentiles
+                .iter()
+                .map(|percent| {
+                    (
+                        format!("p{}", (percent * 10_000.0).round() / 100.0),
+                        calculate_response_time_percentile(
+                            &request.response_time_histogram,
+                            request.min_response_time,
+                            request.max_response_time,
+                            *percent,
+                        ),
+                    )
+                })
+                .collect(),
+            status_codes: request
+                .status_code_counts
+                .iter()
+                .map(|(status_code, count)| (*status_code, *count))
+                .collect(),
+        }
+    }
+}
+
+/// How many requests completed during one bucket of the load test, tracked to show how
+/// throughput changed over time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseThroughput {
+    /// Seconds from the start of the load test to the start of this bucket.
+    pub second: usize,
+    /// Number of requests that completed during this bucket.
+    pub requests: usize,
+    /// Number of those requests that failed.
+    pub fails: usize,
+    /// The most users running at once during this bucket.
+    #[serde(default)]
+    pub users: usize,
+    /// Whether users were paused with `--pause-file` at any time during this bucket.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// A time users were paused with `--pause-file`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GoosePause {
+    /// Seconds from the start of the load test to when users were paused.
+    pub started: usize,
+    /// Seconds from the start of the load test to when users were resumed, or `None` if
+    /// they were still paused when the load test finished.
+    pub resumed: Option<usize>,
+}
+
+/// One of the slowest individual requests of a load test, tracked with `--show-slowest`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GooseSlowRequest {
+    /// The method of the request.
+    pub method: GooseMethod,
+    /// The full URL that was requested.
+    pub url: String,
+    /// The HTTP response code, or 0 if there was no response.
+    pub status_code: u16,
+    /// How many milliseconds the request took.
+    pub response_time: u64,
+    /// Seconds from the start of the load test to when the request completed.
+    pub elapsed: usize,
+}
+// Slow requests are ordered by response time, ties are broken by the other fields so the
+// ordering is consistent with equality.
+impl Ord for GooseSlowRequest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.response_time
+            .cmp(&other.response_time)
+            .then_with(|| self.elapsed.cmp(&other.elapsed))
+            .then_with(|| self.url.cmp(&other.url))
+            .then_with(|| self.method.cmp(&other.method))
+            .then_with(|| self.status_code.cmp(&other.status_code))
+    }
+}
+impl PartialOrd for GooseSlowRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A named metric recorded by tasks with `GooseUser::record_custom_metric()`, for example
+/// a business value computed from a response.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GooseCustomMetric {
+    /// The name of the metric.
+    pub name: String,
+    /// How many values were recorded.
+    pub count: usize,
+    /// The smallest value recorded.
+    pub min: f64,
+    /// The largest value recorded.
+    pub max: f64,
+    /// The sum of all values recorded.
+    pub total: f64,
+}
+impl GooseCustomMetric {
+    /// Create a new custom metric without any values.
+    pub fn new(name: &str) -> Self {
+        GooseCustomMetric {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Record a value of the metric.
+    pub fn record(&mut self, value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.total += value;
+    }
+
+    /// Merge the values recorded for the same metric elsewhere, for example by a Gaggle
+    /// worker, into this metric.
+    pub fn merge(&mut self, other: &GooseCustomMetric) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            self.min = other.min;
+            self.max = other.max;
+        } else {
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+        self.count += other.count;
+        self.total += other.total;
+    }
+
+    /// The average of all values recorded, or 0 if none were.
+    pub fn average(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total / self.count as f64
+        }
+    }
+}
+
+/// A table of statistics, used to generate reports.
+struct ReportTable {
+    /// The title of the table.
+    title: String,
+    /// The column headers of the table.
+    header: Vec<String>,
+    /// One row of values per request.
+    rows: Vec<Vec<String>>,
+    /// An optional row of values aggregated from all requests.
+    aggregate: Option<Vec<String>>,
+}
+
 /// Statistics collected during a Goose load test.
 ///
 /// # Example
@@ -44,17 +342,201 @@ pub struct GooseStats {
     pub duration: usize,
     /// Total number of users simulated during this load test.
     pub users: usize,
+    /// Flag indicating the load test was canceled with ctrl-c before it completed.
+    pub canceled: bool,
+    /// How many Gaggle workers were dropped by `--worker-timeout`, in which case the
+    /// statistics don't include everything the lost workers requested.
+    pub lost_workers: usize,
     /// Goose request statistics.
     pub requests: GooseRequestStats,
+    /// Requests grouped by the host that served them, only tracked when load testing
+    /// more than one `--host`.
+    pub hosts: BTreeMap<String, GooseRequest>,
     /// Flag indicating whether or not to display percentile. Because we're deriving Default,
     /// this defaults to false.
     pub display_percentile: bool,
     /// Flag indicating whether or not to display status_codes. Because we're deriving Default,
     /// this defaults to false.
     pub display_status_codes: bool,
+    /// Percentiles to display, expressed as fractions (for example 0.99 for 99%). If empty,
+    /// the default percentiles are displayed.
+    pub percentiles: Vec<f32>,
+    /// Flag indicating whether or not to display the 50th, 95th and 99th percentiles when
+    /// `display_percentile` is false, configured with `--running-percentiles`.
+    pub running_percentiles: bool,
+    /// How many seconds each bucket of the throughput time series spans, configured with
+    /// `--throughput-interval`.
+    pub throughput_interval: usize,
+    /// Requests completed in each bucket of the load test, see `GooseStats::throughput()`.
+    pub throughput: Vec<GooseThroughput>,
+    /// Times users were paused with `--pause-file`. Like the throughput time series,
+    /// pauses aren't reset and cover the entire load test.
+    pub pauses: Vec<GoosePause>,
+    /// How many of the slowest individual requests to track, configured with
+    /// `--show-slowest`.
+    pub slowest_count: usize,
+    /// A bounded min-heap of the slowest requests, see `GooseStats::slowest()`.
+    pub(crate) slowest: BinaryHeap<Reverse<GooseSlowRequest>>,
+    /// Custom metrics recorded by tasks, keyed by name.
+    pub custom_metrics: BTreeMap<String, GooseCustomMetric>,
 }
 
 impl GooseStats {
+    /// Returns how many requests completed, and how many of them failed, in each bucket
+    /// of `--throughput-interval` seconds since the load test started. Buckets without
+    /// any requests are included, so the index of each bucket is also its position in
+    /// time.
+    ///
+    /// Unlike the request statistics, the time series isn't reset by `--reset-stats` or
+    /// `--reset-interval` and always covers the entire load test.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     let goose_stats = GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("ExampleUsers")
+    ///             .register_task(task!(example_task))
+    ///         )
+    ///         .execute()?;
+    ///
+    ///     for bucket in goose_stats.throughput() {
+    ///         println!("{}s: {} requests, {} failed", bucket.second, bucket.requests, bucket.fails);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn throughput(&self) -> &[GooseThroughput] {
+        &self.throughput
+    }
+
+    /// Returns the slowest individual requests of the load test, slowest first. Only
+    /// tracked if `--show-slowest` is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     let goose_stats = GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("ExampleUsers")
+    ///             .register_task(task!(example_task))
+    ///         )
+    ///         .execute()?;
+    ///
+    ///     for request in goose_stats.slowest() {
+    ///         println!("{:?} {} took {} ms", request.method, request.url, request.response_time);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn slowest(&self) -> Vec<GooseSlowRequest> {
+        self.slowest
+            .iter()
+            .map(|Reverse(request)| request.clone())
+            .sorted_by(|a, b| b.cmp(a))
+            .collect()
+    }
+
+    /// Tracks a request if it's one of the `slowest_count` slowest requests so far. The
+    /// fastest tracked request is at the top of the min-heap, so memory stays constant.
+    pub(crate) fn record_slow_request(&mut self, request: GooseSlowRequest) {
+        if self.slowest_count == 0 {
+            return;
+        }
+        if self.slowest.len() < self.slowest_count {
+            self.slowest.push(Reverse(request));
+        } else if let Some(Reverse(fastest)) = self.slowest.peek() {
+            if request > *fastest {
+                self.slowest.pop();
+                self.slowest.push(Reverse(request));
+            }
+        }
+    }
+
+    /// Records a value of a custom metric, see `GooseUser::record_custom_metric()`.
+    pub(crate) fn record_custom_metric(&mut self, name: &str, value: f64) {
+        self.custom_metrics
+            .entry(name.to_string())
+            .or_insert_with(|| GooseCustomMetric::new(name))
+            .record(value);
+    }
+
+    /// Records a request in the statistics of the host that served it, including updates
+    /// made with `GooseUser::set_success()` and `GooseUser::set_failure()`.
+    pub(crate) fn record_host_request(
+        &mut self,
+        raw_request: &GooseRawRequest,
+        max_response_time: u64,
+        significant_figures: u8,
+    ) {
+        // Statistics were reset since the updated request was counted.
+        if raw_request.update && !self.hosts.contains_key(&raw_request.host) {
+            return;
+        }
+        let hash = self.hash;
+        let request = self
+            .hosts
+            .entry(raw_request.host.clone())
+            .or_insert_with(|| {
+                GooseRequest::new(
+                    &raw_request.host,
+                    GooseMethod::GET,
+                    hash,
+                    GooseHistogram::new(max_response_time, significant_figures),
+                )
+            });
+        if !raw_request.update {
+            request.set_response_time(raw_request.response_time);
+        }
+        request.record_outcome(raw_request);
+    }
+
+    /// Stops tracking all slow requests, used when statistics are reset.
+    pub(crate) fn reset_slowest(&mut self) {
+        self.slowest.clear();
+    }
+
+    /// Discards the statistics collected so far, used by `--reset-stats`, `--warmup` and
+    /// `--reset-interval`. The throughput time series and pauses cover the entire load
+    /// test, so they're kept. Updates to requests counted before the reset are ignored.
+    pub(crate) fn reset_requests(&mut self) {
+        self.requests = HashMap::new();
+        self.hosts.clear();
+        self.custom_metrics.clear();
+        self.reset_slowest();
+    }
+
+    /// Returns the throughput bucket for the specified number of seconds since the load
+    /// test started, adding empty buckets as needed.
+    pub(crate) fn throughput_bucket(&mut self, elapsed: usize) -> &mut GooseThroughput {
+        let interval = self.throughput_interval.max(1);
+        let index = elapsed / interval;
+        while self.throughput.len() <= index {
+            let second = self.throughput.len() * interval;
+            self.throughput.push(GooseThroughput {
+                second,
+                ..Default::default()
+            });
+        }
+        &mut self.throughput[index]
+    }
+
     /// Consumes and display all statistics from a completed load test.
     ///
     /// # Example
@@ -79,6 +561,15 @@ impl GooseStats {
     /// }
     /// ```
     pub fn print(&self) {
+        if self.canceled {
+            info!("load test was canceled");
+        }
+        if self.lost_workers > 0 {
+            warn!(
+                "statistics are incomplete, {} workers stopped responding",
+                self.lost_workers
+            );
+        }
         info!("printing statistics after {} seconds...", self.duration);
 
         print!("{}", self);
@@ -95,6 +586,30 @@ impl GooseStats {
         println!("{}", self);
     }
 
+    /// Displays statistics collected during a window of a running load test, including
+    /// percentiles, then resets request statistics to start collecting the next window.
+    /// Used by `--reset-interval`.
+    pub fn print_window_and_reset(&mut self, duration: usize) {
+        self.duration = duration;
+        info!(
+            "printing statistics for {} second window, then resetting...",
+            self.duration
+        );
+        if self.lost_workers > 0 {
+            warn!(
+                "statistics for this window are incomplete, {} workers stopped responding",
+                self.lost_workers
+            );
+        }
+
+        let display_percentile = self.display_percentile;
+        self.display_percentile = true;
+        // Include a blank line after printing windowed statistics.
+        println!("{}", self);
+        self.display_percentile = display_percentile;
+        self.reset_requests();
+    }
+
     /// Optionally prepares a table of requests and fails.
     pub fn fmt_requests(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         // If there's nothing to display, exit immediately.
@@ -116,6 +631,8 @@ impl GooseStats {
             fmt,
             " ----------------------------------------------------------------------------- "
         )?;
+        // Avoid dividing by zero if statistics are displayed in the first second.
+        let duration = if self.duration > 0 { self.duration } else { 1 };
         let mut aggregate_fail_count = 0;
         let mut aggregate_total_count = 0;
         for (request_key, request) in self.requests.iter().sorted() {
@@ -137,8 +654,8 @@ impl GooseStats {
                         request.fail_count.to_formatted_string(&Locale::en),
                         fail_percent as usize
                     ),
-                    (total_count / self.duration).to_formatted_string(&Locale::en),
-                    (request.fail_count / self.duration).to_formatted_string(&Locale::en),
+                    (total_count / duration).to_formatted_string(&Locale::en),
+                    (request.fail_count / duration).to_formatted_string(&Locale::en),
                 )?;
             } else {
                 writeln!(
@@ -151,8 +668,8 @@ impl GooseStats {
                         request.fail_count.to_formatted_string(&Locale::en),
                         fail_percent
                     ),
-                    (total_count / self.duration).to_formatted_string(&Locale::en),
-                    (request.fail_count / self.duration).to_formatted_string(&Locale::en),
+                    (total_count / duration).to_formatted_string(&Locale::en),
+                    (request.fail_count / duration).to_formatted_string(&Locale::en),
                 )?;
             }
             aggregate_total_count += total_count;
@@ -180,8 +697,8 @@ impl GooseStats {
                         aggregate_fail_count.to_formatted_string(&Locale::en),
                         aggregate_fail_percent as usize
                     ),
-                    (aggregate_total_count / self.duration).to_formatted_string(&Locale::en),
-                    (aggregate_fail_count / self.duration).to_formatted_string(&Locale::en),
+                    (aggregate_total_count / duration).to_formatted_string(&Locale::en),
+                    (aggregate_fail_count / duration).to_formatted_string(&Locale::en),
                 )?;
             } else {
                 writeln!(
@@ -194,8 +711,8 @@ impl GooseStats {
                         aggregate_fail_count.to_formatted_string(&Locale::en),
                         aggregate_fail_percent
                     ),
-                    (aggregate_total_count / self.duration).to_formatted_string(&Locale::en),
-                    (aggregate_fail_count / self.duration).to_formatted_string(&Locale::en),
+                    (aggregate_total_count / duration).to_formatted_string(&Locale::en),
+                    (aggregate_fail_count / duration).to_formatted_string(&Locale::en),
                 )?;
             }
         }
@@ -210,7 +727,7 @@ impl GooseStats {
             return Ok(());
         }
 
-        let mut aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut aggregate_response_time_histogram = new_aggregate_histogram(self.requests.values());
         let mut aggregate_total_response_time: usize = 0;
         let mut aggregate_response_time_counter: usize = 0;
         let mut aggregate_min_response_time: usize = 0;
@@ -229,9 +746,8 @@ impl GooseStats {
             " ----------------------------------------------------------------------------- "
         )?;
         for (request_key, request) in self.requests.iter().sorted() {
-            // Iterate over user response times, and merge into global response times.
-            aggregate_response_times =
-                merge_response_times(aggregate_response_times, request.response_times.clone());
+            // Merge the response times into the aggregate histogram.
+            aggregate_response_time_histogram.merge(&request.response_time_histogram);
 
             // Increment total response time counter.
             aggregate_total_response_time += &request.total_response_time;
@@ -254,11 +770,11 @@ impl GooseStats {
                 request.total_response_time / request.response_time_counter,
                 request.min_response_time,
                 request.max_response_time,
-                util::median(
-                    &request.response_times,
-                    request.response_time_counter,
+                calculate_response_time_percentile(
+                    &request.response_time_histogram,
                     request.min_response_time,
-                    request.max_response_time
+                    request.max_response_time,
+                    0.5
                 ),
             )?;
         }
@@ -277,11 +793,11 @@ impl GooseStats {
                 aggregate_total_response_time / aggregate_response_time_counter,
                 aggregate_min_response_time,
                 aggregate_max_response_time,
-                util::median(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
+                calculate_response_time_percentile(
+                    &aggregate_response_time_histogram,
                     aggregate_min_response_time,
-                    aggregate_max_response_time
+                    aggregate_max_response_time,
+                    0.5
                 ),
             )?;
         }
@@ -290,48 +806,37 @@ impl GooseStats {
     }
 
     // Optionallyl prepares a table of slowest response times within several percentiles.
+    // Running statistics only include the compact running percentiles, if enabled.
     pub fn fmt_percentiles(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // If there's nothing to display, exit immediately.
-        if !self.display_percentile {
+        let percentiles = if self.display_percentile {
+            self.get_percentiles()
+        } else if self.running_percentiles {
+            RUNNING_PERCENTILES.to_vec()
+        } else {
+            // If there's nothing to display, exit immediately.
             return Ok(());
-        }
+        };
+        // Each percentile column is 9 characters wide, including the separator.
+        let width = 25 + percentiles.len() * 9;
 
-        let mut aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        let mut aggregate_total_response_time: usize = 0;
-        let mut aggregate_response_time_counter: usize = 0;
+        let mut aggregate_histogram = new_aggregate_histogram(self.requests.values());
         let mut aggregate_min_response_time: usize = 0;
         let mut aggregate_max_response_time: usize = 0;
-        writeln!(
-            fmt,
-            "-------------------------------------------------------------------------------"
-        )?;
+        writeln!(fmt, "{}", "-".repeat(width))?;
         writeln!(
             fmt,
             " Slowest page load within specified percentile of requests (in ms):"
         )?;
-        writeln!(
-            fmt,
-            " ------------------------------------------------------------------------------"
-        )?;
-        writeln!(
-            fmt,
-            " {:<23} | {:<6} | {:<6} | {:<6} | {:<6} | {:<6} | {:6}",
-            "Name", "50%", "75%", "98%", "99%", "99.9%", "99.99%"
-        )?;
-        writeln!(
-            fmt,
-            " ----------------------------------------------------------------------------- "
-        )?;
+        writeln!(fmt, " {}", "-".repeat(width - 1))?;
+        let mut header = format!(" {:<23}", "Name");
+        for percent in &percentiles {
+            header.push_str(&format!(" | {:<6}", format_percentile(*percent)));
+        }
+        writeln!(fmt, "{}", header)?;
+        writeln!(fmt, " {}", "-".repeat(width - 1))?;
         for (request_key, request) in self.requests.iter().sorted() {
-            // Iterate over user response times, and merge into global response times.
-            aggregate_response_times =
-                merge_response_times(aggregate_response_times, request.response_times.clone());
-
-            // Increment total response time counter.
-            aggregate_total_response_time += &request.total_response_time;
-
-            // Increment counter tracking individual response times seen.
-            aggregate_response_time_counter += &request.response_time_counter;
+            // Merge user response times into global response times.
+            aggregate_histogram.merge(&request.response_time_histogram);
 
             // If user had new fastest response time, update global fastest response time.
             aggregate_min_response_time =
@@ -340,112 +845,54 @@ impl GooseStats {
             // If user had new slowest response time, update global slowest resposne time.
             aggregate_max_response_time =
                 update_max_response_time(aggregate_max_response_time, request.max_response_time);
-            // Sort response times so we can calculate a mean.
-            writeln!(
-                fmt,
-                " {:<23} | {:<6.2} | {:<6.2} | {:<6.2} | {:<6.2} | {:<6.2} | {:6.2}",
-                util::truncate_string(&request_key, 23),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.5
-                ),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.75
-                ),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.98
-                ),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.99
-                ),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.999
-                ),
-                calculate_response_time_percentile(
-                    &request.response_times,
-                    request.response_time_counter,
-                    request.min_response_time,
-                    request.max_response_time,
-                    0.999
-                ),
-            )?;
+
+            let mut row = format!(" {:<23}", util::truncate_string(&request_key, 23));
+            for percent in &percentiles {
+                row.push_str(&format!(
+                    " | {:<6.2}",
+                    calculate_response_time_percentile(
+                        &request.response_time_histogram,
+                        request.min_response_time,
+                        request.max_response_time,
+                        *percent
+                    )
+                ));
+            }
+            writeln!(fmt, "{}", row)?;
         }
         if self.requests.len() > 1 {
-            writeln!(
-                fmt,
-                " ------------------------+--------+--------+--------+--------+--------+------- "
-            )?;
-            writeln!(
-                fmt,
-                " {:<23} | {:<6.2} | {:<6.2} | {:<6.2} | {:<6.2} | {:<6.2} | {:6.2}",
-                "Aggregated",
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.5
-                ),
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.75
-                ),
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.98
-                ),
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.99
-                ),
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.999
-                ),
-                calculate_response_time_percentile(
-                    &aggregate_response_times,
-                    aggregate_response_time_counter,
-                    aggregate_min_response_time,
-                    aggregate_max_response_time,
-                    0.9999
-                ),
-            )?;
+            let mut separator = " ------------------------".to_string();
+            for _ in &percentiles {
+                separator.push_str("+--------");
+            }
+            writeln!(fmt, "{}", separator)?;
+            let mut row = format!(" {:<23}", "Aggregated");
+            for percent in &percentiles {
+                row.push_str(&format!(
+                    " | {:<6.2}",
+                    calculate_response_time_percentile(
+                        &aggregate_histogram,
+                        aggregate_min_response_time,
+                        aggregate_max_response_time,
+                        *percent
+                    )
+                ));
+            }
+            writeln!(fmt, "{}", row)?;
         }
 
         Ok(())
     }
 
+    /// Returns the percentiles to display, configured with `--stats-percentiles`.
+    pub fn get_percentiles(&self) -> Vec<f32> {
+        if self.percentiles.is_empty() {
+            DEFAULT_PERCENTILES.to_vec()
+        } else {
+            self.percentiles.clone()
+        }
+    }
+
     // Optionally prepares a table of response status codes.
     pub fn fmt_status_codes(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         // If there's nothing to display, exit immediately.
@@ -523,91 +970,1491 @@ impl GooseStats {
 
         Ok(())
     }
-}
-
-impl fmt::Display for GooseStats {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        // Formats from zero to four tables of data, depending on what data is contained
-        // and which contained flags are set.
-        self.fmt_requests(fmt)?;
-        self.fmt_response_times(fmt)?;
-        self.fmt_percentiles(fmt)?;
-        self.fmt_status_codes(fmt)
-    }
-}
-
-/// A helper function that merges together response times.
-///
-/// Used in `lib.rs` to merge together per-thread response times, and in `stats.rs`
-/// to aggregate all response times.
-pub fn merge_response_times(
-    mut global_response_times: BTreeMap<usize, usize>,
-    local_response_times: BTreeMap<usize, usize>,
-) -> BTreeMap<usize, usize> {
-    // Iterate over user response times, and merge into global response times.
-    for (response_time, count) in &local_response_times {
-        let counter = match global_response_times.get(&response_time) {
-            // We've seen this response_time before, increment counter.
-            Some(c) => *c + count,
-            // First time we've seen this response time, initialize counter.
-            None => *count,
-        };
-        global_response_times.insert(*response_time, counter);
-    }
-    global_response_times
-}
-
-// Update global minimum response time based on local resposne time.
-pub fn update_min_response_time(mut global_min: usize, min: usize) -> usize {
-    if global_min == 0 || (min > 0 && min < global_min) {
-        global_min = min;
-    }
-    global_min
-}
 
-// Update global maximum response time based on local resposne time.
-pub fn update_max_response_time(mut global_max: usize, max: usize) -> usize {
-    if global_max < max {
-        global_max = max;
-    }
-    global_max
-}
+    // Optionally prepares a table of response times split by class of status code.
+    pub fn fmt_status_class_response_times(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if !self.display_status_codes {
+            return Ok(());
+        }
 
-/// Get the response time that a certain number of percent of the requests finished within.
-fn calculate_response_time_percentile(
-    response_times: &BTreeMap<usize, usize>,
-    total_requests: usize,
-    min: usize,
-    max: usize,
-    percent: f32,
-) -> usize {
-    let percentile_request = (total_requests as f32 * percent).round() as usize;
-    debug!(
-        "percentile: {}, request {} of total {}",
-        percent, percentile_request, total_requests
-    );
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(fmt, " Response times by status code (in ms):")?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<6} | {:<8} | {:<6} | {:<6} | {:<6} | 99%",
+            "Name", "Status", "# reqs", "Avg", "50%", "95%"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        let mut aggregated_histograms: BTreeMap<u16, GooseHistogram> = BTreeMap::new();
+        for (request_key, request) in self.requests.iter().sorted() {
+            for (index, (status_class, histogram)) in
+                request.status_class_histograms.iter().enumerate()
+            {
+                // Only display the name of the request once.
+                let name = if index == 0 {
+                    util::truncate_string(request_key, 23)
+                } else {
+                    "".to_string()
+                };
+                writeln!(
+                    fmt,
+                    " {:<23} | {}",
+                    name,
+                    format_status_class_response_times(*status_class, histogram)
+                )?;
+                aggregated_histograms
+                    .entry(*status_class)
+                    .or_insert_with(|| histogram.new_with_same_bounds())
+                    .merge(histogram);
+            }
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        for (index, (status_class, histogram)) in aggregated_histograms.iter().enumerate() {
+            let name = if index == 0 { "Aggregated" } else { "" };
+            writeln!(
+                fmt,
+                " {:<23} | {}",
+                name,
+                format_status_class_response_times(*status_class, histogram)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of retried requests, only if any requests were retried.
+    pub fn fmt_retries(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aggregate_retry_count: usize = self.requests.values().map(|r| r.retry_count).sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_retry_count == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(fmt, " {:<23} | {:<14} ", "Name", "# retries")?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (request_key, request) in self.requests.iter().sorted() {
+            if request.retry_count > 0 {
+                writeln!(
+                    fmt,
+                    " {:<23} | {:<14}",
+                    util::truncate_string(request_key, 23),
+                    request.retry_count.to_formatted_string(&Locale::en),
+                )?;
+            }
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<14} ",
+            "Aggregated",
+            aggregate_retry_count.to_formatted_string(&Locale::en)
+        )?;
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of requests that exceeded the latency budget of their
+    // task, only if any did.
+    pub fn fmt_over_budget(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_request_counts(fmt, "# over budget", |request| request.over_budget_count)
+    }
+
+    // Prepares a table of how many of each request were counted by `count`, only if any
+    // were, shared by the tables of requests that failed a response time check.
+    fn fmt_request_counts(
+        &self,
+        fmt: &mut fmt::Formatter<'_>,
+        header: &str,
+        count: fn(&GooseRequest) -> usize,
+    ) -> fmt::Result {
+        let aggregate_count: usize = self.requests.values().map(count).sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_count == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(fmt, " {:<23} | {:<14} ", "Name", header)?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (request_key, request) in self.requests.iter().sorted() {
+            if count(request) > 0 {
+                writeln!(
+                    fmt,
+                    " {:<23} | {:<14}",
+                    util::truncate_string(request_key, 23),
+                    count(request).to_formatted_string(&Locale::en),
+                )?;
+            }
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<14} ",
+            "Aggregated",
+            aggregate_count.to_formatted_string(&Locale::en)
+        )?;
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of the slowest individual requests, only if tracked
+    // with `--show-slowest` and percentiles are being displayed.
+    pub fn fmt_slowest(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if !self.display_percentile || self.slowest.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<44} | {:<6} | {:<10} | {:<9}",
+            "Slowest requests", "Status", "Time (ms)", "At"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for request in self.slowest() {
+            writeln!(
+                fmt,
+                " {:<44} | {:<6} | {:<10} | {}",
+                util::truncate_string(&format!("{:?} {}", request.method, request.url), 44),
+                request.status_code,
+                request.response_time.to_formatted_string(&Locale::en),
+                format_elapsed(request.elapsed),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of the tags tasks marked requests as failures with, only
+    // if any were.
+    pub fn fmt_failure_tags(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut failure_tags: BTreeMap<&str, usize> = BTreeMap::new();
+        for request in self.requests.values() {
+            for (tag, count) in &request.failure_tags {
+                *failure_tags.entry(tag).or_insert(0) += count;
+            }
+        }
+        // If there's nothing to display, exit immediately.
+        if failure_tags.is_empty() {
+            return Ok(());
+        }
+        let aggregate_fail_count: usize = self.requests.values().map(|r| r.fail_count).sum();
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<14} | {:<14}",
+            "Failure tag", "# fails", "% of fails"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (tag, count) in &failure_tags {
+            writeln!(
+                fmt,
+                " {:<23} | {:<14} | {:.1}",
+                util::truncate_string(tag, 23),
+                count.to_formatted_string(&Locale::en),
+                *count as f32 / aggregate_fail_count.max(1) as f32 * 100.0,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of why requests failed, only if any failed.
+    pub fn fmt_errors(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut error_categories: BTreeMap<GooseErrorCategory, usize> = BTreeMap::new();
+        for request in self.requests.values() {
+            for (category, count) in &request.error_categories {
+                *error_categories.entry(*category).or_insert(0) += count;
+            }
+        }
+        let aggregate_fail_count: usize = error_categories.values().sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_fail_count == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<14} | {:<14}",
+            "Error category", "# fails", "% of fails"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (category, count) in &error_categories {
+            writeln!(
+                fmt,
+                " {:<23} | {:<14} | {:.1}",
+                category.to_string(),
+                count.to_formatted_string(&Locale::en),
+                *count as f32 / aggregate_fail_count as f32 * 100.0,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of custom metrics, only if any were recorded.
+    pub fn fmt_custom_metrics(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if self.custom_metrics.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<10} | {:<12} | {:<12} | {:<12}",
+            "Custom metric", "# count", "Min", "Max", "Average"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for metric in self.custom_metrics.values() {
+            writeln!(
+                fmt,
+                " {:<23} | {:<10} | {:<12.2} | {:<12.2} | {:<12.2}",
+                util::truncate_string(&metric.name, 23),
+                metric.count.to_formatted_string(&Locale::en),
+                metric.min,
+                metric.max,
+                metric.average(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of requests that were faster than the minimum response
+    // time of their task, only if any were.
+    pub fn fmt_too_fast(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_request_counts(fmt, "# too fast", |request| request.too_fast_count)
+    }
+
+    // Optionally prepares a table comparing the server time reported in Server-Timing
+    // headers with the response time measured by Goose, only if any were reported.
+    pub fn fmt_server_timing(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aggregate_server_timing_counter: usize = self
+            .requests
+            .values()
+            .map(|r| r.server_timing_counter)
+            .sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_server_timing_counter == 0 {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<10} | {:<10} | {:<10} | {:<10}",
+            "Name", "# timed", "Server avg", "Client avg", "Overhead"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        let mut aggregate_server_time = 0;
+        let mut aggregate_response_time = 0;
+        for (request_key, request) in self.requests.iter().sorted() {
+            if request.server_timing_counter > 0 {
+                aggregate_server_time += request.total_server_time;
+                aggregate_response_time += request.total_server_timed_response_time;
+                fmt_server_timing_row(
+                    fmt,
+                    &util::truncate_string(request_key, 23),
+                    request.server_timing_counter,
+                    request.total_server_time,
+                    request.total_server_timed_response_time,
+                )?;
+            }
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        fmt_server_timing_row(
+            fmt,
+            "Aggregated",
+            aggregate_server_timing_counter,
+            aggregate_server_time,
+            aggregate_response_time,
+        )
+    }
+
+    // Optionally prepares a table comparing each host, only when load testing more than one
+    // `--host`.
+    pub fn fmt_hosts(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If there's nothing to display, exit immediately.
+        if self.hosts.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<8} | {:<13} | {:<8} | {:<6} | {:<5}",
+            "Host", "# reqs", "# fails", "Avg (ms)", "Median", "Max"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (host, request) in &self.hosts {
+            let total_count = request.success_count + request.fail_count;
+            let fail_percent = if request.fail_count > 0 {
+                request.fail_count as f32 / total_count as f32 * 100.0
+            } else {
+                0.0
+            };
+            let response_time_counter = request.response_time_counter.max(1);
+            writeln!(
+                fmt,
+                " {:<23} | {:<8} | {:<13} | {:<8} | {:<6} | {:<5}",
+                util::truncate_string(host, 23),
+                total_count.to_formatted_string(&Locale::en),
+                format!(
+                    "{} ({:.1}%)",
+                    request.fail_count.to_formatted_string(&Locale::en),
+                    fail_percent
+                ),
+                request.total_response_time / response_time_counter,
+                calculate_response_time_percentile(
+                    &request.response_time_histogram,
+                    request.min_response_time,
+                    request.max_response_time,
+                    0.5
+                ),
+                request.max_response_time,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of bytes received, only if any response bodies were received.
+    pub fn fmt_bytes(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aggregate_bytes: u64 = self.requests.values().map(|r| r.bytes).sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_bytes == 0 {
+            return Ok(());
+        }
+
+        // Avoid dividing by zero if statistics are displayed in the first second.
+        let duration = self.duration.max(1) as u64;
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<16} | {:<16} ",
+            "Name", "bytes", "bytes/s"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (request_key, request) in self.requests.iter().sorted() {
+            writeln!(
+                fmt,
+                " {:<23} | {:<16} | {:<16}",
+                util::truncate_string(request_key, 23),
+                request.bytes.to_formatted_string(&Locale::en),
+                (request.bytes / duration).to_formatted_string(&Locale::en),
+            )?;
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<16} | {:<16} ",
+            "Aggregated",
+            aggregate_bytes.to_formatted_string(&Locale::en),
+            (aggregate_bytes / duration).to_formatted_string(&Locale::en),
+        )?;
+
+        Ok(())
+    }
+
+    // Optionally prepares a table of bytes sent, only if any request bodies were sent.
+    pub fn fmt_bytes_sent(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aggregate_bytes_sent: u64 = self.requests.values().map(|r| r.bytes_sent).sum();
+        // If there's nothing to display, exit immediately.
+        if aggregate_bytes_sent == 0 {
+            return Ok(());
+        }
+
+        // Avoid dividing by zero if statistics are displayed in the first second.
+        let duration = self.duration.max(1) as u64;
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<16} | {:<16} ",
+            "Name", "bytes sent", "bytes sent/s"
+        )?;
+        writeln!(
+            fmt,
+            " ----------------------------------------------------------------------------- "
+        )?;
+        for (request_key, request) in self.requests.iter().sorted() {
+            if request.bytes_sent > 0 {
+                writeln!(
+                    fmt,
+                    " {:<23} | {:<16} | {:<16}",
+                    util::truncate_string(request_key, 23),
+                    request.bytes_sent.to_formatted_string(&Locale::en),
+                    (request.bytes_sent / duration).to_formatted_string(&Locale::en),
+                )?;
+            }
+        }
+        writeln!(
+            fmt,
+            "-------------------------------------------------------------------------------"
+        )?;
+        writeln!(
+            fmt,
+            " {:<23} | {:<16} | {:<16} ",
+            "Aggregated",
+            aggregate_bytes_sent.to_formatted_string(&Locale::en),
+            (aggregate_bytes_sent / duration).to_formatted_string(&Locale::en),
+        )?;
+
+        Ok(())
+    }
+
+    /// Writes a self-contained HTML report of all statistics from a completed load
+    /// test to the specified path.
+    ///
+    /// This is invoked automatically when the load test finishes if `--report-file`
+    /// is set.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("ExampleUsers")
+    ///             .register_task(task!(example_task))
+    ///         )
+    ///         .execute()?
+    ///         .write_html_report(Path::new("report.html"))?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write_html_report(&self, path: &Path) -> Result<(), io::Error> {
+        info!("writing html report to: {}", path.display());
+
+        let mut tables = String::new();
+        for table in self.report_tables() {
+            tables.push_str(&format!("<h2>{}</h2>\n<table>\n<tr>", table.title));
+            for column in &table.header {
+                tables.push_str(&format!("<th>{}</th>", html_escape(column)));
+            }
+            tables.push_str("</tr>\n");
+            for row in &table.rows {
+                tables.push_str("<tr>");
+                for value in row {
+                    tables.push_str(&format!("<td>{}</td>", html_escape(value)));
+                }
+                tables.push_str("</tr>\n");
+            }
+            if let Some(aggregate) = &table.aggregate {
+                tables.push_str("<tr class=\"aggregate\">");
+                for value in aggregate {
+                    tables.push_str(&format!("<td>{}</td>", html_escape(value)));
+                }
+                tables.push_str("</tr>\n");
+            }
+            tables.push_str("</table>\n");
+        }
+
+        let mut file = File::create(path)?;
+        write!(
+            file,
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Goose Load Test Report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #333; }}
+table {{ border-collapse: collapse; margin-bottom: 2em; }}
+th, td {{ border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: right; }}
+th:first-child, td:first-child {{ text-align: left; }}
+th {{ background: #eee; }}
+tr.aggregate {{ font-weight: bold; }}
+svg.throughput {{ margin-bottom: 2em; }}
+svg.throughput polyline {{ fill: none; stroke-width: 2; }}
+</style>
+</head>
+<body>
+<h1>Goose Load Test Report</h1>
+<ul>
+<li>Duration: {} seconds</li>
+<li>Users: {}</li>
+<li>Total requests: {}</li>
+</ul>
+{}{}</body>
+</html>
+"#,
+            self.duration.to_formatted_string(&Locale::en),
+            self.users.to_formatted_string(&Locale::en),
+            self.total_requests().to_formatted_string(&Locale::en),
+            self.html_throughput_chart(),
+            tables
+        )?;
+
+        Ok(())
+    }
+
+    // Prepares an inline SVG chart of requests and failures per second over the course
+    // of the load test, for the HTML report. Empty if there's no throughput to chart.
+    fn html_throughput_chart(&self) -> String {
+        if self.throughput.is_empty() {
+            return String::new();
+        }
+        const WIDTH: f64 = 600.0;
+        const HEIGHT: f64 = 200.0;
+        const MARGIN: f64 = 40.0;
+
+        let interval = self.throughput_interval.max(1) as f64;
+        let max_rps = self
+            .throughput
+            .iter()
+            .map(|bucket| bucket.requests as f64 / interval)
+            .fold(0.0, f64::max)
+            .max(1.0);
+        // A single bucket is drawn as a flat line across the chart.
+        let step = WIDTH / (self.throughput.len().max(2) - 1) as f64;
+        let points = |count: fn(&GooseThroughput) -> usize| {
+            let mut points: Vec<String> = self
+                .throughput
+                .iter()
+                .enumerate()
+                .map(|(index, bucket)| {
+                    let rps = count(bucket) as f64 / interval;
+                    format!(
+                        "{:.1},{:.1}",
+                        MARGIN + index as f64 * step,
+                        MARGIN + HEIGHT - rps / max_rps * HEIGHT
+                    )
+                })
+                .collect();
+            if points.len() == 1 {
+                let y = points[0].split(',').nth(1).unwrap_or_default().to_string();
+                points.push(format!("{:.1},{}", MARGIN + WIDTH, y));
+            }
+            points.join(" ")
+        };
+        let last_second = self.throughput.last().map_or(0, |bucket| bucket.second);
 
-    let mut total_count: usize = 0;
+        format!(
+            r##"<h2>Requests per second</h2>
+<svg class="throughput" xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+<line x1="{margin}" y1="{bottom}" x2="{right}" y2="{bottom}" stroke="#999"/>
+<line x1="{margin}" y1="{margin}" x2="{margin}" y2="{bottom}" stroke="#999"/>
+<text x="{label}" y="{margin}" text-anchor="end" font-size="12">{max_rps:.0}</text>
+<text x="{label}" y="{bottom}" text-anchor="end" font-size="12">0</text>
+<text x="{margin}" y="{axis}" font-size="12">0s</text>
+<text x="{right}" y="{axis}" text-anchor="end" font-size="12">{last_second}s</text>
+<polyline class="requests" stroke="#36c" points="{requests}"/>
+<polyline class="fails" stroke="#c33" points="{fails}"/>
+</svg>
+"##,
+            width = WIDTH + MARGIN * 2.0,
+            height = HEIGHT + MARGIN * 2.0,
+            margin = MARGIN,
+            bottom = MARGIN + HEIGHT,
+            right = MARGIN + WIDTH,
+            label = MARGIN - 5.0,
+            axis = MARGIN + HEIGHT + 15.0,
+            max_rps = max_rps,
+            last_second = last_second,
+            requests = points(|bucket| bucket.requests),
+            fails = points(|bucket| bucket.fails),
+        )
+    }
 
-    for (value, counter) in response_times {
-        total_count += counter;
-        if total_count >= percentile_request {
-            if *value < min {
-                return min;
-            } else if *value > max {
-                return max;
-            } else {
-                return *value;
+    /// Writes a GitHub-flavored markdown report of all statistics from a completed
+    /// load test to the specified path, suitable for pasting into a pull request
+    /// comment or a CI job summary.
+    ///
+    /// This is invoked automatically when the load test finishes if `--report-file`
+    /// is set to a file name ending in `.md`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("ExampleUsers")
+    ///             .register_task(task!(example_task))
+    ///         )
+    ///         .execute()?
+    ///         .write_markdown_report(Path::new("report.md"))?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write_markdown_report(&self, path: &Path) -> Result<(), io::Error> {
+        info!("writing markdown report to: {}", path.display());
+
+        let total_requests = self.total_requests();
+        let total_fails: usize = self.requests.values().map(|r| r.fail_count).sum();
+
+        let mut file = File::create(path)?;
+        writeln!(file, "# Goose Load Test Report\n")?;
+        writeln!(
+            file,
+            " - Duration: {} seconds",
+            self.duration.to_formatted_string(&Locale::en)
+        )?;
+        writeln!(
+            file,
+            " - Users: {}",
+            self.users.to_formatted_string(&Locale::en)
+        )?;
+        writeln!(
+            file,
+            " - Total requests: {}",
+            total_requests.to_formatted_string(&Locale::en)
+        )?;
+        writeln!(
+            file,
+            " - Failures: {}",
+            format_fails(total_fails, total_requests)
+        )?;
+
+        for table in self.report_tables() {
+            writeln!(file, "\n## {}\n", table.title)?;
+            writeln!(file, "| {} |", markdown_row(&table.header))?;
+            writeln!(
+                file,
+                "|{}",
+                // Left-align the name column, right-align all values.
+                table
+                    .header
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| if i == 0 { " :--- |" } else { " ---: |" })
+                    .join("")
+            )?;
+            for row in &table.rows {
+                writeln!(file, "| {} |", markdown_row(row))?;
+            }
+            if let Some(aggregate) = &table.aggregate {
+                let aggregate: Vec<String> =
+                    aggregate.iter().map(|v| format!("**{}**", v)).collect();
+                writeln!(file, "| {} |", markdown_row(&aggregate))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Summarizes all statistics from a completed load test, including the percentiles
+    /// configured with `--stats-percentiles`.
+    pub fn summary(&self) -> GooseStatsSummary {
+        let percentiles = self.get_percentiles();
+        let mut aggregated = GooseRequest::new(
+            "Aggregated",
+            GooseMethod::GET,
+            self.hash,
+            new_aggregate_histogram(self.requests.values()),
+        );
+        let mut requests = Vec::new();
+        for (_, request) in self.requests.iter().sorted() {
+            aggregated
+                .response_time_histogram
+                .merge(&request.response_time_histogram);
+            aggregated.min_response_time =
+                update_min_response_time(aggregated.min_response_time, request.min_response_time);
+            aggregated.max_response_time =
+                update_max_response_time(aggregated.max_response_time, request.max_response_time);
+            aggregated.total_response_time += request.total_response_time;
+            aggregated.response_time_counter += request.response_time_counter;
+            aggregated.success_count += request.success_count;
+            aggregated.fail_count += request.fail_count;
+            aggregated.retry_count += request.retry_count;
+            aggregated.over_budget_count += request.over_budget_count;
+            aggregated.too_fast_count += request.too_fast_count;
+            aggregated.bytes += request.bytes;
+            aggregated.bytes_sent += request.bytes_sent;
+            aggregated.server_timing_counter += request.server_timing_counter;
+            aggregated.total_server_time += request.total_server_time;
+            aggregated.total_server_timed_response_time += request.total_server_timed_response_time;
+            for (category, count) in &request.error_categories {
+                *aggregated.error_categories.entry(*category).or_insert(0) += count;
+            }
+            for (tag, count) in &request.failure_tags {
+                *aggregated.failure_tags.entry(tag.to_string()).or_insert(0) += count;
+            }
+            for (status_code, count) in &request.status_code_counts {
+                *aggregated
+                    .status_code_counts
+                    .entry(*status_code)
+                    .or_insert(0) += count;
+            }
+            requests.push(GooseRequestSummary::new(
+                Some(request.method.clone()),
+                &request.path,
+                request,
+                &percentiles,
+            ));
+        }
+
+        GooseStatsSummary {
+            version: STATS_SUMMARY_VERSION,
+            hash: self.hash,
+            duration: self.duration,
+            users: self.users,
+            canceled: self.canceled,
+            lost_workers: self.lost_workers,
+            throughput_interval: self.throughput_interval.max(1),
+            throughput: self.throughput.clone(),
+            pauses: self.pauses.clone(),
+            slowest: self.slowest(),
+            requests,
+            aggregated: GooseRequestSummary::new(None, "Aggregated", &aggregated, &percentiles),
+            hosts: self
+                .hosts
+                .iter()
+                .map(|(host, request)| GooseRequestSummary::new(None, host, request, &percentiles))
+                .collect(),
+        }
+    }
+
+    /// Writes a JSON summary of all statistics from a completed load test to the
+    /// specified path. The summary is much smaller than `--stats-log-file`, and follows
+    /// the versioned `GooseStatsSummary` schema so it can be compared across load tests.
+    ///
+    /// This is invoked automatically when the load test finishes if
+    /// `--stats-summary-file` is set.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    /// use std::path::Path;
+    ///
+    /// fn main() -> Result<(), GooseError> {
+    ///     GooseAttack::initialize()?
+    ///         .register_taskset(taskset!("ExampleUsers")
+    ///             .register_task(task!(example_task))
+    ///         )
+    ///         .execute()?
+    ///         .write_json_summary(Path::new("summary.json"))?;
+    ///
+    ///     Ok(())
+    /// }
+    ///
+    /// async fn example_task(user: &GooseUser) -> GooseTaskResult {
+    ///     let _goose = user.get("/").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write_json_summary(&self, path: &Path) -> Result<(), io::Error> {
+        info!("writing json summary to: {}", path.display());
+
+        let mut file = File::create(path)?;
+        serde_json::to_writer_pretty(&mut file, &self.summary())?;
+        writeln!(file)?;
+
+        Ok(())
+    }
+
+    /// Total number of requests made during the load test.
+    fn total_requests(&self) -> usize {
+        self.requests
+            .values()
+            .map(|request| request.success_count + request.fail_count)
+            .sum()
+    }
+
+    /// Prepares all tables of statistics included in reports.
+    fn report_tables(&self) -> Vec<ReportTable> {
+        let mut tables = Vec::new();
+        // If there's nothing to report, return no tables.
+        if self.requests.is_empty() {
+            return tables;
+        }
+
+        // Avoid dividing by zero if the load test ran for less than a second.
+        let duration = if self.duration > 0 { self.duration } else { 1 };
+
+        let mut requests = ReportTable {
+            title: "Requests".to_string(),
+            header: ["Name", "# reqs", "# fails", "req/s", "fail/s"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: Vec::new(),
+            aggregate: None,
+        };
+        let mut response_times = ReportTable {
+            title: "Response times".to_string(),
+            header: ["Name", "Avg (ms)", "Min", "Max", "Median"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: Vec::new(),
+            aggregate: None,
+        };
+        let report_percentiles = self.get_percentiles();
+        let mut percentile_header = vec!["Name".to_string()];
+        for percent in report_percentiles.iter() {
+            percentile_header.push(format_percentile(*percent));
+        }
+        let mut percentiles = ReportTable {
+            title: "Slowest page load within specified percentile of requests (in ms)".to_string(),
+            header: percentile_header,
+            rows: Vec::new(),
+            aggregate: None,
+        };
+        let mut status_codes = ReportTable {
+            title: "Status codes".to_string(),
+            header: vec!["Name".to_string(), "Status codes".to_string()],
+            rows: Vec::new(),
+            aggregate: None,
+        };
+
+        let mut aggregate_fail_count = 0;
+        let mut aggregate_total_count = 0;
+        let mut aggregate_total_response_time: usize = 0;
+        let mut aggregate_response_time_counter: usize = 0;
+        let mut aggregate_min_response_time: usize = 0;
+        let mut aggregate_max_response_time: usize = 0;
+        let mut aggregate_status_code_counts: HashMap<u16, usize> = HashMap::new();
+        let mut aggregate_histogram = new_aggregate_histogram(self.requests.values());
+        for (request_key, request) in self.requests.iter().sorted() {
+            let total_count = request.success_count + request.fail_count;
+            requests.rows.push(vec![
+                request_key.to_string(),
+                total_count.to_formatted_string(&Locale::en),
+                format_fails(request.fail_count, total_count),
+                (total_count / duration).to_formatted_string(&Locale::en),
+                (request.fail_count / duration).to_formatted_string(&Locale::en),
+            ]);
+            aggregate_total_count += total_count;
+            aggregate_fail_count += request.fail_count;
+
+            aggregate_total_response_time += request.total_response_time;
+            aggregate_response_time_counter += request.response_time_counter;
+            aggregate_min_response_time =
+                update_min_response_time(aggregate_min_response_time, request.min_response_time);
+            aggregate_max_response_time =
+                update_max_response_time(aggregate_max_response_time, request.max_response_time);
+
+            response_times.rows.push(vec![
+                request_key.to_string(),
+                (request.total_response_time / request.response_time_counter.max(1))
+                    .to_formatted_string(&Locale::en),
+                request.min_response_time.to_formatted_string(&Locale::en),
+                request.max_response_time.to_formatted_string(&Locale::en),
+                calculate_response_time_percentile(
+                    &request.response_time_histogram,
+                    request.min_response_time,
+                    request.max_response_time,
+                    0.5,
+                )
+                .to_formatted_string(&Locale::en),
+            ]);
+
+            aggregate_histogram.merge(&request.response_time_histogram);
+            let mut row = vec![request_key.to_string()];
+            for percent in report_percentiles.iter() {
+                row.push(
+                    calculate_response_time_percentile(
+                        &request.response_time_histogram,
+                        request.min_response_time,
+                        request.max_response_time,
+                        *percent,
+                    )
+                    .to_formatted_string(&Locale::en),
+                );
+            }
+            percentiles.rows.push(row);
+
+            for (status_code, count) in &request.status_code_counts {
+                *aggregate_status_code_counts
+                    .entry(*status_code)
+                    .or_insert(0) += count;
+            }
+            status_codes.rows.push(vec![
+                request_key.to_string(),
+                format_status_codes(&request.status_code_counts),
+            ]);
+        }
+
+        if self.requests.len() > 1 {
+            requests.aggregate = Some(vec![
+                "Aggregated".to_string(),
+                aggregate_total_count.to_formatted_string(&Locale::en),
+                format_fails(aggregate_fail_count, aggregate_total_count),
+                (aggregate_total_count / duration).to_formatted_string(&Locale::en),
+                (aggregate_fail_count / duration).to_formatted_string(&Locale::en),
+            ]);
+            response_times.aggregate = Some(vec![
+                "Aggregated".to_string(),
+                (aggregate_total_response_time / aggregate_response_time_counter.max(1))
+                    .to_formatted_string(&Locale::en),
+                aggregate_min_response_time.to_formatted_string(&Locale::en),
+                aggregate_max_response_time.to_formatted_string(&Locale::en),
+                calculate_response_time_percentile(
+                    &aggregate_histogram,
+                    aggregate_min_response_time,
+                    aggregate_max_response_time,
+                    0.5,
+                )
+                .to_formatted_string(&Locale::en),
+            ]);
+            let mut row = vec!["Aggregated".to_string()];
+            for percent in report_percentiles.iter() {
+                row.push(
+                    calculate_response_time_percentile(
+                        &aggregate_histogram,
+                        aggregate_min_response_time,
+                        aggregate_max_response_time,
+                        *percent,
+                    )
+                    .to_formatted_string(&Locale::en),
+                );
             }
+            percentiles.aggregate = Some(row);
         }
+        status_codes.aggregate = Some(vec![
+            "Aggregated".to_string(),
+            format_status_codes(&aggregate_status_code_counts),
+        ]);
+
+        tables.push(requests);
+        tables.push(response_times);
+        tables.push(percentiles);
+        // Status codes are only collected if enabled.
+        if self.display_status_codes {
+            tables.push(status_codes);
+        }
+        tables
+    }
+}
+
+impl fmt::Display for GooseStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        // Formats from zero to nine tables of data, depending on what data is contained
+        // and which contained flags are set.
+        self.fmt_requests(fmt)?;
+        self.fmt_response_times(fmt)?;
+        self.fmt_percentiles(fmt)?;
+        self.fmt_status_codes(fmt)?;
+        self.fmt_status_class_response_times(fmt)?;
+        self.fmt_server_timing(fmt)?;
+        self.fmt_hosts(fmt)?;
+        self.fmt_errors(fmt)?;
+        self.fmt_failure_tags(fmt)?;
+        self.fmt_retries(fmt)?;
+        self.fmt_over_budget(fmt)?;
+        self.fmt_too_fast(fmt)?;
+        self.fmt_bytes(fmt)?;
+        self.fmt_bytes_sent(fmt)?;
+        self.fmt_slowest(fmt)?;
+        self.fmt_custom_metrics(fmt)
+    }
+}
+
+/// Formats a row of the server timing table, comparing the average server time with the
+/// average response time measured by Goose, in milliseconds. The overhead is the time
+/// spent outside of the server, for example on the network or in queues.
+fn fmt_server_timing_row(
+    fmt: &mut fmt::Formatter<'_>,
+    name: &str,
+    count: usize,
+    total_server_time: usize,
+    total_response_time: usize,
+) -> fmt::Result {
+    let server_time = total_server_time as f32 / count as f32;
+    let response_time = total_response_time as f32 / count as f32;
+    writeln!(
+        fmt,
+        " {:<23} | {:<10} | {:<10.2} | {:<10.2} | {:<10.2}",
+        name,
+        count.to_formatted_string(&Locale::en),
+        server_time,
+        response_time,
+        response_time - server_time,
+    )
+}
+
+/// Formats seconds since the start of the load test as hours, minutes and seconds, for
+/// example `00:05:34`.
+fn format_elapsed(elapsed: usize) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        elapsed / 3_600,
+        elapsed % 3_600 / 60,
+        elapsed % 60
+    )
+}
+
+/// Formats the response times of one class of status codes, for example `5xx`, or `none`
+/// for requests that failed without a response.
+fn format_status_class_response_times(status_class: u16, histogram: &GooseHistogram) -> String {
+    let status = if status_class == 0 {
+        "none".to_string()
+    } else {
+        format!("{}xx", status_class)
+    };
+    format!(
+        "{:<6} | {:<8} | {:<6.1} | {:<6} | {:<6} | {}",
+        status,
+        histogram.len().to_formatted_string(&Locale::en),
+        histogram.mean(),
+        histogram.value_at_percentile(0.5),
+        histogram.value_at_percentile(0.95),
+        histogram.value_at_percentile(0.99),
+    )
+}
+
+/// Formats a failure count along with the percentage of requests that failed,
+/// compressing 100.0 and 0.0 to 100 and 0 respectively.
+fn format_fails(fail_count: usize, total_count: usize) -> String {
+    let fail_percent = if fail_count > 0 {
+        fail_count as f32 / total_count as f32 * 100.0
+    } else {
+        0.0
+    };
+    if fail_percent as usize == 100 || fail_percent as usize == 0 {
+        format!(
+            "{} ({}%)",
+            fail_count.to_formatted_string(&Locale::en),
+            fail_percent as usize
+        )
+    } else {
+        format!(
+            "{} ({:.1}%)",
+            fail_count.to_formatted_string(&Locale::en),
+            fail_percent
+        )
+    }
+}
+
+/// Parses a comma-separated list of percentiles, for example `50,90,95,99.9`, into a
+/// vector of fractions. Returns an explanation of the problem if any value is not a
+/// number between 0 and 100 exclusive.
+pub fn parse_percentiles(value: &str) -> Result<Vec<f32>, String> {
+    let mut percentiles = Vec::new();
+    for percentile in value.split(',') {
+        match percentile.trim().parse::<f32>() {
+            Ok(p) if p > 0.0 && p < 100.0 => percentiles.push(p / 100.0),
+            _ => {
+                return Err(format!(
+                    "--stats-percentiles must be a comma-separated list of numbers between 0 and 100 exclusive, invalid value: {}",
+                    percentile
+                ))
+            }
+        }
+    }
+    Ok(percentiles)
+}
+
+/// Formats a percentile for display, for example 0.999 is displayed as `99.9%`.
+fn format_percentile(percent: f32) -> String {
+    format!("{}%", (percent * 10_000.0).round() / 100.0)
+}
+
+/// Formats status code counts, for example `10 [200], 2 [404]`.
+fn format_status_codes(status_code_counts: &HashMap<u16, usize>) -> String {
+    status_code_counts
+        .iter()
+        .sorted()
+        .map(|(status_code, count)| {
+            format!(
+                "{} [{}]",
+                count.to_formatted_string(&Locale::en),
+                status_code
+            )
+        })
+        .join(", ")
+}
+
+/// Formats a row of values as the contents of a markdown table row, escaping pipes.
+fn markdown_row(values: &[String]) -> String {
+    values.iter().map(|v| v.replace('|', "\\|")).join(" | ")
+}
+
+/// Escapes characters that have special meaning in HTML.
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Creates an empty histogram to aggregate response times from several requests, with the
+/// bounds configured with `--stats-max-response-time` and `--stats-significant-figures`.
+pub fn new_aggregate_histogram<'a>(
+    mut requests: impl Iterator<Item = &'a GooseRequest>,
+) -> GooseHistogram {
+    requests
+        .next()
+        .map_or_else(GooseHistogram::default, |request| {
+            request.response_time_histogram.new_with_same_bounds()
+        })
+}
+
+// Update global minimum response time based on local resposne time.
+pub fn update_min_response_time(mut global_min: usize, min: usize) -> usize {
+    if global_min == 0 || (min > 0 && min < global_min) {
+        global_min = min;
+    }
+    global_min
+}
+
+// Update global maximum response time based on local resposne time.
+pub fn update_max_response_time(mut global_max: usize, max: usize) -> usize {
+    if global_max < max {
+        global_max = max;
+    }
+    global_max
+}
+
+/// Correct for coordinated omission by back-filling synthetic response times.
+///
+/// Goose is a closed-loop load generator: a GooseUser waits for each response before
+/// making its next request. When the server stalls, the requests the user would have
+/// made during the stall are never sent, hiding their latency from the statistics. When
+/// `response_time` is longer than `expected_interval`, this records the response times
+/// those missing requests would have seen: `response_time - expected_interval`,
+/// `response_time - 2 * expected_interval`, and so on while they're at least
+/// `expected_interval`. Nothing is back-filled if `expected_interval` is 0.
+pub fn correct_coordinated_omission(
+    histogram: &mut GooseHistogram,
+    response_time: u64,
+    expected_interval: u64,
+) {
+    if expected_interval == 0 {
+        return;
+    }
+    let mut missing_response_time = response_time.saturating_sub(expected_interval);
+    while missing_response_time >= expected_interval {
+        histogram.record(missing_response_time);
+        missing_response_time -= expected_interval;
+    }
+}
+
+/// Get the response time that a certain number of percent of the requests finished within.
+///
+/// The histogram tracks response times with a fixed precision, so the result is limited
+/// to the actual fastest and slowest response times seen.
+fn calculate_response_time_percentile(
+    histogram: &GooseHistogram,
+    min: usize,
+    max: usize,
+    percent: f32,
+) -> usize {
+    if histogram.is_empty() {
+        return 0;
+    }
+    let value = histogram.value_at_percentile(percent) as usize;
+    debug!(
+        "percentile: {}, value {} of total {}",
+        percent,
+        value,
+        histogram.len()
+    );
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
     }
-    0
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn custom_metrics() {
+        let mut stats = GooseStats::default();
+        stats.record_custom_metric("items", 3.0);
+        stats.record_custom_metric("items", -1.5);
+        stats.record_custom_metric("items", 10.0);
+        stats.record_custom_metric("score", 0.5);
+
+        let items = &stats.custom_metrics["items"];
+        assert_eq!(items.name, "items");
+        assert_eq!(items.count, 3);
+        assert_eq!(items.min, -1.5);
+        assert_eq!(items.max, 10.0);
+        assert_eq!(items.total, 11.5);
+        assert!((items.average() - 11.5 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.custom_metrics["score"].count, 1);
+        assert_eq!(stats.custom_metrics["score"].min, 0.5);
+
+        // Metrics recorded elsewhere, such as by a Gaggle worker, can be merged.
+        let mut merged = GooseCustomMetric::new("items");
+        merged.merge(&GooseCustomMetric::new("items"));
+        assert_eq!(merged.count, 0);
+        merged.merge(&stats.custom_metrics["items"]);
+        assert_eq!(merged, stats.custom_metrics["items"]);
+        let mut worker = GooseCustomMetric::new("items");
+        worker.record(20.0);
+        merged.merge(&worker);
+        assert_eq!(merged.count, 4);
+        assert_eq!(merged.min, -1.5);
+        assert_eq!(merged.max, 20.0);
+        assert_eq!(merged.total, 31.5);
+
+        // Metrics are printed in their own table.
+        let printed = stats.to_string();
+        assert!(printed.contains("Custom metric"));
+        assert!(printed.contains(" items "));
+
+        assert_eq!(GooseCustomMetric::new("empty").average(), 0.0);
+    }
+
+    #[test]
+    fn percentiles() {
+        assert_eq!(parse_percentiles("50").unwrap(), vec![0.5]);
+        assert_eq!(
+            parse_percentiles("50, 90,95,99.9").unwrap(),
+            vec![0.5, 0.9, 0.95, 0.999]
+        );
+        assert!(parse_percentiles("0").is_err());
+        assert!(parse_percentiles("100").is_err());
+        assert!(parse_percentiles("50,").is_err());
+        assert!(parse_percentiles("foo").is_err());
+
+        assert_eq!(format_percentile(0.5), "50%");
+        assert_eq!(format_percentile(0.999), "99.9%");
+        assert_eq!(format_percentile(0.9999), "99.99%");
+
+        // Default percentiles are used if none are configured.
+        let mut stats = GooseStats::default();
+        assert_eq!(stats.get_percentiles(), DEFAULT_PERCENTILES.to_vec());
+        stats.percentiles = vec![0.9, 0.95];
+        assert_eq!(stats.get_percentiles(), vec![0.9, 0.95]);
+
+        // Running statistics only include percentiles if enabled, and then only compact ones.
+        assert!(!format!("{}", stats).contains("Slowest page load"));
+        stats.running_percentiles = true;
+        let running = format!("{}", stats);
+        assert!(running.contains(" | 50%    | 95%    | 99%   "));
+        stats.display_percentile = true;
+        let summary = format!("{}", stats);
+        assert!(summary.contains(" | 90%    | 95%   "));
+        assert!(!summary.contains("99%"));
+    }
+
+    #[test]
+    fn aggregate_histogram_bounds() {
+        let mut stats = GooseStats {
+            display_percentile: true,
+            percentiles: vec![0.99],
+            ..Default::default()
+        };
+        // Track response times above the default maximum of 60 seconds.
+        let mut request =
+            GooseRequest::new("/", GooseMethod::GET, 0, GooseHistogram::new(300_000, 3));
+        request.set_response_time(120_000);
+        request.success_count = 1;
+        stats.requests.insert("GET /".to_string(), request);
+
+        let summary = stats.summary();
+        assert_eq!(summary.requests[0].percentiles["p99"], 120_000);
+        assert_eq!(summary.aggregated.percentiles["p99"], 120_000);
+        let aggregated = new_aggregate_histogram(stats.requests.values());
+        assert_eq!(
+            format!("{:?}", aggregated),
+            "GooseHistogram { len: 0, max: 300000, significant_figures: 3 }"
+        );
+    }
+
+    #[test]
+    fn throughput_buckets() {
+        let mut stats = GooseStats {
+            throughput_interval: 5,
+            ..Default::default()
+        };
+        stats.throughput_bucket(3).requests += 1;
+        // Gaps are filled with empty buckets.
+        stats.throughput_bucket(12).requests += 1;
+        stats.throughput_bucket(14).fails += 1;
+        assert_eq!(
+            stats.throughput(),
+            &[
+                GooseThroughput {
+                    second: 0,
+                    requests: 1,
+                    fails: 0,
+                    users: 0,
+                    paused: false
+                },
+                GooseThroughput {
+                    second: 5,
+                    requests: 0,
+                    fails: 0,
+                    users: 0,
+                    paused: false
+                },
+                GooseThroughput {
+                    second: 10,
+                    requests: 1,
+                    fails: 1,
+                    users: 0,
+                    paused: false
+                },
+            ]
+        );
+        assert_eq!(stats.summary().throughput_interval, 5);
+        assert_eq!(stats.summary().throughput.len(), 3);
+    }
+
+    #[test]
+    fn html_throughput_chart() {
+        let mut stats = GooseStats {
+            throughput_interval: 2,
+            ..Default::default()
+        };
+        // There's nothing to chart without throughput.
+        assert_eq!(stats.html_throughput_chart(), "");
+
+        stats.throughput_bucket(0).requests += 20;
+        stats.throughput_bucket(2).requests += 10;
+        stats.throughput_bucket(2).fails += 10;
+        let chart = stats.html_throughput_chart();
+        assert!(chart.contains("<svg"));
+        // The busiest bucket, at 10 requests per second, is the top of the chart.
+        assert!(chart.contains(">10</text>"));
+        assert!(
+            chart.contains("class=\"requests\" stroke=\"#36c\" points=\"40.0,40.0 640.0,140.0\"")
+        );
+        assert!(chart.contains("class=\"fails\" stroke=\"#c33\" points=\"40.0,240.0 640.0,140.0\""));
+        assert!(chart.contains(">2s</text>"));
+
+        // A single bucket is drawn across the whole chart.
+        stats.throughput.truncate(1);
+        assert!(stats
+            .html_throughput_chart()
+            .contains("points=\"40.0,40.0 640.0,40.0\""));
+    }
+
+    #[test]
+    fn slowest_requests() {
+        let mut stats = GooseStats {
+            slowest_count: 2,
+            ..Default::default()
+        };
+        for (elapsed, response_time) in [(1, 30), (2, 10), (3, 50), (4, 20)].iter() {
+            stats.record_slow_request(GooseSlowRequest {
+                method: GooseMethod::GET,
+                url: format!("http://localhost/{}", elapsed),
+                status_code: 200,
+                response_time: *response_time,
+                elapsed: *elapsed,
+            });
+        }
+        // Only the two slowest requests are kept, slowest first.
+        let slowest = stats.slowest();
+        assert_eq!(slowest.len(), 2);
+        assert_eq!(slowest[0].response_time, 50);
+        assert_eq!(slowest[0].url, "http://localhost/3");
+        assert_eq!(slowest[1].response_time, 30);
+        assert_eq!(stats.summary().slowest, slowest);
+        assert_eq!(format_elapsed(334), "00:05:34");
+
+        // Each --reset-interval window tracks its own slowest requests.
+        stats.print_window_and_reset(5);
+        assert!(stats.slowest().is_empty());
+    }
+
     #[test]
     fn max_response_time() {
         let mut max_response_time = 99;
@@ -634,76 +2481,93 @@ mod test {
     }
 
     #[test]
-    fn response_time_merge() {
-        let mut global_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        let local_response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        global_response_times =
-            merge_response_times(global_response_times, local_response_times.clone());
-        // @TODO: how can we do useful testing of private method and objects?
-        assert_eq!(&global_response_times, &local_response_times);
+    fn coordinated_omission() {
+        let mut histogram = GooseHistogram::default();
+        // Without an expected interval nothing is back-filled.
+        correct_coordinated_omission(&mut histogram, 5_000, 0);
+        assert!(histogram.is_empty());
+
+        // Responses faster than twice the expected interval aren't corrected.
+        correct_coordinated_omission(&mut histogram, 1_000, 1_000);
+        correct_coordinated_omission(&mut histogram, 1_999, 1_000);
+        assert!(histogram.is_empty());
+
+        // A 500 millisecond stall hides requests that would have been sent after 100,
+        // 200, 300 and 400 milliseconds, which would have taken 400, 300, 200 and 100
+        // milliseconds respectively.
+        correct_coordinated_omission(&mut histogram, 500, 100);
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram.value_at_percentile(0.0), 100);
+        assert_eq!(histogram.value_at_percentile(0.5), 200);
+        assert_eq!(histogram.value_at_percentile(1.0), 400);
     }
 
     #[test]
     fn max_response_time_percentile() {
-        let mut response_times: BTreeMap<usize, usize> = BTreeMap::new();
-        response_times.insert(1, 1);
-        response_times.insert(2, 1);
-        response_times.insert(3, 1);
-        // 3 * .5 = 1.5, rounds to 2.
-        assert_eq!(
-            calculate_response_time_percentile(&response_times, 3, 1, 3, 0.5),
-            2
-        );
-        response_times.insert(3, 2);
-        // 4 * .5 = 2
-        assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 1, 3, 0.5),
-            2
-        );
-        // 4 * .25 = 1
+        let mut histogram = GooseHistogram::default();
+        // An empty histogram has no percentiles.
+        assert_eq!(calculate_response_time_percentile(&histogram, 0, 0, 0.5), 0);
+
+        histogram.record(1);
+        histogram.record(2);
+        histogram.record(3);
+        assert_eq!(calculate_response_time_percentile(&histogram, 1, 3, 0.5), 2);
+        histogram.record(3);
+        assert_eq!(calculate_response_time_percentile(&histogram, 1, 3, 0.5), 2);
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 1, 3, 0.25),
+            calculate_response_time_percentile(&histogram, 1, 3, 0.25),
             1
         );
-        // 4 * .75 = 3
-        assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 1, 3, 0.75),
-            3
-        );
-        // 4 * 1 = 4 (and the 4th response time is also 3)
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 1, 3, 1.0),
+            calculate_response_time_percentile(&histogram, 1, 3, 0.75),
             3
         );
+        assert_eq!(calculate_response_time_percentile(&histogram, 1, 3, 1.0), 3);
 
-        // 4 * .5 = 2, but uses specified minimum of 2
+        // Uses specified minimum of 2.
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 2, 3, 0.25),
+            calculate_response_time_percentile(&histogram, 2, 3, 0.25),
             2
         );
-        // 4 * .75 = 3, but uses specified maximum of 2
+        // Uses specified maximum of 2.
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 4, 1, 2, 0.75),
+            calculate_response_time_percentile(&histogram, 1, 2, 0.75),
             2
         );
 
-        response_times.insert(10, 25);
-        response_times.insert(20, 25);
-        response_times.insert(30, 25);
-        response_times.insert(50, 25);
-        response_times.insert(100, 10);
-        response_times.insert(200, 1);
+        let mut histogram = GooseHistogram::default();
+        for _ in 0..25 {
+            histogram.record(10);
+            histogram.record(20);
+            histogram.record(30);
+            histogram.record(50);
+        }
+        for _ in 0..10 {
+            histogram.record(100);
+        }
+        histogram.record(200);
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 115, 1, 200, 0.9),
+            calculate_response_time_percentile(&histogram, 10, 200, 0.9),
             50
         );
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 115, 1, 200, 0.99),
+            calculate_response_time_percentile(&histogram, 10, 200, 0.99),
             100
         );
         assert_eq!(
-            calculate_response_time_percentile(&response_times, 115, 1, 200, 0.999),
+            calculate_response_time_percentile(&histogram, 10, 200, 0.999),
             200
         );
+
+        // Rare slow requests aren't lost to rounding.
+        let mut histogram = GooseHistogram::default();
+        for _ in 0..999 {
+            histogram.record(5);
+        }
+        histogram.record(1_234);
+        assert_eq!(
+            calculate_response_time_percentile(&histogram, 5, 1_234, 0.9999),
+            1_234
+        );
     }
 }
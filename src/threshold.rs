@@ -0,0 +1,304 @@
+//! Thresholds configured with `--fail-if`, checked against the final statistics.
+//!
+//! Each threshold has the form `[<method> <name> ]<metric><operator><value>`, for example
+//! `p95>500ms`, `error-rate>1%` or `GET /login avg>=2s`. When a method and name are
+//! included the threshold only applies to requests with that statistics key, otherwise
+//! it applies to all requests combined. The load test fails if the condition is true.
+
+use std::fmt;
+
+use crate::stats::{new_aggregate_histogram, GooseStats};
+
+/// A metric calculated from request statistics.
+#[derive(Debug, Clone, PartialEq)]
+enum Metric {
+    /// Response time in milliseconds that this percent of requests finished within.
+    Percentile(f32),
+    /// Average response time in milliseconds.
+    Average,
+    /// Slowest response time in milliseconds.
+    Max,
+    /// Percentage of requests that failed.
+    ErrorRate,
+    /// Requests per second.
+    RequestsPerSecond,
+}
+
+/// How a metric is compared to the threshold value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+impl Operator {
+    fn matches(self, actual: f64, value: f64) -> bool {
+        match self {
+            Operator::GreaterThan => actual > value,
+            Operator::GreaterThanOrEqual => actual >= value,
+            Operator::LessThan => actual < value,
+            Operator::LessThanOrEqual => actual <= value,
+        }
+    }
+}
+
+/// A single `--fail-if` threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GooseThreshold {
+    /// The original expression, used when reporting failures.
+    expression: String,
+    /// Optional statistics key (ie `GET /login`) the threshold applies to.
+    request: Option<String>,
+    metric: Metric,
+    operator: Operator,
+    /// The threshold value, in milliseconds for response times and percent for error rates.
+    value: f64,
+}
+impl fmt::Display for GooseThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+impl GooseThreshold {
+    /// Parse a threshold expression, returning an explanation if it's invalid.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        // Everything before the last space is the optional method and request name.
+        let (request, condition) = match expression.rfind(' ') {
+            Some(index) => (
+                Some(expression[..index].trim().to_string()),
+                &expression[index + 1..],
+            ),
+            None => (None, expression),
+        };
+
+        // Operators are checked longest first, so `>=` isn't parsed as `>`.
+        let operators = [
+            (">=", Operator::GreaterThanOrEqual),
+            ("<=", Operator::LessThanOrEqual),
+            (">", Operator::GreaterThan),
+            ("<", Operator::LessThan),
+        ];
+        let (index, symbol, operator) = match operators
+            .iter()
+            .filter_map(|(symbol, operator)| {
+                condition
+                    .find(symbol)
+                    .map(|index| (index, *symbol, *operator))
+            })
+            .next()
+        {
+            Some(found) => found,
+            None => {
+                return Err(format!(
+                    "{} must compare a metric with >, >=, < or <=",
+                    expression
+                ))
+            }
+        };
+        let metric_name = &condition[..index];
+        let value = &condition[index + symbol.len()..];
+
+        let metric = match metric_name {
+            "avg" => Metric::Average,
+            "max" => Metric::Max,
+            "error-rate" => Metric::ErrorRate,
+            "rps" => Metric::RequestsPerSecond,
+            _ if metric_name.starts_with('p') => match metric_name[1..].parse::<f32>() {
+                Ok(percent) if percent > 0.0 && percent <= 100.0 => {
+                    Metric::Percentile(percent / 100.0)
+                }
+                _ => return Err(format!("{} is not a valid percentile", metric_name)),
+            },
+            _ => {
+                return Err(format!(
+                    "{} is not a valid metric, use p<percentile>, avg, max, error-rate or rps",
+                    metric_name
+                ))
+            }
+        };
+
+        let value = match metric {
+            Metric::ErrorRate => parse_number(value.trim_end_matches('%')),
+            Metric::RequestsPerSecond => parse_number(value),
+            _ => {
+                if let Some(seconds) = value.strip_suffix("ms") {
+                    parse_number(seconds)
+                } else if let Some(seconds) = value.strip_suffix('s') {
+                    parse_number(seconds).map(|s| s * 1_000.0)
+                } else {
+                    parse_number(value)
+                }
+            }
+        }
+        .ok_or_else(|| format!("{} is not a valid value for {}", value, metric_name))?;
+
+        Ok(GooseThreshold {
+            expression: expression.to_string(),
+            request,
+            metric,
+            operator,
+            value,
+        })
+    }
+
+    /// Check the threshold against statistics, returning a description of the failure if
+    /// the condition is true.
+    pub fn check(&self, stats: &GooseStats) -> Option<String> {
+        let requests: Vec<_> = match &self.request {
+            Some(key) => match stats.requests.get(key) {
+                Some(request) => vec![request],
+                None => return Some(format!("{}: no {} requests recorded", self, key)),
+            },
+            None => stats.requests.values().collect(),
+        };
+
+        let mut histogram = new_aggregate_histogram(requests.iter().copied());
+        let mut max_response_time = 0;
+        let mut total_response_time = 0;
+        let mut response_time_counter = 0;
+        let mut success_count = 0;
+        let mut fail_count = 0;
+        for request in requests {
+            histogram.merge(&request.response_time_histogram);
+            max_response_time = max_response_time.max(request.max_response_time);
+            total_response_time += request.total_response_time;
+            response_time_counter += request.response_time_counter;
+            success_count += request.success_count;
+            fail_count += request.fail_count;
+        }
+        let total_count = success_count + fail_count;
+
+        let actual = match self.metric {
+            Metric::Percentile(percent) => {
+                if histogram.is_empty() {
+                    0.0
+                } else {
+                    // The histogram can't return a larger value than was actually seen.
+                    (histogram.value_at_percentile(percent) as f64).min(max_response_time as f64)
+                }
+            }
+            Metric::Average => {
+                if response_time_counter == 0 {
+                    0.0
+                } else {
+                    total_response_time as f64 / response_time_counter as f64
+                }
+            }
+            Metric::Max => max_response_time as f64,
+            Metric::ErrorRate => {
+                if total_count == 0 {
+                    0.0
+                } else {
+                    fail_count as f64 / total_count as f64 * 100.0
+                }
+            }
+            Metric::RequestsPerSecond => total_count as f64 / stats.duration.max(1) as f64,
+        };
+
+        if self.operator.matches(actual, self.value) {
+            Some(format!("{} (actual: {:.2})", self, actual))
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|number| *number >= 0.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::goose::{GooseHistogram, GooseMethod, GooseRequest};
+
+    #[test]
+    fn parse() {
+        let threshold = GooseThreshold::parse("p95>500ms").unwrap();
+        assert_eq!(threshold.request, None);
+        assert_eq!(threshold.metric, Metric::Percentile(0.95));
+        assert_eq!(threshold.operator, Operator::GreaterThan);
+        assert_eq!(threshold.value, 500.0);
+
+        let threshold = GooseThreshold::parse("GET /login avg>=2s").unwrap();
+        assert_eq!(threshold.request, Some("GET /login".to_string()));
+        assert_eq!(threshold.metric, Metric::Average);
+        assert_eq!(threshold.operator, Operator::GreaterThanOrEqual);
+        assert_eq!(threshold.value, 2_000.0);
+
+        let threshold = GooseThreshold::parse("error-rate>1%").unwrap();
+        assert_eq!(threshold.metric, Metric::ErrorRate);
+        assert_eq!(threshold.value, 1.0);
+
+        let threshold = GooseThreshold::parse("rps<=10").unwrap();
+        assert_eq!(threshold.metric, Metric::RequestsPerSecond);
+        assert_eq!(threshold.operator, Operator::LessThanOrEqual);
+
+        assert_eq!(
+            GooseThreshold::parse("p99.9<100").unwrap().metric,
+            Metric::Percentile(0.999)
+        );
+
+        assert!(GooseThreshold::parse("p95").is_err());
+        assert!(GooseThreshold::parse("p0>1").is_err());
+        assert!(GooseThreshold::parse("median>1").is_err());
+        assert!(GooseThreshold::parse("avg>fast").is_err());
+        assert!(GooseThreshold::parse("max>-1").is_err());
+    }
+
+    #[test]
+    fn check() {
+        let mut stats = GooseStats {
+            duration: 2,
+            ..Default::default()
+        };
+        let mut request = GooseRequest::new("/", GooseMethod::GET, 0, GooseHistogram::default());
+        for response_time in &[10, 20, 30, 1_000] {
+            request.set_response_time(*response_time);
+        }
+        request.success_count = 3;
+        request.fail_count = 1;
+        stats.requests.insert("GET /".to_string(), request);
+
+        let failed = |expression: &str| {
+            GooseThreshold::parse(expression)
+                .unwrap()
+                .check(&stats)
+                .is_some()
+        };
+        assert!(failed("p50>15ms"));
+        assert!(!failed("p50>20ms"));
+        assert!(failed("max>=1s"));
+        assert!(failed("avg>264"));
+        assert!(!failed("avg>265"));
+        assert!(failed("error-rate>=25%"));
+        assert!(!failed("error-rate>25%"));
+        assert!(failed("rps<3"));
+        assert!(!failed("rps<2"));
+        assert!(failed("GET / max>999"));
+        // Thresholds for requests that weren't made fail.
+        assert!(failed("GET /missing max>999"));
+    }
+
+    #[test]
+    fn check_configured_histogram_bounds() {
+        let mut stats = GooseStats::default();
+        // Track response times above the default maximum of 60 seconds.
+        let mut request =
+            GooseRequest::new("/", GooseMethod::GET, 0, GooseHistogram::new(300_000, 3));
+        request.set_response_time(120_000);
+        request.success_count = 1;
+        stats.requests.insert("GET /".to_string(), request);
+
+        let failed = |expression: &str| {
+            GooseThreshold::parse(expression)
+                .unwrap()
+                .check(&stats)
+                .is_some()
+        };
+        assert!(failed("p99>100s"));
+        assert!(!failed("p99>130s"));
+    }
+}
@@ -1,8 +1,43 @@
-use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::time;
 
+/// Spawn a throttle thread limiting requests to `throttle_requests` per second. Returns
+/// the channel GooseUser threads add tokens to before making a request, and the channel
+/// used by the parent to tell the throttle thread the load test is complete.
+pub async fn spawn_throttle(throttle_requests: usize) -> (Sender<bool>, Sender<bool>) {
+    // Create a bounded channel allowing single-sender multi-receiver to throttle
+    // GooseUser threads.
+    let (all_threads_throttle, throttle_receiver): (Sender<bool>, Receiver<bool>) =
+        mpsc::channel(throttle_requests);
+
+    // Create a channel allowing the parent to inform the throttle thread when the
+    // load test is finished. Even though we only send one message, we can't use a
+    // oneshot channel as we don't want to block waiting for a message.
+    let (parent_to_throttle_tx, throttle_rx) = mpsc::channel(1);
+
+    // Launch a new thread for throttling, no need to rejoin it.
+    let _ = Some(tokio::spawn(throttle_main(
+        throttle_requests,
+        throttle_receiver,
+        throttle_rx,
+    )));
+
+    let mut sender = all_threads_throttle.clone();
+    // We start from 1 instead of 0 to intentionally fill all but one slot in the
+    // channel to avoid a burst of traffic during startup. The channel then provides
+    // an implementation of the leaky bucket algorithm as a queue. Requests have to
+    // add a token to the bucket before making a request, and are blocked until this
+    // throttle thread "leaks out" a token thereby creating space. More information
+    // can be found at: https://en.wikipedia.org/wiki/Leaky_bucket
+    for _ in 1..throttle_requests {
+        let _ = sender.send(true).await;
+    }
+
+    (all_threads_throttle, parent_to_throttle_tx)
+}
+
 /// This throttle thread limits the maximum number of requests that can be made across
-/// all GooseUser threads. When enabled, GooseUser threads must add a token to the
+/// all GooseUser threads sharing the throttle. When enabled, GooseUser threads must add a token to the
 /// bounded channel before making a request, and this thread limits how frequently
 /// tokens are removed thereby throttling how fast requests can be made. It is an
 /// implementation of the leaky bucket algorithm as a queue: instead of leaking the
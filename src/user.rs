@@ -1,12 +1,16 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rand::Rng;
-use std::sync::atomic::Ordering;
-use std::time;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::{future::Future, pin::Pin, time};
 use tokio::sync::mpsc;
 
+use crate::data;
 use crate::get_worker_id;
-use crate::goose::{GooseTaskSet, GooseUser, GooseUserCommand};
+use crate::goose::{
+    GooseNextTask, GooseScheduler, GooseTaskError, GooseTaskResult, GooseTaskSet, GooseUser,
+    GooseUserCommand, GooseWaitDistribution,
+};
 
 pub async fn user_main(
     thread_number: usize,
@@ -14,6 +18,8 @@ pub async fn user_main(
     mut thread_user: GooseUser,
     mut thread_receiver: mpsc::UnboundedReceiver<GooseUserCommand>,
     worker: bool,
+    canceled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
 ) {
     if worker {
         info!(
@@ -29,25 +35,37 @@ pub async fn user_main(
         );
     }
 
+    // Optionally pace every request the user makes.
+    thread_user.user_pace = thread_task_set.user_pace;
+
+    // Optionally open connections before anything is measured.
+    thread_user.warm_up_connections().await;
+
     // User is starting, first invoke the weighted on_start tasks.
     if !thread_user.weighted_on_start_tasks.is_empty() {
         for mut sequence in thread_user.weighted_on_start_tasks.clone() {
-            if sequence.len() > 1 {
-                sequence.shuffle(&mut thread_rng());
+            if sequence.len() > 1 && thread_task_set.scheduler == GooseScheduler::Random {
+                data::with_rng(|rng| sequence.shuffle(rng));
             }
             for task_index in &sequence {
                 // Determine which task we're going to run next.
                 let thread_task_name = &thread_task_set.tasks[*task_index].name;
                 let function = &thread_task_set.tasks[*task_index].function;
+                thread_user.stats_normalization =
+                    thread_task_set.tasks[*task_index].stats_normalization;
+                thread_user.retries = thread_task_set.tasks[*task_index].retries;
+                thread_user.retry_backoff = thread_task_set.tasks[*task_index].retry_backoff;
+                thread_user.latency_budget = thread_task_set.tasks[*task_index].latency_budget;
+                thread_user.min_response_time =
+                    thread_task_set.tasks[*task_index].min_response_time;
+                thread_user.task_data = thread_task_set.tasks[*task_index].data.clone();
                 debug!(
                     "launching on_start {} task from {}",
                     thread_task_name, thread_task_set.name
                 );
-                if thread_task_name != "" {
-                    thread_user.task_request_name = Some(thread_task_name.to_string());
-                }
+                thread_user.task_request_name = task_request_name(thread_task_name);
                 // Invoke the task function.
-                let _ = function(&thread_user).await;
+                invoke_task_function(function, &thread_user, &thread_task_set, &canceled).await;
             }
         }
     }
@@ -56,11 +74,19 @@ pub async fn user_main(
     let mut thread_continue: bool = true;
     let mut weighted_bucket = thread_user.weighted_bucket.load(Ordering::SeqCst);
     let mut weighted_bucket_position = thread_user.weighted_bucket_position.load(Ordering::SeqCst);
+    // Count how many times the user has run through all of its tasks.
+    let mut iterations: usize = 0;
     if thread_user.weighted_tasks.is_empty() {
         // Handle the edge case where a load test doesn't define any normal tasks.
         thread_continue = false;
     }
     while thread_continue {
+        // Don't run any tasks while the load test is paused.
+        if paused.load(Ordering::SeqCst) && !wait_while_paused(&paused, &mut thread_receiver).await
+        {
+            break;
+        }
+
         // Weighted_tasks is divided into buckets of tasks sorted by sequence, and then all non-sequenced tasks.
         if thread_user.weighted_tasks[weighted_bucket].len() <= weighted_bucket_position {
             // This bucket is exhausted, move on to position 0 of the next bucket.
@@ -72,37 +98,91 @@ pub async fn user_main(
             weighted_bucket += 1;
             if thread_user.weighted_tasks.len() <= weighted_bucket {
                 weighted_bucket = 0;
+
+                // All buckets are exhausted, the user has completed an iteration.
+                iterations += 1;
+                if thread_user.config.iterations > 0 && iterations >= thread_user.config.iterations
+                {
+                    debug!(
+                        "user {} from {} completed {} iterations",
+                        thread_number, thread_task_set.name, iterations
+                    );
+                    break;
+                }
             }
             thread_user
                 .weighted_bucket
                 .store(weighted_bucket_position, Ordering::SeqCst);
-            // Shuffle new bucket before we walk through the tasks.
-            thread_user.weighted_tasks[weighted_bucket].shuffle(&mut thread_rng());
-            debug!(
-                "re-shuffled {} tasks: {:?}",
-                &thread_task_set.name, thread_user.weighted_tasks[weighted_bucket]
-            );
+            // Shuffle new bucket before we walk through the tasks, unless the task set
+            // runs its tasks in order.
+            if thread_task_set.scheduler == GooseScheduler::Random {
+                data::with_rng(|rng| thread_user.weighted_tasks[weighted_bucket].shuffle(rng));
+                debug!(
+                    "re-shuffled {} tasks: {:?}",
+                    &thread_task_set.name, thread_user.weighted_tasks[weighted_bucket]
+                );
+            }
         }
 
-        // Determine which task we're going to run next.
-        let thread_weighted_task =
-            thread_user.weighted_tasks[weighted_bucket][weighted_bucket_position];
+        // Determine which task we're going to run next, unless the previous task chose it.
+        let scheduled_task = thread_user.weighted_tasks[weighted_bucket][weighted_bucket_position];
+        let thread_weighted_task = match thread_user.take_next_task() {
+            GooseNextTask::Scheduled => scheduled_task,
+            // Only tasks the scheduler could run can be chosen, which excludes on_start and
+            // on_stop tasks, and tasks not selected by --tags or --exclude-tags.
+            GooseNextTask::Task(index)
+                if thread_user
+                    .weighted_tasks
+                    .iter()
+                    .any(|bucket| bucket.contains(&index)) =>
+            {
+                index
+            }
+            GooseNextTask::Task(index) => {
+                warn!(
+                    "{} has no scheduled task {}, running the scheduled task instead",
+                    thread_task_set.name, index
+                );
+                scheduled_task
+            }
+            GooseNextTask::Skipped => {
+                debug!(
+                    "skipping {} task from {}",
+                    thread_task_set.tasks[scheduled_task].name, thread_task_set.name
+                );
+                weighted_bucket_position += 1;
+                thread_user
+                    .weighted_bucket_position
+                    .store(weighted_bucket_position, Ordering::SeqCst);
+                continue;
+            }
+        };
         let thread_task_name = &thread_task_set.tasks[thread_weighted_task].name;
         let function = &thread_task_set.tasks[thread_weighted_task].function;
+        thread_user.stats_normalization =
+            thread_task_set.tasks[thread_weighted_task].stats_normalization;
+        thread_user.retries = thread_task_set.tasks[thread_weighted_task].retries;
+        thread_user.retry_backoff = thread_task_set.tasks[thread_weighted_task].retry_backoff;
+        thread_user.latency_budget = thread_task_set.tasks[thread_weighted_task].latency_budget;
+        thread_user.min_response_time =
+            thread_task_set.tasks[thread_weighted_task].min_response_time;
+        thread_user.task_data = thread_task_set.tasks[thread_weighted_task].data.clone();
         debug!(
             "launching {} task from {}",
             thread_task_name, thread_task_set.name
         );
         // If task name is set, it will be used for storing request statistics instead of the raw url.
-        if thread_task_name != "" {
-            thread_user.task_request_name = Some(thread_task_name.to_string());
-        }
+        thread_user.task_request_name = task_request_name(thread_task_name);
         // Invoke the task function.
-        let _ = function(&thread_user).await;
+        invoke_task_function(function, &thread_user, &thread_task_set, &canceled).await;
 
         // Prepare to sleep for a random value from min_wait to max_wait.
         let wait_time = if thread_user.max_wait > 0 {
-            rand::thread_rng().gen_range(thread_user.min_wait, thread_user.max_wait)
+            select_wait_time(
+                thread_user.min_wait,
+                thread_user.max_wait,
+                thread_task_set.wait_distribution,
+            )
         } else {
             0
         };
@@ -152,22 +232,28 @@ pub async fn user_main(
     // User is exiting, first invoke the weighted on_stop tasks.
     if !thread_user.weighted_on_stop_tasks.is_empty() {
         for mut sequence in thread_user.weighted_on_stop_tasks.clone() {
-            if sequence.len() > 1 {
-                sequence.shuffle(&mut thread_rng());
+            if sequence.len() > 1 && thread_task_set.scheduler == GooseScheduler::Random {
+                data::with_rng(|rng| sequence.shuffle(rng));
             }
             for task_index in &sequence {
                 // Determine which task we're going to run next.
                 let thread_task_name = &thread_task_set.tasks[*task_index].name;
                 let function = &thread_task_set.tasks[*task_index].function;
+                thread_user.stats_normalization =
+                    thread_task_set.tasks[*task_index].stats_normalization;
+                thread_user.retries = thread_task_set.tasks[*task_index].retries;
+                thread_user.retry_backoff = thread_task_set.tasks[*task_index].retry_backoff;
+                thread_user.latency_budget = thread_task_set.tasks[*task_index].latency_budget;
+                thread_user.min_response_time =
+                    thread_task_set.tasks[*task_index].min_response_time;
+                thread_user.task_data = thread_task_set.tasks[*task_index].data.clone();
                 debug!(
                     "launching on_stop {} task from {}",
                     thread_task_name, thread_task_set.name
                 );
-                if thread_task_name != "" {
-                    thread_user.task_request_name = Some(thread_task_name.to_string());
-                }
+                thread_user.task_request_name = task_request_name(thread_task_name);
                 // Invoke the task function.
-                let _ = function(&thread_user).await;
+                invoke_task_function(function, &thread_user, &thread_task_set, &canceled).await;
             }
         }
     }
@@ -187,3 +273,121 @@ pub async fn user_main(
         );
     }
 }
+
+/// Wait until the load test is resumed, checking regularly for messages from the parent
+/// thread. Returns false if the user was told to exit while paused.
+async fn wait_while_paused(
+    paused: &AtomicBool,
+    thread_receiver: &mut mpsc::UnboundedReceiver<GooseUserCommand>,
+) -> bool {
+    while paused.load(Ordering::SeqCst) {
+        let mut message = thread_receiver.try_recv();
+        while message.is_ok() {
+            match message.unwrap() {
+                // Time to exit.
+                GooseUserCommand::EXIT => return false,
+                command => {
+                    debug!("ignoring unexpected GooseUserCommand: {:?}", command);
+                }
+            }
+            message = thread_receiver.try_recv();
+        }
+        tokio::time::delay_for(time::Duration::from_secs(1)).await;
+    }
+    true
+}
+
+/// Requests made by a named task are stored in statistics under the task name, while
+/// requests made by an unnamed task are stored under their path. The name is reset before
+/// each task, so a named task doesn't affect requests made by the tasks that follow it.
+fn task_request_name(thread_task_name: &str) -> Option<String> {
+    if thread_task_name.is_empty() {
+        None
+    } else {
+        Some(thread_task_name.to_string())
+    }
+}
+
+/// Invoke a task function. If the task returns an error and the task set has an on_error
+/// function, invoke it with the error. Errors returned by the on_error function are only
+/// logged, so the user keeps running. If the task returns `StopLoadTest`, the load test
+/// is canceled instead.
+async fn invoke_task_function(
+    function: &for<'r> fn(
+        &'r GooseUser,
+    ) -> Pin<Box<dyn Future<Output = GooseTaskResult> + Send + 'r>>,
+    thread_user: &GooseUser,
+    thread_task_set: &GooseTaskSet,
+    canceled: &AtomicBool,
+) {
+    if let Err(error) = function(thread_user).await {
+        if let GooseTaskError::StopLoadTest { reason } = &error {
+            error!(
+                "task from {} stopped the load test: {}",
+                thread_task_set.name, reason
+            );
+            canceled.store(true, Ordering::SeqCst);
+            return;
+        }
+        if let Some(on_error) = thread_task_set.on_error {
+            if let Err(on_error_error) = on_error(thread_user, &error).await {
+                warn!(
+                    "on_error function from {} failed: {:?}",
+                    thread_task_set.name, on_error_error
+                );
+            }
+        }
+    }
+}
+
+/// Select how many seconds to sleep after running a task, from min_wait to max_wait.
+fn select_wait_time(
+    min_wait: usize,
+    max_wait: usize,
+    distribution: GooseWaitDistribution,
+) -> usize {
+    if min_wait >= max_wait {
+        return max_wait;
+    }
+    match distribution {
+        GooseWaitDistribution::Uniform => data::with_rng(|rng| rng.gen_range(min_wait, max_wait)),
+        GooseWaitDistribution::Constant => max_wait,
+        GooseWaitDistribution::Exponential => {
+            // Sample the time past min_wait from an exponential distribution with a mean
+            // halfway to max_wait, capping outliers at max_wait.
+            let mean = (max_wait - min_wait) as f64 / 2.0;
+            let sample: f64 = data::with_rng(|rng| rng.gen());
+            let wait_time = min_wait + (-(1.0 - sample).ln() * mean).round() as usize;
+            wait_time.min(max_wait)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wait_time() {
+        // Constant wait times don't vary.
+        for _ in 0..100 {
+            assert_eq!(select_wait_time(3, 3, GooseWaitDistribution::Constant), 3);
+            assert_eq!(select_wait_time(3, 3, GooseWaitDistribution::Uniform), 3);
+        }
+
+        // Random wait times are always between min_wait and max_wait.
+        let mut uniform_total = 0;
+        let mut exponential_total = 0;
+        for _ in 0..10_000 {
+            let uniform = select_wait_time(10, 30, GooseWaitDistribution::Uniform);
+            assert!((10..=30).contains(&uniform));
+            uniform_total += uniform;
+            let exponential = select_wait_time(10, 30, GooseWaitDistribution::Exponential);
+            assert!((10..=30).contains(&exponential));
+            exponential_total += exponential;
+        }
+
+        // Exponential wait times favor shorter waits than uniform wait times.
+        assert!(exponential_total < uniform_total);
+    }
+}
@@ -1,5 +1,5 @@
 use std::cmp::{max, min};
-use std::collections::BTreeMap;
+use std::net::IpAddr;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -46,6 +46,70 @@ pub fn parse_timespan(time_str: &str) -> usize {
     }
 }
 
+/// Parse a step load schedule, a comma-separated list of users@hold time steps, for
+/// example "10@30s,20@30s,40@60s". Returns a vector of (users, seconds to hold) tuples.
+pub fn parse_step_load(value: &str) -> Result<Vec<(usize, usize)>, String> {
+    let mut steps: Vec<(usize, usize)> = Vec::new();
+    for step in value.split(',') {
+        let mut parts = step.trim().splitn(2, '@');
+        let users = match usize::from_str(parts.next().unwrap_or("").trim()) {
+            Ok(users) => users,
+            Err(_) => {
+                return Err(format!(
+                    "--step-load steps must be formatted as users@hold time, not {}.",
+                    step
+                ))
+            }
+        };
+        let hold = parse_timespan(parts.next().unwrap_or("").trim());
+        if hold == 0 {
+            return Err(format!(
+                "--step-load steps must hold for at least 1 second, not {}.",
+                step
+            ));
+        }
+        let previous_users = steps.last().map_or(0, |previous| previous.0);
+        if users <= previous_users {
+            return Err(format!(
+                "--step-load steps must each launch more users than the previous step, not {}.",
+                step
+            ));
+        }
+        steps.push((users, hold));
+    }
+    Ok(steps)
+}
+
+/// Parse a `--resolve` host mapping formatted host:port:ip, for example
+/// "www.example.com:443:10.0.0.5". IPv6 addresses may be wrapped in brackets. Returns a
+/// (host, port, ip) tuple.
+pub fn parse_resolve(value: &str) -> Result<(String, u16, IpAddr), String> {
+    let mut parts = value.splitn(3, ':');
+    let host = parts.next().unwrap_or("").trim();
+    let port = parts.next().unwrap_or("").trim();
+    let ip = parts
+        .next()
+        .unwrap_or("")
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    if host.is_empty() || port.is_empty() || ip.is_empty() {
+        return Err(format!(
+            "--resolve must be formatted as host:port:ip, not {}.",
+            value
+        ));
+    }
+    let port = match u16::from_str(port) {
+        Ok(port) if port > 0 => port,
+        _ => return Err(format!("--resolve port must be 1-65535, not {}.", port)),
+    };
+    let ip = match IpAddr::from_str(ip) {
+        Ok(ip) => ip,
+        Err(_) => return Err(format!("--resolve ip must be an IP address, not {}.", ip)),
+    };
+    Ok((host.to_lowercase(), port, ip))
+}
+
 /// Calculate the greatest commond divisor using binary GCD (or Stein's) algorithm.
 /// More detail: https://en.wikipedia.org/wiki/Binary_GCD_algorithm
 pub fn gcd(u: usize, v: usize) -> usize {
@@ -61,33 +125,6 @@ pub fn gcd(u: usize, v: usize) -> usize {
     }
 }
 
-/// Calculate median for a BTreeMap of usizes.
-pub fn median(
-    btree: &BTreeMap<usize, usize>,
-    total_elements: usize,
-    min: usize,
-    max: usize,
-) -> usize {
-    let mut total_count: usize = 0;
-    let half_elements: usize = (total_elements as f64 / 2.0).round() as usize;
-    for (value, counter) in btree {
-        total_count += counter;
-        if total_count >= half_elements {
-            // We're working with rounded values, it's possible the mean is greater than the
-            // max response time, or smaller than the min response time -- in these cases
-            // return the actual values;
-            if *value > max {
-                return max;
-            } else if *value < min {
-                return min;
-            } else {
-                return *value;
-            }
-        }
-    }
-    0
-}
-
 /// Truncate strings when they're too long to display.
 pub fn truncate_string(str_to_truncate: &str, max_length: u64) -> String {
     let mut string_to_truncate = str_to_truncate.to_string();
@@ -104,15 +141,18 @@ pub fn timer_expired(started: time::Instant, run_time: usize) -> bool {
     run_time > 0 && started.elapsed().as_secs() >= run_time as u64
 }
 
+/// Cancel the load test when ctrl-c (SIGINT) is caught, or when the process is asked to
+/// terminate (SIGTERM and SIGHUP on Unix, closing the console on Windows), for example
+/// when a container is stopped. A second signal exits immediately.
 pub fn setup_ctrlc_handler(canceled: &Arc<AtomicBool>) {
     let caught_ctrlc = canceled.clone();
     match ctrlc::set_handler(move || {
-        // We've caught a ctrl-c, determine if it's the first time or an additional time.
+        // We've caught a signal, determine if it's the first time or an additional time.
         if caught_ctrlc.load(Ordering::SeqCst) {
-            warn!("caught another ctrl-c, exiting immediately...");
+            warn!("caught another ctrl-c or SIGTERM, exiting immediately...");
             std::process::exit(1);
         } else {
-            warn!("caught ctrl-c, stopping...");
+            warn!("caught ctrl-c or SIGTERM, stopping...");
             caught_ctrlc.store(true, Ordering::SeqCst);
         }
     }) {
@@ -154,6 +194,58 @@ mod tests {
         assert_eq!(parse_timespan("100hourblah"), 360000);
     }
 
+    #[test]
+    fn step_load() {
+        assert_eq!(parse_step_load("10@30s"), Ok(vec![(10, 30)]));
+        assert_eq!(
+            parse_step_load("10@30s,20@30,40@1m"),
+            Ok(vec![(10, 30), (20, 30), (40, 60)])
+        );
+        assert_eq!(parse_step_load(" 1@1s, 2@1h "), Ok(vec![(1, 1), (2, 3600)]));
+        // Steps require both users and a hold time.
+        assert!(parse_step_load("").is_err());
+        assert!(parse_step_load("10").is_err());
+        assert!(parse_step_load("@30s").is_err());
+        assert!(parse_step_load("10@").is_err());
+        assert!(parse_step_load("10@0s").is_err());
+        // Each step must launch more users.
+        assert!(parse_step_load("0@30s").is_err());
+        assert!(parse_step_load("20@30s,10@30s").is_err());
+        assert!(parse_step_load("10@30s,10@30s").is_err());
+    }
+
+    #[test]
+    fn resolve() {
+        assert_eq!(
+            parse_resolve("www.example.com:443:10.0.0.5").unwrap(),
+            (
+                "www.example.com".to_string(),
+                443,
+                IpAddr::from_str("10.0.0.5").unwrap()
+            )
+        );
+        // Hosts are case insensitive, and IPv6 addresses may be wrapped in brackets.
+        assert_eq!(
+            parse_resolve("WWW.Example.com:80:[::1]").unwrap(),
+            (
+                "www.example.com".to_string(),
+                80,
+                IpAddr::from_str("::1").unwrap()
+            )
+        );
+        assert_eq!(
+            parse_resolve("localhost:80:::1").unwrap().2.to_string(),
+            "::1"
+        );
+
+        assert!(parse_resolve("www.example.com").is_err());
+        assert!(parse_resolve("www.example.com:443").is_err());
+        assert!(parse_resolve(":443:10.0.0.5").is_err());
+        assert!(parse_resolve("www.example.com:https:10.0.0.5").is_err());
+        assert!(parse_resolve("www.example.com:0:10.0.0.5").is_err());
+        assert!(parse_resolve("www.example.com:443:backend").is_err());
+    }
+
     #[test]
     fn greatest_common_divisor() {
         assert_eq!(gcd(2, 4), 2);
@@ -166,67 +258,6 @@ mod tests {
         assert_eq!(gcd(gcd(25, 7425), gcd(15, 9025)), 5);
     }
 
-    #[test]
-    fn median_test() {
-        // Simple median test - add 3 numbers and pick the middle one.
-        let mut btree: BTreeMap<usize, usize> = BTreeMap::new();
-        btree.insert(1, 1);
-        btree.insert(2, 1);
-        btree.insert(3, 1);
-        // 1: 1, 2: 1, 3: 1
-        assert_eq!(median(&btree, 3, 1, 3), 2);
-        assert_eq!(median(&btree, 3, 1, 1), 1);
-        assert_eq!(median(&btree, 3, 3, 3), 3);
-        btree.insert(1, 2);
-        // 1: 2, 2: 1, 3: 1
-        // We don't do a true median, we find the first value that is positioned >= 1/2 way
-        // into the total btree size
-        assert_eq!(median(&btree, 3, 1, 3), 1);
-        btree.insert(4, 1);
-        btree.insert(5, 1);
-        // 1: 2, 2: 1, 3: 1, 4: 1, 5: 1
-        assert_eq!(median(&btree, 6, 1, 5), 2);
-        btree.insert(6, 1);
-        btree.insert(7, 2);
-        btree.insert(8, 1);
-        btree.insert(9, 2);
-        // 1: 2, 2: 1, 3: 1, 4: 1, 5: 1, 6: 1, 7: 2, 8: 1, 9: 2
-        assert_eq!(median(&btree, 12, 1, 9), 5);
-
-        // Confirm we're counting and not just returning the key.
-        let mut btree: BTreeMap<usize, usize> = BTreeMap::new();
-        btree.insert(2, 1);
-        btree.insert(5, 1);
-        btree.insert(25, 1);
-        // 2: 1, 5: 1, 25: 1
-        assert_eq!(median(&btree, 3, 2, 25), 5);
-        btree.insert(5, 3);
-        // 2: 1, 5: 3, 25: 1
-        assert_eq!(median(&btree, 4, 2, 25), 5);
-        btree.insert(25, 10);
-        // 2: 1, 5: 3, 25: 10
-        assert_eq!(median(&btree, 14, 2, 25), 25);
-        btree.insert(100, 5);
-        // 2: 1, 5: 3, 25: 10, 100: 5
-        assert_eq!(median(&btree, 19, 2, 100), 25);
-        btree.insert(100, 20);
-        // 2: 1, 5: 3, 25: 20, 100: 5
-        assert_eq!(median(&btree, 29, 2, 100), 100);
-
-        // We round response times, be sure we return min or max when appropriate.
-        let mut btree: BTreeMap<usize, usize> = BTreeMap::new();
-        btree.insert(100, 3);
-        btree.insert(210, 1);
-        btree.insert(240, 1);
-        // 100: 3, 210: 1, 240: 1
-        // Minimum is more than median, use minimum.
-        assert_eq!(median(&btree, 5, 101, 243), 101);
-        btree.insert(240, 1);
-        // 100: 3, 210: 1, 240: 5
-        // Maximum is less than median, use maximum.
-        assert_eq!(median(&btree, 9, 101, 239), 239);
-    }
-
     #[test]
     fn truncate() {
         assert_eq!(
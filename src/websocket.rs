@@ -0,0 +1,168 @@
+//! WebSocket connections held by a GooseUser.
+//!
+//! A [`GooseWebSocket`](./struct.GooseWebSocket.html) is opened with
+//! [`GooseUser::connect_websocket`](../goose/struct.GooseUser.html#method.connect_websocket)
+//! and can be used by tasks to send and receive messages. Opening the connection, and
+//! every message sent and received, is recorded in the statistics with the `WEBSOCKET`
+//! method, so each type of WebSocket activity is displayed as a separate request:
+//!  - `WEBSOCKET <name> connect`: how long the WebSocket handshake took;
+//!  - `WEBSOCKET <name> send`: how long it took to send each message;
+//!  - `WEBSOCKET <name> recv`: how long each message was waited for.
+//!
+//! The request counts of `send` and `recv` are the number of messages sent and received,
+//! and the bytes are the size of their payloads.
+
+use futures::{SinkExt, StreamExt};
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::goose::{GooseMethod, GooseRawRequest, GooseTaskError, GooseTaskResult};
+
+pub use tokio_tungstenite::tungstenite::Message;
+
+/// A WebSocket connection, see
+/// [`GooseUser::connect_websocket`](../goose/struct.GooseUser.html#method.connect_websocket).
+pub struct GooseWebSocket {
+    /// The underlying WebSocket stream.
+    stream: WebSocketStream<TcpStream>,
+    /// The name used to record statistics, defaults to the path connected to.
+    name: String,
+    /// The URL connected to.
+    url: String,
+    /// The Instant when the GooseUser that opened the connection started.
+    user_started: Instant,
+    /// Which GooseUser thread opened the connection.
+    user: usize,
+    /// Channel to parent, only set if statistics are enabled.
+    parent: Option<mpsc::UnboundedSender<GooseRawRequest>>,
+}
+impl GooseWebSocket {
+    /// Open a WebSocket connection and record how long the handshake took.
+    pub(crate) async fn connect(
+        url: &str,
+        name: &str,
+        user_started: Instant,
+        user: usize,
+        parent: Option<mpsc::UnboundedSender<GooseRawRequest>>,
+    ) -> Result<Self, GooseTaskError> {
+        let mut raw_request = GooseRawRequest::new(
+            GooseMethod::WEBSOCKET,
+            &format!("{} connect", name),
+            url,
+            user_started.elapsed().as_millis(),
+            user,
+        );
+        let started = Instant::now();
+        let connected = tokio_tungstenite::connect_async(url).await;
+        raw_request.response_time = started.elapsed().as_millis() as u64;
+        match &connected {
+            Ok((_, response)) => raw_request.status_code = response.status().as_u16(),
+            Err(_) => raw_request.success = false,
+        }
+        if let Some(parent) = parent.as_ref() {
+            parent.send(raw_request)?;
+        }
+        let (stream, _) = connected?;
+
+        Ok(GooseWebSocket {
+            stream,
+            name: name.to_string(),
+            url: url.to_string(),
+            user_started,
+            user,
+            parent,
+        })
+    }
+
+    /// Send a message, recording how long it took to send.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    /// use goose::websocket::Message;
+    ///
+    /// let mut task = task!(chat);
+    ///
+    /// async fn chat(user: &GooseUser) -> GooseTaskResult {
+    ///     let mut websocket = user.connect_websocket("/chat").await?;
+    ///     websocket.send(Message::text("hello")).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn send(&mut self, message: Message) -> GooseTaskResult {
+        let bytes = message.len() as u64;
+        let started = Instant::now();
+        let sent = self.stream.send(message).await;
+        self.record("send", started, bytes, sent.is_ok()).await?;
+        Ok(sent?)
+    }
+
+    /// Wait for the next message, recording how long it took to arrive. Returns `None`
+    /// once the server has closed the connection.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use goose::prelude::*;
+    /// use goose::websocket::Message;
+    ///
+    /// let mut task = task!(echo);
+    ///
+    /// async fn echo(user: &GooseUser) -> GooseTaskResult {
+    ///     let mut websocket = user.connect_websocket("/echo").await?;
+    ///     websocket.send(Message::text("hello")).await?;
+    ///     if let Some(message) = websocket.recv().await? {
+    ///         assert_eq!(message.into_text().unwrap(), "hello");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn recv(&mut self) -> Result<Option<Message>, GooseTaskError> {
+        let started = Instant::now();
+        match self.stream.next().await {
+            Some(Ok(message)) => {
+                self.record("recv", started, message.len() as u64, true)
+                    .await?;
+                Ok(Some(message))
+            }
+            Some(Err(e)) => {
+                self.record("recv", started, 0, false).await?;
+                Err(e.into())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Close the connection, waiting for the server to acknowledge it.
+    pub async fn close(mut self) -> GooseTaskResult {
+        Ok(self.stream.close(None).await?)
+    }
+
+    /// Send statistics about a message to the parent thread, if enabled.
+    async fn record(
+        &self,
+        action: &str,
+        started: Instant,
+        bytes: u64,
+        success: bool,
+    ) -> GooseTaskResult {
+        if let Some(parent) = self.parent.as_ref() {
+            let mut raw_request = GooseRawRequest::new(
+                GooseMethod::WEBSOCKET,
+                &format!("{} {}", self.name, action),
+                &self.url,
+                self.user_started.elapsed().as_millis(),
+                self.user,
+            );
+            raw_request.response_time = started.elapsed().as_millis() as u64;
+            raw_request.bytes = bytes;
+            raw_request.success = success;
+            parent.send(raw_request)?;
+        }
+
+        Ok(())
+    }
+}
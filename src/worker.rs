@@ -1,14 +1,22 @@
 use nng::*;
-use std::collections::HashMap;
-use std::sync::atomic::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::{thread, time};
 use url::Url;
 
-use crate::goose::{GooseMethod, GooseRequest, GooseUser, GooseUserCommand};
-use crate::manager::GooseUserInitializer;
+use crate::goose::{
+    self, GooseHistogram, GooseMethod, GooseRequest, GooseTask, GooseUser, GooseUserCommand,
+};
+use crate::manager::{GooseUserInitializer, GooseWorkerStats};
+use crate::stats::GooseCustomMetric;
 use crate::util;
 use crate::{get_worker_id, GooseAttack, GooseConfiguration, WORKER_ID};
 
+/// How often, in seconds, a worker without statistics to push sends a heartbeat to the
+/// manager instead.
+pub const HEARTBEAT_INTERVAL: usize = 1;
+
 // If pipe closes unexpectedly, exit.
 fn pipe_closed(_pipe: Pipe, event: PipeEvent) {
     if event == PipeEvent::RemovePost {
@@ -69,15 +77,33 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
     // "Fake" request for manager to validate this worker's load test hash.
     requests.insert(
         "load_test_hash".to_string(),
-        GooseRequest::new("none", GooseMethod::GET, goose_attack.stats.hash),
+        GooseRequest::new(
+            "none",
+            GooseMethod::GET,
+            goose_attack.stats.hash,
+            GooseHistogram::default(),
+        ),
     );
     debug!(
         "sending load test hash to manager: {}",
         goose_attack.stats.hash
     );
-    push_stats_to_manager(&manager, &requests, false);
+    // The manager authenticates this worker with its --gaggle-secret, if configured.
+    let handshake = GooseWorkerStats {
+        requests,
+        custom_metrics: BTreeMap::new(),
+        gaggle_secret: goose_attack.configuration.gaggle_secret.clone(),
+    };
+    let mut message = Message::new().unwrap();
+    serde_cbor::to_writer(&mut message, &handshake)
+        .map_err(|error| eprintln!("{:?}", error))
+        .expect("failed to serialize GooseWorkerStats");
+    manager
+        .try_send(message)
+        .map_err(|error| eprintln!("{:?}", error))
+        .expect("communication failure");
 
-    // Only send load_test_hash one time.
+    // Only send load_test_hash and gaggle_secret one time.
     requests = HashMap::new();
 
     let mut hatch_rate: Option<f32> = None;
@@ -108,6 +134,10 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
                         warn!("received EXIT command from manager");
                         std::process::exit(0);
                     }
+                    GooseUserCommand::REJECT => {
+                        error!("manager rejected this worker, --gaggle-secret must match the manager's --gaggle-secret and both must run the same load test build");
+                        std::process::exit(1);
+                    }
                     other => {
                         info!("received unknown command from manager: {:?}", other);
                     }
@@ -123,16 +153,28 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
             if worker_id == 0 {
                 worker_id = initializer.worker_id;
             }
-            let user = GooseUser::new(
+            let mut user = GooseUser::new_with_client_builder(
                 initializer.task_sets_index,
                 Url::parse(&initializer.base_url).unwrap(),
                 initializer.min_wait,
                 initializer.max_wait,
                 &initializer.config,
                 goose_attack.stats.hash,
+                goose_attack.client_builder.as_ref(),
             )
             .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
             .expect("failed to create socket");
+            for host in &initializer.rotate_hosts {
+                user.rotate_hosts.push(Url::parse(host).unwrap());
+            }
+            // Users start rotating from the host they'd otherwise be assigned.
+            if let Some(position) = initializer
+                .rotate_hosts
+                .iter()
+                .position(|host| host == &initializer.base_url)
+            {
+                user.rotate_hosts_position = Arc::new(AtomicUsize::new(position));
+            }
 
             weighted_users.push(user);
             if hatch_rate == None {
@@ -157,12 +199,18 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
         break;
     }
 
+    // Run worker_start_task, if defined, before starting any users.
+    if let Some(task) = &goose_attack.worker_start_task {
+        info!("[{}] running worker_start_task", get_worker_id());
+        run_worker_task(goose_attack, task, &config).await;
+    }
+
     info!("[{}] waiting for go-ahead from manager", get_worker_id());
 
     // Wait for the manager to send go-ahead to start the load test.
     loop {
         // Push statistics to manager to force a reply, waiting for RUN.
-        push_stats_to_manager(&manager, &requests, false);
+        push_stats_to_manager(&manager, &requests, &BTreeMap::new(), false);
         let msg = manager
             .recv()
             .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
@@ -215,16 +263,55 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
     }
     worker_goose_attack.weighted_users = weighted_users;
     worker_goose_attack.configuration.worker = true;
-    worker_goose_attack
+    let worker_goose_attack = worker_goose_attack
         .launch_users(sleep_duration, Some(manager))
         .await
         .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
-        .expect("failed to launch GooseAttack")
+        .expect("failed to launch GooseAttack");
+
+    // Run worker_stop_task, if defined, now that all users have stopped.
+    if let Some(task) = &goose_attack.worker_stop_task {
+        info!("[{}] running worker_stop_task", get_worker_id());
+        run_worker_task(goose_attack, task, &config).await;
+    }
+
+    worker_goose_attack
+}
+
+// Run a worker_start or worker_stop task one time, with a one-time-use user.
+async fn run_worker_task(
+    goose_attack: &GooseAttack,
+    task: &GooseTask,
+    config: &GooseConfiguration,
+) {
+    let config_host = config.host.first().cloned();
+    let user = goose::get_base_url(config_host, None, goose_attack.get_default_host()).and_then(
+        |base_url| {
+            GooseUser::single_with_client_builder(
+                base_url,
+                config,
+                goose_attack.client_builder.as_ref(),
+            )
+        },
+    );
+    match user {
+        Ok(mut user) => {
+            user.feeders = goose_attack.feeders.clone();
+            let function = task.function;
+            let _ = function(&user).await;
+        }
+        Err(error) => error!(
+            "[{}] failed to create user to run worker task: {:?}",
+            get_worker_id(),
+            error
+        ),
+    }
 }
 
 pub fn push_stats_to_manager(
     manager: &Socket,
     requests: &HashMap<String, GooseRequest>,
+    custom_metrics: &BTreeMap<String, GooseCustomMetric>,
     get_response: bool,
 ) -> bool {
     debug!(
@@ -232,10 +319,15 @@ pub fn push_stats_to_manager(
         get_worker_id(),
         requests.len()
     );
+    let worker_stats = GooseWorkerStats {
+        requests: requests.clone(),
+        custom_metrics: custom_metrics.clone(),
+        ..Default::default()
+    };
     let mut message = Message::new().unwrap();
-    serde_cbor::to_writer(&mut message, requests)
+    serde_cbor::to_writer(&mut message, &worker_stats)
         .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
-        .expect("failed to serialize empty Vec<GooseRequest>");
+        .expect("failed to serialize GooseWorkerStats");
 
     manager
         .try_send(message)
@@ -243,25 +335,48 @@ pub fn push_stats_to_manager(
         .expect("communication failure");
 
     if get_response {
-        // Wait for server to reply.
-        let msg = manager
-            .recv()
-            .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
-            .expect("error receiving manager message");
+        return receive_command_from_manager(manager);
+    }
+    true
+}
 
-        let command: GooseUserCommand = serde_cbor::from_reader(msg.as_slice())
+/// Tell the manager this worker is still alive when there are no statistics to push,
+/// returning false if the manager replies with EXIT.
+pub fn send_heartbeat_to_manager(manager: &Socket) -> bool {
+    trace!("[{}] sending heartbeat to manager", get_worker_id());
+    let mut message = Message::new().unwrap();
+    serde_cbor::to_writer(&mut message, &GooseUserCommand::HEARTBEAT)
+        .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
+        .expect("failed to serialize heartbeat");
+
+    manager
+        .try_send(message)
+        .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
+        .expect("communication failure");
+
+    receive_command_from_manager(manager)
+}
+
+// Wait for the manager to reply, returning false if the manager replied with EXIT.
+fn receive_command_from_manager(manager: &Socket) -> bool {
+    // Wait for server to reply.
+    let msg = manager
+        .recv()
+        .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
+        .expect("error receiving manager message");
+
+    let command: GooseUserCommand = serde_cbor::from_reader(msg.as_slice())
+        .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
+        .expect("invalid message");
+
+    if command == GooseUserCommand::EXIT {
+        info!("[{}] received EXIT command from manager", get_worker_id());
+        // Shutting down, register shutdown pipe handler.
+        manager
+            .pipe_notify(pipe_closed_during_shutdown)
             .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
-            .expect("invalid message");
-
-        if command == GooseUserCommand::EXIT {
-            info!("[{}] received EXIT command from manager", get_worker_id());
-            // Shutting down, register shutdown pipe handler.
-            manager
-                .pipe_notify(pipe_closed_during_shutdown)
-                .map_err(|error| eprintln!("{:?} worker_id({})", error, get_worker_id()))
-                .expect("failed to set up new pipe handler");
-            return false;
-        }
+            .expect("failed to set up new pipe handler");
+        return false;
     }
     true
 }
@@ -0,0 +1,85 @@
+use httpmock::Method::{GET, POST};
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+const LOGIN_PATH: &str = "/login";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get_named(ABOUT_PATH, "about").await?;
+    Ok(())
+}
+
+pub async fn post_login(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.post(LOGIN_PATH, "").await?;
+    Ok(())
+}
+
+#[test]
+fn test_aggregate_only() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(404)
+        .create_on(&server);
+    let login = Mock::new()
+        .expect_method(POST)
+        .expect_path(LOGIN_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.aggregate_only = true;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_about))
+                .register_task(task!(post_login)),
+        )
+        .execute()
+        .unwrap();
+
+    // Requests are only grouped by method, even if they're named.
+    assert_eq!(goose_stats.requests.len(), 2);
+    let get_requests = goose_stats.requests.get("GET (all requests)").unwrap();
+    assert_eq!(get_requests.success_count, index.times_called());
+    assert_eq!(get_requests.fail_count, about.times_called());
+    assert_eq!(
+        get_requests.response_time_counter,
+        index.times_called() + about.times_called()
+    );
+    let post_requests = goose_stats.requests.get("POST (all requests)").unwrap();
+    assert!(login.times_called() > 0);
+    assert_eq!(post_requests.success_count, login.times_called());
+}
+
+#[test]
+fn test_aggregate_only_requires_stats() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.aggregate_only = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,182 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const INDEX_BODY: &str = "<html>Goose</html>";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(INDEX_PATH).await?;
+    // The body can still be read after the bytes are counted.
+    assert_eq!(goose.response?.text().await?, INDEX_BODY);
+    Ok(())
+}
+
+pub async fn head_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.head(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn stream_index(user: &GooseUser) -> GooseTaskResult {
+    let request_builder = user.goose_get(INDEX_PATH).await?;
+    let goose = user.goose_send_raw(request_builder, None).await?;
+    // The body is streamed by the task, which reports how many bytes it read.
+    let mut bytes = 0;
+    let mut response = goose.response?;
+    while let Some(chunk) = response.chunk().await? {
+        bytes += chunk.len() as u64;
+    }
+    user.record_bytes(&goose.request, bytes).await?;
+    Ok(())
+}
+
+#[test]
+fn test_bytes() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .return_body(INDEX_BODY)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Every response body was counted.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.fail_count, 0);
+    assert_eq!(
+        index_request.bytes,
+        (INDEX_BODY.len() * index.times_called()) as u64
+    );
+    assert_eq!(goose_stats.summary().aggregated.bytes, index_request.bytes);
+}
+
+#[test]
+fn test_bytes_chunked() {
+    // Serve chunked responses without a content length, so the bytes must be counted
+    // as the task reads the body.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer);
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n6\r\n<html>\r\n7\r\nGoose</\r\n5\r\nhtml>\r\n0\r\n\r\n",
+            );
+        }
+    });
+
+    let server = MockServer::start();
+    let mut config = common::build_configuration(&server);
+    config.host = vec![format!("http://{}", address)];
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    let total_count = index_request.success_count + index_request.fail_count;
+    assert!(total_count > 0);
+    assert_eq!(index_request.fail_count, 0);
+    assert_eq!(index_request.bytes, (INDEX_BODY.len() * total_count) as u64);
+}
+
+#[test]
+fn test_bytes_head() {
+    // Responses to HEAD requests have the content length of the body they'd have
+    // returned, but no body.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 18\r\nConnection: close\r\n\r\n");
+        }
+    });
+
+    let server = MockServer::start();
+    let mut config = common::build_configuration(&server);
+    config.host = vec![format!("http://{}", address)];
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(head_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("HEAD /").unwrap();
+    assert!(index_request.success_count > 0);
+    assert_eq!(index_request.fail_count, 0);
+    assert_eq!(index_request.bytes, 0);
+}
+
+#[test]
+fn test_bytes_streamed() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .return_body(INDEX_BODY)
+        .create_on(&server);
+
+    const STATS_LOG_FILE: &str = "bytes-streamed-stats.log";
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.stats_log_file = STATS_LOG_FILE.to_string();
+    let callbacks = Arc::new(AtomicUsize::new(0));
+    let counter = callbacks.clone();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_request_callback(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .register_taskset(taskset!("LoadTest").register_task(task!(stream_index)))
+        .execute()
+        .unwrap();
+
+    // The bytes recorded by the task aren't counted as additional requests.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.success_count, index.times_called());
+    // Nor are they logged, or passed to the request callback, a second time.
+    let stats_log = std::fs::read_to_string(STATS_LOG_FILE).unwrap();
+    let _ = std::fs::remove_file(STATS_LOG_FILE);
+    assert_eq!(stats_log.lines().count(), index.times_called());
+    assert!(!stats_log.contains("\"streamed\":true"));
+    assert_eq!(callbacks.load(Ordering::SeqCst), index.times_called());
+    assert_eq!(
+        index_request.bytes,
+        (INDEX_BODY.len() * index.times_called()) as u64
+    );
+}
@@ -0,0 +1,53 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_client_builder() {
+    let server = MockServer::start();
+
+    // Only requests made by the custom client match.
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .expect_header("user-agent", "custom-agent")
+        .expect_header("x-custom-header", "custom value")
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_client_builder(|| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(
+                "X-Custom-Header",
+                reqwest::header::HeaderValue::from_static("custom value"),
+            );
+            reqwest::Client::builder()
+                .user_agent("custom-agent")
+                .default_headers(headers)
+        })
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(index_request.fail_count, 0);
+}
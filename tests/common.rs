@@ -6,31 +6,88 @@ pub fn build_configuration(server: &MockServer) -> GooseConfiguration {
     // Manually specify configuration for test, normally this is provided as
     // CLI options.
     GooseConfiguration {
-        host: server.url("/"),
+        config_file: "".to_string(),
+        host: vec![server.url("/")],
         users: Some(1),
         hatch_rate: 1,
         run_time: "1".to_string(),
+        shutdown_time: "".to_string(),
+        pause_file: "".to_string(),
+        iterations: 0,
+        seed: None,
+        step_load: "".to_string(),
         no_stats: true,
         status_codes: false,
+        server_timing: false,
         only_summary: false,
+        quiet: false,
+        running_stats_interval: 15,
+        running_percentiles: false,
         reset_stats: false,
+        warmup: "".to_string(),
+        warmup_requests: 0,
+        warmup_path: "".to_string(),
+        throughput_interval: 1,
+        throughput_log: "".to_string(),
+        show_slowest: 0,
+        reset_interval: "".to_string(),
+        co_correction: false,
+        aggregate_only: false,
         list: false,
+        list_json: false,
+        dry_run: false,
+        print_hash: false,
+        tags: "".to_string(),
+        exclude_tags: "".to_string(),
         verbose: 0,
         log_level: 0,
         log_file: "goose.log".to_string(),
         stats_log_file: "".to_string(),
         stats_log_format: "json".to_string(),
+        stats_log_columns: "".to_string(),
+        stats_log_append: false,
+        stats_log_required: false,
+        stats_percentiles: "".to_string(),
+        stats_max_response_time: 0,
+        stats_significant_figures: 0,
+        report_file: "".to_string(),
+        stats_summary_file: "".to_string(),
+        fail_if: vec![],
+        exit_on_error: false,
+        hatch_error_rate: None,
+        hatch_error_exit: false,
         debug_log_file: "".to_string(),
         debug_log_format: "json".to_string(),
+        debug_log_sample: 1.0,
+        log_requests: "".to_string(),
+        log_requests_body: false,
+        log_requests_sample: 100.0,
         throttle_requests: None,
+        max_in_flight: 6,
+        prometheus_port: None,
+        control_port: None,
         sticky_follow: false,
+        user_id: false,
+        user_id_header: "X-Goose-User".to_string(),
+        max_redirects: None,
+        timeout: "".to_string(),
+        connect_timeout: "".to_string(),
+        http2: false,
+        pool_max_idle: None,
+        tcp_keepalive: "".to_string(),
+        accept_invalid_certs: false,
+        proxy: "".to_string(),
+        proxy_user: "".to_string(),
+        resolve: vec![],
         manager: false,
         no_hash_check: false,
         expect_workers: 0,
+        worker_timeout: "".to_string(),
         manager_bind_host: "0.0.0.0".to_string(),
         manager_bind_port: 5115,
         worker: false,
         manager_host: "127.0.0.1".to_string(),
         manager_port: 5115,
+        gaggle_secret: "".to_string(),
     }
 }
@@ -0,0 +1,56 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_connection_options() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Make all requests with HTTP/2, reusing at most one idle connection, without
+    // verifying certificates.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.http2 = true;
+    config.pool_max_idle = Some(1);
+    config.tcp_keepalive = "30s".to_string();
+    config.accept_invalid_certs = true;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(index_request.fail_count, 0);
+}
+
+#[test]
+fn test_tcp_keepalive_invalid() {
+    let server = MockServer::start();
+
+    // The TCP keepalive interval must be at least 1 second.
+    let mut config = common::build_configuration(&server);
+    config.tcp_keepalive = "0s".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,60 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::{thread, time};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const CONTROL_PORT: u16 = 9_293;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Send a command to the control port once the load test is running, returning the
+/// first line of the response.
+fn send_command(command: &str) -> String {
+    thread::sleep(time::Duration::from_secs(2));
+    let mut stream =
+        TcpStream::connect(("127.0.0.1", CONTROL_PORT)).expect("failed to connect to control port");
+    stream.write_all(command.as_bytes()).unwrap();
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_control_add_users() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "4".to_string();
+    config.control_port = Some(CONTROL_PORT);
+    config.no_stats = false;
+
+    let controller = thread::spawn(|| send_command("add 2\n"));
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoint.
+    assert!(index.times_called() > 0);
+
+    // Confirm the users were added while the load test was running.
+    assert_eq!(controller.join().unwrap(), "adding 2 users\n");
+    assert_eq!(goose_stats.users, 3);
+}
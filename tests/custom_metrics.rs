@@ -0,0 +1,56 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    // Alternate between recording 2 and 4 items.
+    let items = if user.get_session_data::<bool>().await.unwrap_or(false) {
+        4.0
+    } else {
+        2.0
+    };
+    user.set_session_data(items < 3.0).await;
+    user.record_custom_metric("items", items).await?;
+    user.record_custom_metric("not a number", f64::NAN).await?;
+    Ok(())
+}
+
+#[test]
+/// Custom metrics recorded by tasks are aggregated separately from requests.
+fn test_custom_metrics() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Custom metrics aren't recorded as requests.
+    assert_eq!(goose_stats.requests.len(), 1);
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+
+    // Every value was recorded, ignoring values that aren't numbers.
+    assert_eq!(goose_stats.custom_metrics.len(), 1);
+    let items = goose_stats.custom_metrics.get("items").unwrap();
+    assert_eq!(items.count, index.times_called());
+    assert!(items.min > 1.9 && items.min < 2.1);
+    assert!(items.max > 3.9 && items.max < 4.1);
+    assert!(items.average() >= 2.0 && items.average() <= 4.0);
+}
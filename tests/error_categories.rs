@@ -0,0 +1,275 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use lazy_static::lazy_static;
+use std::net::{TcpListener, TcpStream};
+use std::{thread, time};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+const NOT_FOUND_PATH: &str = "/not-found";
+
+lazy_static! {
+    /// A port that nothing listens on, so connections are refused.
+    static ref REFUSED_URL: String = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/", listener.local_addr().unwrap())
+    };
+    /// A server that accepts connections but never responds, so requests time out.
+    static ref SILENT_URL: String = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let mut connections = Vec::new();
+            for stream in listener.incoming() {
+                connections.push(stream);
+            }
+        });
+        url
+    };
+    /// A server that never accepts connections, with a full backlog so new connections
+    /// can't be established and connecting times out.
+    static ref BACKLOGGED_URL: String = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut connections = Vec::new();
+            while let Ok(stream) =
+                TcpStream::connect_timeout(&address, time::Duration::from_millis(200))
+            {
+                connections.push(stream);
+            }
+            // Keep the backlog full, without ever accepting the connections.
+            let _listener = listener;
+            loop {
+                thread::sleep(time::Duration::from_secs(60));
+            }
+        });
+        // Wait for the backlog to fill up.
+        thread::sleep(time::Duration::from_secs(1));
+        format!("http://{}/", address)
+    };
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_refused(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get_named(&REFUSED_URL, "refused").await?;
+    Ok(())
+}
+
+pub async fn get_silent(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get_named(&SILENT_URL, "silent").await?;
+    Ok(())
+}
+
+pub async fn get_backlogged(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get_named(&BACKLOGGED_URL, "backlogged").await?;
+    Ok(())
+}
+
+pub async fn get_index_failure(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(INDEX_PATH).await?;
+    // The request succeeded, but the task considers it a failure.
+    let _ = user.set_failure("unexpected page", &mut goose.request, None, None);
+    Ok(())
+}
+
+pub async fn get_not_found_success(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(NOT_FOUND_PATH).await?;
+    // The request failed, but the task expects a 404.
+    user.set_success(&mut goose.request)?;
+    Ok(())
+}
+
+pub async fn get_login_failed(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get_named(INDEX_PATH, "login").await?;
+    let _ = user.set_failure_with_tag(
+        "login_failed",
+        "login page shown again",
+        &mut goose.request,
+        None,
+        None,
+    );
+    Ok(())
+}
+
+pub async fn get_cart_failed(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get_named(INDEX_PATH, "cart").await?;
+    let _ = user.set_failure_with_tag("empty_cart", "no items", &mut goose.request, None, None);
+    Ok(())
+}
+
+pub async fn get_cart_recovered(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get_named(INDEX_PATH, "recovered").await?;
+    let _ = user.set_failure_with_tag("empty_cart", "no items", &mut goose.request, None, None);
+    // The task changed its mind, the request is no longer a failure.
+    user.set_success(&mut goose.request)?;
+    Ok(())
+}
+
+#[test]
+/// Failed requests are counted by why they failed.
+fn test_error_categories() {
+    let server = MockServer::start();
+
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(&server);
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(NOT_FOUND_PATH)
+        .return_status(404)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.timeout = "1".to_string();
+    config.run_time = "2".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .set_scheduler(GooseScheduler::Serial)
+                .register_task(task!(get_error))
+                .register_task(task!(get_refused))
+                .register_task(task!(get_silent))
+                .register_task(task!(get_index_failure))
+                .register_task(task!(get_not_found_success)),
+        )
+        .execute()
+        .unwrap();
+
+    // Each request failed for a single reason, counted with every failure.
+    for (key, category) in &[
+        ("GET /error", GooseErrorCategory::HttpStatus),
+        ("GET refused", GooseErrorCategory::Connection),
+        ("GET silent", GooseErrorCategory::Timeout),
+        ("GET /", GooseErrorCategory::Other),
+    ] {
+        let request = goose_stats.requests.get(*key).unwrap();
+        assert!(request.fail_count > 0);
+        assert_eq!(request.error_categories.len(), 1);
+        assert_eq!(request.error_categories[category], request.fail_count);
+    }
+
+    // Failures set without a tag aren't counted by tag.
+    assert!(goose_stats.requests["GET /"].failure_tags.is_empty());
+
+    // Failures updated to a success are no longer counted.
+    let not_found = goose_stats.requests.get("GET /not-found").unwrap();
+    assert_eq!(not_found.fail_count, 0);
+    assert!(not_found.error_categories.is_empty());
+}
+
+#[test]
+/// Timing out while connecting is counted separately from timing out waiting for a
+/// response.
+fn test_connect_timeout() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.timeout = "5".to_string();
+    config.connect_timeout = "1".to_string();
+    config.run_time = "2".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .set_scheduler(GooseScheduler::Serial)
+                .register_task(task!(get_backlogged))
+                .register_task(task!(get_silent)),
+        )
+        .execute()
+        .unwrap();
+
+    for (key, category) in &[
+        ("GET backlogged", GooseErrorCategory::ConnectTimeout),
+        ("GET silent", GooseErrorCategory::Timeout),
+    ] {
+        let request = goose_stats.requests.get(*key).unwrap();
+        assert!(request.fail_count > 0);
+        assert_eq!(request.error_categories.len(), 1);
+        assert_eq!(request.error_categories[category], request.fail_count);
+    }
+}
+
+#[test]
+fn test_connect_timeout_invalid() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.connect_timeout = "0".to_string();
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--connect-timeout"),
+        _ => panic!("--connect-timeout must be at least 1 second"),
+    }
+}
+
+#[test]
+/// Requests tasks mark as failures with a tag are counted by tag, and the tag is logged.
+fn test_failure_tags() {
+    let server = MockServer::start();
+
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let debug_log = std::env::temp_dir().join("goose-failure-tags-debug.log");
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.debug_log_file = debug_log.to_str().unwrap().to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .set_scheduler(GooseScheduler::Serial)
+                .register_task(task!(get_login_failed))
+                .register_task(task!(get_cart_failed))
+                .register_task(task!(get_cart_recovered)),
+        )
+        .execute()
+        .unwrap();
+
+    let login = goose_stats.requests.get("GET login").unwrap();
+    assert!(login.fail_count > 0);
+    assert_eq!(login.failure_tags["login_failed"], login.fail_count);
+    let cart = goose_stats.requests.get("GET cart").unwrap();
+    assert_eq!(cart.failure_tags["empty_cart"], cart.fail_count);
+    // Failures updated to a success are no longer counted.
+    let recovered = goose_stats.requests.get("GET recovered").unwrap();
+    assert!(recovered.failure_tags.is_empty());
+
+    // Failures are summarized by tag.
+    let summary = goose_stats.summary();
+    assert_eq!(
+        summary.aggregated.failure_tags["login_failed"],
+        login.fail_count
+    );
+    let display = goose_stats.to_string();
+    assert!(display.contains(" Failure tag "));
+    assert!(display.contains(" empty_cart "));
+
+    // The tag is included with each failed request in the debug log.
+    let debug_log = std::fs::read_to_string(&debug_log).unwrap();
+    assert!(debug_log.contains(r#""failure_tag":"login_failed""#));
+}
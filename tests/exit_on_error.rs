@@ -0,0 +1,104 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time;
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+#[test]
+/// Without failures, --exit-on-error doesn't change how the load test ends.
+fn test_exit_on_error_passed() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.exit_on_error = true;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+/// The first failed request stops the load test long before --run-time.
+fn test_exit_on_error_failed() {
+    let server = MockServer::start();
+
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.exit_on_error = true;
+    config.run_time = "30".to_string();
+    config.shutdown_time = "30".to_string();
+    let started = time::Instant::now();
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_error)),
+        )
+        .execute();
+
+    assert!(started.elapsed() < time::Duration::from_secs(10));
+    match result {
+        Err(GooseError::RequestFailed { request }) => {
+            assert_eq!(request.url, server.url(ERROR_PATH));
+            assert_eq!(request.status_code, 503);
+            assert!(!request.success);
+        }
+        _ => panic!("expected the load test to exit on the failed request"),
+    }
+}
+
+#[test]
+/// Failed requests are only noticed when statistics are enabled.
+fn test_exit_on_error_no_stats() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.exit_on_error = true;
+    let result = crate::GooseAttack::initialize_with_config(config).setup();
+
+    match result {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--no-stats"),
+        _ => panic!("expected --exit-on-error to require statistics"),
+    }
+}
@@ -0,0 +1,95 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_fail_if_passed() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // No requests fail, and the mock server responds well within a minute.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.fail_if = vec!["error-rate>0%".to_string(), "GET / p95>60s".to_string()];
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+fn test_fail_if_failed() {
+    let server = MockServer::start();
+
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    // All requests fail, so the error rate threshold is exceeded.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.fail_if = vec!["error-rate>1%".to_string(), "max>60s".to_string()];
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_error)))
+        .execute();
+
+    assert!(error.times_called() > 0);
+    match result {
+        Err(GooseError::ThresholdsFailed { failures }) => {
+            assert_eq!(failures.len(), 1);
+            assert!(failures[0].starts_with("error-rate>1%"));
+        }
+        _ => panic!("expected the error rate threshold to fail"),
+    }
+}
+
+#[test]
+fn test_fail_if_invalid() {
+    let server = MockServer::start();
+
+    // Thresholds must compare a known metric to a value.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.fail_if = vec!["median>500ms".to_string()];
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Thresholds require statistics.
+    let mut config = common::build_configuration(&server);
+    config.fail_if = vec!["p95>500ms".to_string()];
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,61 @@
+use httpmock::Method::POST;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const LOGIN_PATH: &str = "/login";
+
+const USERS: usize = 4;
+
+pub async fn login(user: &GooseUser) -> GooseTaskResult {
+    let row = user.get_feeder_row("users").await?;
+    let request_builder = user.goose_post(LOGIN_PATH).await?;
+    let params = [
+        ("username", &row["username"]),
+        ("password", &row["password"]),
+    ];
+    let _goose = user.goose_send(request_builder.form(&params), None).await?;
+    Ok(())
+}
+
+#[test]
+fn test_feeder() {
+    let server = MockServer::start();
+
+    let foo = Mock::new()
+        .expect_method(POST)
+        .expect_path(LOGIN_PATH)
+        .expect_body("username=foo&password=secret")
+        .return_status(200)
+        .create_on(&server);
+    let bar = Mock::new()
+        .expect_method(POST)
+        .expect_path(LOGIN_PATH)
+        .expect_body("username=bar&password=hunter2")
+        .return_status(200)
+        .create_on(&server);
+
+    let path = std::env::temp_dir().join("goose-feeder-integration-test.csv");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, "username,password\nfoo,secret\nbar,hunter2\n").unwrap();
+
+    // Each user logs in once, wrapping around to the first row after the second user.
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_feeder("users", GooseFeeder::from_csv(path).unwrap())
+        .register_taskset(taskset!("LoadTest").register_task(task!(login).set_on_start()))
+        .execute()
+        .unwrap();
+
+    std::fs::remove_file(path).unwrap();
+
+    // Rows were handed out in order, each to one user at a time.
+    assert_eq!(foo.times_called(), USERS / 2);
+    assert_eq!(bar.times_called(), USERS / 2);
+}
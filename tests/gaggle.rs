@@ -57,7 +57,7 @@ fn test_gaggle() {
     // Start worker instance of the load test.
     let worker_handle = thread::spawn(move || {
         configuration.worker = true;
-        configuration.host = "".to_string();
+        configuration.host = Vec::new();
         configuration.users = None;
         configuration.no_stats = false;
         configuration.run_time = "".to_string();
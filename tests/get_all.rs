@@ -0,0 +1,99 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockRef, MockServer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time;
+
+mod common;
+
+use goose::prelude::*;
+
+const ASSET_PATHS: [&str; 3] = ["/style.css", "/script.js", "/logo.png"];
+
+// Each asset takes this long to load.
+const DELAY_MS: u64 = 500;
+
+// How long loading all assets took, in milliseconds.
+static ELAPSED_MS: AtomicU64 = AtomicU64::new(0);
+
+pub async fn get_assets(user: &GooseUser) -> GooseTaskResult {
+    let started = time::Instant::now();
+    let goose = user.get_all(&ASSET_PATHS).await?;
+    ELAPSED_MS.store(started.elapsed().as_millis() as u64, Ordering::SeqCst);
+
+    // Responses are returned in the order the paths were requested.
+    for (path, goose) in ASSET_PATHS.iter().zip(goose) {
+        assert_eq!(&goose.request.name, path);
+        assert!(goose.request.success);
+    }
+    Ok(())
+}
+
+fn mock_assets(server: &MockServer) -> Vec<MockRef<'_>> {
+    ASSET_PATHS
+        .iter()
+        .map(|path| {
+            Mock::new()
+                .expect_method(GET)
+                .expect_path(path)
+                .return_status(200)
+                .return_with_delay(time::Duration::from_millis(DELAY_MS))
+                .create_on(server)
+        })
+        .collect()
+}
+
+/// Load all assets once, making at most max_in_flight requests at once, and return how
+/// long it took.
+fn run_load_test(server: &MockServer, max_in_flight: usize) -> time::Duration {
+    let mut config = common::build_configuration(server);
+    config.run_time = "".to_string();
+    config.iterations = 1;
+    config.no_stats = false;
+    config.max_in_flight = max_in_flight;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_assets)))
+        .execute()
+        .unwrap();
+
+    // Each request is recorded individually.
+    for path in &ASSET_PATHS {
+        let request = goose_stats.requests.get(&format!("GET {}", path)).unwrap();
+        assert_eq!(request.success_count, 1);
+    }
+    time::Duration::from_millis(ELAPSED_MS.load(Ordering::SeqCst))
+}
+
+#[test]
+/// A user makes several requests at once, or one at a time with --max-in-flight 1.
+fn test_get_all() {
+    let server = MockServer::start();
+    let assets = mock_assets(&server);
+
+    // All assets load at once.
+    let elapsed = run_load_test(&server, 6);
+    assert!(elapsed < time::Duration::from_millis(DELAY_MS * 2));
+    for asset in &assets {
+        assert_eq!(asset.times_called(), 1);
+    }
+
+    // Assets load one at a time.
+    let elapsed = run_load_test(&server, 1);
+    assert!(elapsed >= time::Duration::from_millis(DELAY_MS * 3));
+    for asset in &assets {
+        assert_eq!(asset.times_called(), 2);
+    }
+}
+
+#[test]
+/// Users make at least one request at a time.
+fn test_max_in_flight_invalid() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.max_in_flight = 0;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
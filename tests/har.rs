@@ -0,0 +1,120 @@
+#![cfg(feature = "har")]
+
+use httpmock::Method::{GET, POST};
+use httpmock::{Mock, MockServer};
+use std::sync::{Arc, Mutex};
+use std::time;
+
+mod common;
+
+use goose::prelude::*;
+
+// The second request starts half a second after the first, and the stylesheet is
+// requested from another origin.
+const HAR: &str = r#"{"log": {"version": "1.2", "entries": [
+    {
+        "startedDateTime": "2021-03-01T12:30:05.000Z",
+        "request": {
+            "method": "GET",
+            "url": "https://example.com/search?q=goose",
+            "headers": [{"name": "Accept", "value": "text/html"}]
+        }
+    },
+    {
+        "startedDateTime": "2021-03-01T12:30:05.100Z",
+        "request": {"method": "GET", "url": "https://cdn.example.com/style.css"}
+    },
+    {
+        "startedDateTime": "2021-03-01T12:30:05.500Z",
+        "request": {
+            "method": "POST",
+            "url": "https://example.com/cart",
+            "headers": [{"name": "Content-Type", "value": "application/json"}],
+            "postData": {"mimeType": "application/json", "text": "{\"product\": 1}"}
+        }
+    }
+]}}"#;
+
+const ITERATIONS: usize = 2;
+
+#[test]
+/// A task generated from a HAR file replays its requests against the load test host,
+/// waiting between them as long as they were recorded apart.
+fn test_har() {
+    let server = MockServer::start();
+
+    let search = Mock::new()
+        .expect_method(GET)
+        .expect_path("/search")
+        .expect_query_param("q", "goose")
+        .expect_header("Accept", "text/html")
+        .return_status(200)
+        .return_with_delay(time::Duration::from_millis(300))
+        .create_on(&server);
+    let style = Mock::new()
+        .expect_method(GET)
+        .expect_path("/style.css")
+        .return_status(200)
+        .create_on(&server);
+    let cart = Mock::new()
+        .expect_method(POST)
+        .expect_path("/cart")
+        .expect_body(r#"{"product": 1}"#)
+        .return_status(201)
+        .create_on(&server);
+
+    let har_file = std::env::temp_dir().join("goose-har-test.har");
+    std::fs::write(&har_file, HAR).unwrap();
+    let har_path = har_file.to_str().unwrap();
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    config.no_stats = false;
+    let started = time::Instant::now();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_request_callback(move |request| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((request.name.clone(), request.elapsed));
+        })
+        .register_taskset(
+            taskset!("RecordedUser").register_task(
+                // Renaming the task doesn't change which requests it replays.
+                goose::har::task_from_har(har_path)
+                    .unwrap()
+                    .set_name("session"),
+            ),
+        )
+        .execute()
+        .unwrap();
+
+    assert_eq!(search.times_called(), ITERATIONS);
+    assert_eq!(style.times_called(), 0);
+    assert_eq!(cart.times_called(), ITERATIONS);
+    assert!(started.elapsed() >= time::Duration::from_millis(500) * ITERATIONS as u32);
+
+    // The cart is requested half a second after the search started, including the time
+    // the search took.
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), ITERATIONS * 2);
+    for pair in requests.chunks(2) {
+        assert_eq!(pair[0].0, "/search");
+        assert_eq!(pair[1].0, "/cart");
+        let gap = pair[1].1 - pair[0].1;
+        assert!(gap >= 500 && gap < 700, "{} ms between requests", gap);
+    }
+
+    // Statistics are grouped by path, without the query string.
+    assert!(goose_stats.requests.contains_key("GET /search"));
+    assert!(goose_stats.requests.contains_key("POST /cart"));
+
+    // Invalid HAR files are rejected.
+    std::fs::write(&har_file, r#"{"log": {"entries": []}}"#).unwrap();
+    assert!(goose::har::task_from_har(har_path).is_err());
+}
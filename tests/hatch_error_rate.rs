@@ -0,0 +1,98 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use goose::{GooseConfiguration, GooseError};
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Build a configuration launching 10 users at 2 per second against a failing server.
+fn build_configuration(server: &MockServer) -> GooseConfiguration {
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(500)
+        .create_on(server);
+
+    let mut config = common::build_configuration(server);
+    config.no_stats = false;
+    config.users = Some(10);
+    config.hatch_rate = 2;
+    config.hatch_error_rate = Some(50.0);
+    config
+}
+
+#[test]
+/// Users stop being launched once too many requests fail, but the load test continues.
+fn test_hatch_error_rate() {
+    let server = MockServer::start();
+    let config = build_configuration(&server);
+
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    assert!(goose_stats.users > 0);
+    assert!(goose_stats.users < 10);
+}
+
+#[test]
+/// With --hatch-error-exit, the load test also stops and fails.
+fn test_hatch_error_exit() {
+    let server = MockServer::start();
+    let mut config = build_configuration(&server);
+    config.hatch_error_exit = true;
+    // The load test stops long before the run time.
+    config.run_time = "60".to_string();
+
+    let started = std::time::Instant::now();
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute();
+
+    match result {
+        Err(GooseError::HatchAborted { detail }) => {
+            assert!(detail.contains("--hatch-error-rate 50%"))
+        }
+        _ => panic!("expected the load test to be aborted while launching users"),
+    }
+    assert!(started.elapsed().as_secs() < 30);
+}
+
+#[test]
+/// The error rate must be a percentage, and --hatch-error-exit requires it.
+fn test_hatch_error_rate_invalid() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.hatch_error_rate = Some(0.0);
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => {
+            assert_eq!(option, "--hatch-error-rate")
+        }
+        _ => panic!("expected --hatch-error-rate 0 to be invalid"),
+    }
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.hatch_error_exit = true;
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => {
+            assert_eq!(option, "--hatch-error-exit")
+        }
+        _ => panic!("expected --hatch-error-exit to require --hatch-error-rate"),
+    }
+}
@@ -0,0 +1,150 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockRef, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Mock the index of a server.
+fn mock_index(server: &MockServer) -> MockRef<'_> {
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(server)
+}
+
+/// Run a load test against a weighted pool of two hosts.
+fn run_load_test(
+    server: &MockServer,
+    first: &MockServer,
+    second: &MockServer,
+    rotate: bool,
+    users: usize,
+) {
+    let mut config = common::build_configuration(server);
+    // Don't override the pool of hosts with --host.
+    config.host = Vec::new();
+    config.users = Some(users);
+    config.hatch_rate = users;
+
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_hosts(&[(&first.url("/"), 2), (&second.url("/"), 1)])
+        .set_host_rotation(rotate)
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+}
+
+#[test]
+/// Users are distributed across hosts by weight, each user sticking to one host.
+fn test_hosts_sticky() {
+    let server = MockServer::start();
+    let first = MockServer::start();
+    let second = MockServer::start();
+    let first_index = mock_index(&first);
+    let second_index = mock_index(&second);
+
+    run_load_test(&server, &first, &second, false, 3);
+
+    // Two users load test the first host, one the second. Each user makes about the
+    // same number of requests.
+    assert!(first_index.times_called() > 0);
+    assert!(second_index.times_called() > 0);
+    assert!(first_index.times_called() > second_index.times_called());
+}
+
+#[test]
+/// With host rotation enabled, a single user sends requests to every host.
+fn test_hosts_rotation() {
+    let server = MockServer::start();
+    let first = MockServer::start();
+    let second = MockServer::start();
+    let first_index = mock_index(&first);
+    let second_index = mock_index(&second);
+
+    run_load_test(&server, &first, &second, true, 1);
+
+    assert!(first_index.times_called() > 0);
+    assert!(second_index.times_called() > 0);
+    // The first host has twice the weight, so receives about twice the requests.
+    assert!(first_index.times_called() >= second_index.times_called());
+}
+
+#[test]
+/// Every host in the pool is validated at startup.
+fn test_hosts_invalid() {
+    let server = MockServer::start();
+    let mut config = common::build_configuration(&server);
+    config.host = Vec::new();
+
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_hosts(&[(&server.url("/"), 1), ("not a host", 1)])
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute();
+    assert!(result.is_err());
+}
+
+#[test]
+/// With more than one --host, users are split across the hosts and statistics are
+/// grouped by host.
+fn test_hosts_compared() {
+    let first = MockServer::start();
+    let second = MockServer::start();
+    let first_index = mock_index(&first);
+    let second_index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(500)
+        .create_on(&second);
+
+    let mut config = common::build_configuration(&first);
+    config.host = vec![first.url("/"), second.url("/")];
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.no_stats = false;
+
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // One user load tests each host.
+    assert!(first_index.times_called() > 0);
+    assert!(second_index.times_called() > 0);
+
+    // Each host is summarized separately, named by its scheme, host and port.
+    let summary = goose_stats.summary();
+    assert_eq!(summary.hosts.len(), 2);
+    let first_host = summary
+        .hosts
+        .iter()
+        .find(|host| host.name == first.url(""))
+        .unwrap();
+    assert_eq!(first_host.success_count, first_index.times_called());
+    assert_eq!(first_host.fail_count, 0);
+    let second_host = summary
+        .hosts
+        .iter()
+        .find(|host| host.name == second.url(""))
+        .unwrap();
+    assert_eq!(second_host.success_count, 0);
+    assert_eq!(second_host.fail_count, second_index.times_called());
+
+    // Hosts are compared in the displayed statistics.
+    let displayed = goose_stats.to_string();
+    assert!(displayed.contains(" Host "));
+}
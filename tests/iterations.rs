@@ -0,0 +1,70 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+const USERS: usize = 2;
+const ITERATIONS: usize = 3;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_iterations() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index).set_weight(2).unwrap())
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Each user ran through all weighted tasks exactly ITERATIONS times.
+    assert_eq!(index.times_called(), USERS * ITERATIONS * 2);
+    assert_eq!(about.times_called(), USERS * ITERATIONS);
+}
+
+#[test]
+fn test_iterations_with_run_time() {
+    let server = MockServer::start();
+
+    // Iterations and run time are mutually exclusive.
+    let mut config = common::build_configuration(&server);
+    config.iterations = ITERATIONS;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,74 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::prelude::*;
+
+const FAST_PATH: &str = "/fast";
+const SLOW_PATH: &str = "/slow";
+
+pub async fn get_fast(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(FAST_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_slow(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(SLOW_PATH).await?;
+    // The response is still returned to the task.
+    assert!(goose.response.is_ok());
+    assert!(!goose.request.success);
+    Ok(())
+}
+
+#[test]
+/// Requests slower than the latency budget of their task are failures.
+fn test_latency_budget() {
+    let server = MockServer::start();
+
+    let fast = Mock::new()
+        .expect_method(GET)
+        .expect_path(FAST_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let slow = Mock::new()
+        .expect_method(GET)
+        .expect_path(SLOW_PATH)
+        .return_status(200)
+        .return_with_delay(Duration::from_millis(100))
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_fast).set_latency_budget(Duration::from_secs(5)))
+                .register_task(task!(get_slow).set_latency_budget(Duration::from_millis(20))),
+        )
+        .execute()
+        .unwrap();
+
+    assert!(fast.times_called() > 0);
+    assert!(slow.times_called() > 0);
+
+    // Requests within the budget succeed.
+    let fast_request = goose_stats.requests.get("GET /fast").unwrap();
+    assert_eq!(fast_request.fail_count, 0);
+    assert_eq!(fast_request.over_budget_count, 0);
+
+    // Every request that exceeded the budget is a failure.
+    let slow_request = goose_stats.requests.get("GET /slow").unwrap();
+    assert_eq!(slow_request.success_count, 0);
+    assert_eq!(slow_request.fail_count, slow.times_called());
+    assert_eq!(slow_request.over_budget_count, slow.times_called());
+    // All of them are counted under the same failure tag.
+    assert_eq!(slow_request.failure_tags.len(), 1);
+    assert_eq!(
+        slow_request.failure_tags["over_budget"],
+        slow.times_called()
+    );
+}
@@ -0,0 +1,116 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::fs;
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseConfiguration;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(INDEX_PATH).await?;
+    // The body is still available to the task when it's logged.
+    assert_eq!(goose.response?.text().await?, "index");
+    Ok(())
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+/// Mock an index and an error page, returning a configuration that logs requests.
+fn setup_server(server: &MockServer, log_file: &str) -> GooseConfiguration {
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .return_body("index")
+        .create_on(server);
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(server);
+
+    let mut config = common::build_configuration(server);
+    config.log_requests = log_file.to_string();
+    config
+}
+
+#[test]
+/// Every request is logged as a line of JSON, including the body if enabled.
+fn test_log_requests() {
+    let log_file = "log-requests.log";
+    let server = MockServer::start();
+    let mut config = setup_server(&server, log_file);
+    config.log_requests_body = true;
+
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_error)),
+        )
+        .execute()
+        .unwrap();
+
+    let log = fs::read_to_string(log_file).unwrap();
+    let lines: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!lines.is_empty());
+    for line in &lines {
+        if line["url"] == server.url(INDEX_PATH) {
+            assert_eq!(line["status_code"], 200);
+            assert_eq!(line["body"], "index");
+        } else {
+            assert_eq!(line["url"], server.url(ERROR_PATH));
+            assert_eq!(line["status_code"], 500);
+            assert_eq!(line["success"], false);
+        }
+    }
+
+    fs::remove_file(log_file).unwrap();
+}
+
+#[test]
+/// A custom request logger formats each line, and can skip requests.
+fn test_set_request_logger() {
+    let log_file = "set-request-logger.log";
+    let server = MockServer::start();
+    let config = setup_server(&server, log_file);
+
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_request_logger(|request| {
+            if request.success {
+                None
+            } else {
+                Some(format!("{} {}", request.status_code, request.url))
+            }
+        })
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_error)),
+        )
+        .execute()
+        .unwrap();
+
+    // Only the failed requests were logged.
+    let log = fs::read_to_string(log_file).unwrap();
+    assert!(log.lines().count() > 0);
+    for line in log.lines() {
+        assert_eq!(line, format!("500 {}", server.url(ERROR_PATH)));
+    }
+
+    fs::remove_file(log_file).unwrap();
+}
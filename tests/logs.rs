@@ -103,7 +103,196 @@ fn test_stat_logs_csv() {
     assert!(std::path::Path::new(STATS_LOG_FILE).exists());
     assert!(!std::path::Path::new(DEBUG_LOG_FILE).exists());
 
+    // The default columns include when each request was made.
+    let log = std::fs::read_to_string(STATS_LOG_FILE).unwrap();
+    assert!(log.starts_with("elapsed,timestamp,method,name,"));
+
+    cleanup_files(STATS_LOG_FILE, DEBUG_LOG_FILE);
+}
+
+#[test]
+/// The CSV log can be limited to a custom list of columns.
+fn test_stat_logs_csv_columns() {
+    const STATS_LOG_FILE: &str = "stats-csv-columns.log";
+    const DEBUG_LOG_FILE: &str = "debug-csv-columns.log";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.stats_log_file = STATS_LOG_FILE.to_string();
+    config.stats_log_format = "csv".to_string();
+    config.stats_log_columns = "task_set,name,status_code,bytes_sent".to_string();
+    config.no_stats = false;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoints.
+    assert!(index.times_called() > 0);
+
+    // Only the configured columns are logged, in order.
+    let log = std::fs::read_to_string(STATS_LOG_FILE).unwrap();
+    let mut lines = log.lines();
+    assert_eq!(lines.next(), Some("task_set,name,status_code,bytes_sent"));
+    assert_eq!(lines.next(), Some("\"LoadTest\",\"/\",200,0"));
+    assert!(lines.all(|line| line == "\"LoadTest\",\"/\",200,0"));
+
+    cleanup_files(STATS_LOG_FILE, DEBUG_LOG_FILE);
+
+    // Unknown columns are rejected.
+    let mut config = common::build_configuration(&server);
+    config.stats_log_file = STATS_LOG_FILE.to_string();
+    config.stats_log_format = "csv".to_string();
+    config.stats_log_columns = "name,size".to_string();
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Columns can only be selected for the CSV format.
+    let mut config = common::build_configuration(&server);
+    config.stats_log_file = STATS_LOG_FILE.to_string();
+    config.stats_log_columns = "name".to_string();
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
+
+#[test]
+/// With --stats-log-append, each load test adds to the existing CSV log without
+/// repeating the header.
+fn test_stat_logs_csv_append() {
+    const STATS_LOG_FILE: &str = "stats-csv-append.log";
+    const DEBUG_LOG_FILE: &str = "debug-csv-append.log";
+    const HEADER: &str = "name,status_code";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let run_load_test = |append: bool| {
+        let mut config = common::build_configuration(&server);
+        config.stats_log_file = STATS_LOG_FILE.to_string();
+        config.stats_log_format = "csv".to_string();
+        config.stats_log_columns = HEADER.to_string();
+        config.stats_log_append = append;
+        config.no_stats = false;
+        crate::GooseAttack::initialize_with_config(config)
+            .setup()
+            .unwrap()
+            .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+            .execute()
+            .unwrap();
+        std::fs::read_to_string(STATS_LOG_FILE).unwrap()
+    };
+
+    // Appending to a log that doesn't exist yet creates it with a header.
     cleanup_files(STATS_LOG_FILE, DEBUG_LOG_FILE);
+    let first_log = run_load_test(true);
+    assert!(first_log.starts_with(HEADER));
+    assert!(index.times_called() > 0);
+
+    // The second load test appends its requests without another header.
+    let second_log = run_load_test(true);
+    assert!(second_log.starts_with(&first_log));
+    assert!(second_log.len() > first_log.len());
+    assert_eq!(second_log.matches(HEADER).count(), 1);
+
+    // Without --stats-log-append, the log is overwritten.
+    let third_log = run_load_test(false);
+    assert!(third_log.starts_with(HEADER));
+    assert!(third_log.len() < second_log.len());
+
+    cleanup_files(STATS_LOG_FILE, DEBUG_LOG_FILE);
+
+    // There's nothing to append to without a stats log.
+    let mut config = common::build_configuration(&server);
+    config.stats_log_append = true;
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
+
+#[test]
+/// The --throughput-log has one row per throughput bucket, matching the throughput time
+/// series in the statistics.
+fn test_throughput_log() {
+    const THROUGHPUT_LOG_FILE: &str = "throughput.log";
+    const DEBUG_LOG_FILE: &str = "debug-throughput.log";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.throughput_log = THROUGHPUT_LOG_FILE.to_string();
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.run_time = "2".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let log = std::fs::read_to_string(THROUGHPUT_LOG_FILE).unwrap();
+    let mut lines = log.lines();
+    assert_eq!(
+        lines.next(),
+        Some("timestamp,second,requests,fails,users,paused")
+    );
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    for line in lines {
+        // The load test wasn't paused.
+        let (counts, paused) = line.rsplit_once(',').unwrap();
+        assert_eq!(paused, "false");
+        rows.push(counts.split(',').map(|v| v.parse().unwrap()).collect());
+    }
+    assert_eq!(rows.len(), goose_stats.throughput().len());
+    for (row, bucket) in rows.iter().zip(goose_stats.throughput()) {
+        assert_eq!(row[1], bucket.second);
+        assert_eq!(row[2], bucket.requests);
+        assert_eq!(row[3], bucket.fails);
+        assert_eq!(row[4], bucket.users);
+        // Timestamps are the start of each bucket.
+        assert_eq!(row[0] - rows[0][0], bucket.second);
+    }
+    // Every request is counted, and both users were running.
+    assert_eq!(
+        rows.iter().map(|row| row[2]).sum::<usize>(),
+        index.times_called()
+    );
+    assert_eq!(rows.iter().map(|row| row[4]).max(), Some(2));
+
+    cleanup_files(THROUGHPUT_LOG_FILE, DEBUG_LOG_FILE);
+
+    // There's no throughput to log without statistics.
+    let mut config = common::build_configuration(&server);
+    config.throughput_log = THROUGHPUT_LOG_FILE.to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
 }
 
 #[test]
@@ -269,3 +458,101 @@ fn test_stats_and_debug_logs() {
 
     cleanup_files(STATS_LOG_FILE, DEBUG_LOG_FILE);
 }
+
+pub async fn log_error(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(ERROR_PATH).await?;
+    user.log_debug("error", Some(&goose.request), None, None)
+}
+
+#[test]
+fn test_debug_logs_sample() {
+    const DEBUG_LOG_FILE: &str = "debug-sample.log";
+
+    let server = MockServer::start();
+
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.debug_log_file = DEBUG_LOG_FILE.to_string();
+    config.debug_log_sample = 0.1;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(log_error)))
+        .execute()
+        .unwrap();
+
+    // Confirm only a sample of the errors was logged.
+    let debug_log = std::fs::read_to_string(DEBUG_LOG_FILE).unwrap();
+    let logged = debug_log.lines().count();
+    assert!(logged > 0);
+    assert!(logged < error.times_called());
+
+    cleanup_files("", DEBUG_LOG_FILE);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+/// When the stats log can't be written, for example because the disk is full, it's
+/// disabled and the load test continues.
+fn test_stat_logs_disk_full() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    // Every write to /dev/full fails as if the disk is full.
+    config.stats_log_file = "/dev/full".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Statistics are still collected.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+/// With --stats-log-required, the load test stops and fails if the stats log can't be
+/// written.
+fn test_stat_logs_required() {
+    let server = MockServer::start();
+
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.stats_log_file = "/dev/full".to_string();
+    config.stats_log_required = true;
+    config.no_stats = false;
+    // The load test stops long before the run time.
+    config.run_time = "60".to_string();
+    let started = std::time::Instant::now();
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute();
+
+    match result {
+        Err(goose::GooseError::Io(_)) => (),
+        _ => panic!("expected the load test to fail writing the stats log"),
+    }
+    assert!(started.elapsed().as_secs() < 30);
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "gaggle")]
+
+mod common;
+
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::process::Command;
+use std::{env, thread, time};
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const WORKER_START_PATH: &str = "/worker/start";
+// Use a different port than the other Gaggle tests, as the tests may run at the same time.
+const MANAGER_PORT: u16 = 5_117;
+// Set when this test runs in a child process as the worker that gets lost.
+const LOST_WORKER: &str = "GOOSE_TEST_LOST_WORKER";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn worker_start(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(WORKER_START_PATH).await?;
+    Ok(())
+}
+
+/// Register the same load test on the manager and the workers, so their hashes match.
+fn load_test(configuration: GooseConfiguration) -> GooseAttack {
+    crate::GooseAttack::initialize_with_config(configuration)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("User").register_task(task!(get_index)))
+        .worker_start(task!(worker_start))
+}
+
+fn worker_configuration(server: &MockServer) -> GooseConfiguration {
+    let mut configuration = common::build_configuration(server);
+    configuration.manager_port = MANAGER_PORT;
+    configuration.worker = true;
+    configuration.host = Vec::new();
+    configuration.users = None;
+    configuration.run_time = "".to_string();
+    configuration
+}
+
+/// Wait up to 10 seconds for the mock to be called the expected number of times.
+fn wait_for_calls(mock: &Mock, expected: usize) {
+    let started = time::Instant::now();
+    while mock.times_called() < expected && started.elapsed().as_secs() < 10 {
+        thread::sleep(time::Duration::from_millis(100));
+    }
+    assert_eq!(mock.times_called(), expected);
+}
+
+#[test]
+/// A worker connecting after a worker stopped responding takes over its users.
+fn test_replace_lost_worker() {
+    let server = MockServer::start();
+
+    // Run as the worker that gets lost, until the test kills this process.
+    if env::var(LOST_WORKER).is_ok() {
+        let _goose_stats = load_test(worker_configuration(&server)).execute().unwrap();
+        return;
+    }
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let start = Mock::new()
+        .expect_method(GET)
+        .expect_path(WORKER_START_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Start manager instance of the load test.
+    let mut manager_configuration = common::build_configuration(&server);
+    manager_configuration.manager_bind_port = MANAGER_PORT;
+    let manager_handle = thread::spawn(move || {
+        manager_configuration.users = Some(4);
+        manager_configuration.hatch_rate = 4;
+        manager_configuration.manager = true;
+        manager_configuration.expect_workers = 2;
+        manager_configuration.worker_timeout = "1".to_string();
+        manager_configuration.run_time = "8".to_string();
+        load_test(manager_configuration).execute().unwrap()
+    });
+
+    // Start the worker that gets lost in a child process, so it can be killed.
+    let mut lost_worker = Command::new(env::current_exe().unwrap())
+        .args(&["test_replace_lost_worker", "--exact"])
+        .env(LOST_WORKER, "1")
+        .spawn()
+        .unwrap();
+    let configuration = worker_configuration(&server);
+    let worker_handle = thread::spawn(move || {
+        let _goose_stats = load_test(configuration).execute().unwrap();
+    });
+
+    // Lose a worker once the load test is running.
+    wait_for_calls(&start, 2);
+    thread::sleep(time::Duration::from_secs(2));
+    lost_worker.kill().unwrap();
+    let _ = lost_worker.wait();
+
+    // Start a replacement worker once the manager gave up on the lost worker. A worker
+    // that isn't accepted is told to exit, and doesn't run its start task.
+    thread::sleep(time::Duration::from_secs(2));
+    let calls_before_replacement = index.times_called();
+    let configuration = worker_configuration(&server);
+    let replacement_handle = thread::spawn(move || {
+        let _goose_stats = load_test(configuration).execute().unwrap();
+    });
+    wait_for_calls(&start, 3);
+
+    // Wait for the load test to finish.
+    let _ = replacement_handle.join();
+    let _ = worker_handle.join();
+    let goose_stats = manager_handle.join().unwrap();
+
+    // The manager lost one worker, and the load test continued with its replacement.
+    assert_eq!(goose_stats.lost_workers, 1);
+    assert!(index.times_called() > calls_before_replacement);
+}
@@ -0,0 +1,103 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const FOO_PATH: &str = "/foo";
+const BAR_PATH: &str = "/bar";
+
+const USERS: usize = 4;
+
+pub async fn foo_start(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(FOO_PATH).await?;
+    Ok(())
+}
+
+pub async fn bar_start(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(BAR_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_max_users() {
+    let server = MockServer::start();
+
+    let foo = Mock::new()
+        .expect_method(GET)
+        .expect_path(FOO_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let bar = Mock::new()
+        .expect_method(GET)
+        .expect_path(BAR_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("Foo")
+                .set_weight(3)
+                .unwrap()
+                .set_max_users(1)
+                .unwrap()
+                .register_task(task!(foo_start).set_on_start()),
+        )
+        .register_taskset(taskset!("Bar").register_task(task!(bar_start).set_on_start()))
+        .execute()
+        .unwrap();
+
+    // Each user runs its on_start task once. Foo is weighted to get 3 of every 4 users,
+    // but is limited to 1 user, so its remaining slots are redistributed to Bar.
+    assert_eq!(foo.times_called(), 1);
+    assert_eq!(bar.times_called(), USERS - 1);
+}
+
+#[test]
+fn test_max_users_all_limited() {
+    let server = MockServer::start();
+
+    let foo = Mock::new()
+        .expect_method(GET)
+        .expect_path(FOO_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let bar = Mock::new()
+        .expect_method(GET)
+        .expect_path(BAR_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("Foo")
+                .set_max_users(1)
+                .unwrap()
+                .register_task(task!(foo_start).set_on_start()),
+        )
+        .register_taskset(
+            taskset!("Bar")
+                .set_max_users(2)
+                .unwrap()
+                .register_task(task!(bar_start).set_on_start()),
+        )
+        .execute()
+        .unwrap();
+
+    // When all task sets reach their maximum, fewer users are launched.
+    assert_eq!(goose_stats.users, 3);
+    assert_eq!(foo.times_called(), 1);
+    assert_eq!(bar.times_called(), 2);
+}
@@ -0,0 +1,68 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::prelude::*;
+
+const FAST_PATH: &str = "/fast";
+const SLOW_PATH: &str = "/slow";
+
+pub async fn get_fast(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(FAST_PATH).await?;
+    // The response is still returned to the task.
+    assert!(goose.response.is_ok());
+    assert!(!goose.request.success);
+    Ok(())
+}
+
+pub async fn get_slow(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(SLOW_PATH).await?;
+    Ok(())
+}
+
+#[test]
+/// Requests faster than the minimum response time of their task are failures.
+fn test_min_response_time() {
+    let server = MockServer::start();
+
+    let fast = Mock::new()
+        .expect_method(GET)
+        .expect_path(FAST_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let slow = Mock::new()
+        .expect_method(GET)
+        .expect_path(SLOW_PATH)
+        .return_status(200)
+        .return_with_delay(Duration::from_millis(100))
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_fast).set_min_response_time(Duration::from_secs(5)))
+                .register_task(task!(get_slow).set_min_response_time(Duration::from_millis(20))),
+        )
+        .execute()
+        .unwrap();
+
+    assert!(fast.times_called() > 0);
+    assert!(slow.times_called() > 0);
+
+    // Every request that was faster than the minimum is a failure.
+    let fast_request = goose_stats.requests.get("GET /fast").unwrap();
+    assert_eq!(fast_request.success_count, 0);
+    assert_eq!(fast_request.fail_count, fast.times_called());
+    assert_eq!(fast_request.too_fast_count, fast.times_called());
+
+    // Requests slower than the minimum succeed.
+    let slow_request = goose_stats.requests.get("GET /slow").unwrap();
+    assert_eq!(slow_request.fail_count, 0);
+    assert_eq!(slow_request.too_fast_count, 0);
+}
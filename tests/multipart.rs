@@ -0,0 +1,98 @@
+use httpmock::MockServer;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+mod common;
+
+use goose::prelude::*;
+
+const UPLOAD_PATH: &str = "/upload";
+const FILE_CONTENTS: &str = "a file uploaded by a goose";
+
+/// The file uploaded from disk, tasks can't capture a temporary path.
+fn upload_file() -> PathBuf {
+    std::env::temp_dir().join("goose-multipart-test.txt")
+}
+
+pub async fn upload(user: &GooseUser) -> GooseTaskResult {
+    let form = GooseMultipart::new()
+        .text("title", "Goose")
+        .bytes("note", b"in memory".to_vec(), "note.txt", "text/plain")
+        .file("upload", upload_file(), "text/plain");
+    let goose = user.post_multipart(UPLOAD_PATH, form).await?;
+    assert!(goose.request.success);
+    Ok(())
+}
+
+/// Start a server in its own thread, sending the body of every request it receives to the
+/// returned channel. The mock server only sees the first chunk of a streamed body.
+fn start_upload_server() -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                let line = line.to_lowercase();
+                if let Some(length) = line.strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let _ = sender.send(String::from_utf8(body).unwrap());
+            let _ = reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+    (format!("http://{}", address), receiver)
+}
+
+#[test]
+/// Multipart forms are uploaded with files streamed from disk, counting the bytes sent.
+fn test_post_multipart() {
+    fs::write(upload_file(), FILE_CONTENTS).unwrap();
+    let (host, bodies) = start_upload_server();
+
+    let server = MockServer::start();
+    let mut config = common::build_configuration(&server);
+    config.host = vec![host];
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(upload)))
+        .execute()
+        .unwrap();
+
+    let bodies: Vec<String> = bodies.try_iter().collect();
+    assert!(!bodies.is_empty());
+    for body in &bodies {
+        assert!(body.contains("name=\"title\""));
+        assert!(body.contains("filename=\"note.txt\""));
+        assert!(body.contains("in memory"));
+        assert!(body.contains("filename=\"goose-multipart-test.txt\""));
+        assert!(body.contains(FILE_CONTENTS));
+    }
+
+    // Every byte of every form was counted.
+    let upload_request = goose_stats.requests.get("POST /upload").unwrap();
+    assert_eq!(upload_request.success_count, bodies.len());
+    assert_eq!(upload_request.fail_count, 0);
+    let bytes_sent: usize = bodies.iter().map(|body| body.len()).sum();
+    assert_eq!(upload_request.bytes_sent, bytes_sent as u64);
+
+    fs::remove_file(upload_file()).unwrap();
+}
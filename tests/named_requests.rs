@@ -0,0 +1,134 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const PRODUCT_PATH: &str = "/product/";
+const ABOUT_PATH: &str = "/about.html";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_product(user: &GooseUser) -> GooseTaskResult {
+    let id = goose::data::random_int(1, 1_000);
+    let _goose = user
+        .get_named(&format!("{}{}", PRODUCT_PATH, id), "/product/:id")
+        .await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_named_requests() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let product = Mock::new()
+        .expect_method(GET)
+        .expect_path_contains(PRODUCT_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_product))
+                .register_task(task!(get_about).set_name("about")),
+        )
+        .execute()
+        .unwrap();
+
+    // Requests of dynamic paths are grouped under the request name.
+    assert!(product.times_called() > 0);
+    let product_request = goose_stats.requests.get("GET /product/:id").unwrap();
+    assert_eq!(product_request.success_count, product.times_called());
+
+    // Requests made by a named task are grouped under the task name.
+    let about_request = goose_stats.requests.get("GET about").unwrap();
+    assert_eq!(about_request.success_count, about.times_called());
+
+    // The task name doesn't affect requests made by the tasks that follow it.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(goose_stats.requests.len(), 3);
+}
+
+#[test]
+/// Requests made by users of a task set with a name prefix are grouped separately.
+fn test_name_prefix() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let product = Mock::new()
+        .expect_method(GET)
+        .expect_path_contains(PRODUCT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("Browse")
+                .register_task(task!(get_index))
+                .register_task(task!(get_product)),
+        )
+        .register_taskset(
+            taskset!("Admin")
+                .set_name_prefix("Admin: ")
+                .register_task(task!(get_index))
+                .register_task(task!(get_product)),
+        )
+        .execute()
+        .unwrap();
+
+    // Both task sets request the same paths, but are recorded separately.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    let admin_index_request = goose_stats.requests.get("GET Admin: /").unwrap();
+    assert!(index_request.success_count > 0);
+    assert!(admin_index_request.success_count > 0);
+    assert_eq!(
+        index_request.success_count + admin_index_request.success_count,
+        index.times_called()
+    );
+
+    // Named requests are also prefixed.
+    let product_request = goose_stats.requests.get("GET /product/:id").unwrap();
+    let admin_product_request = goose_stats.requests.get("GET Admin: /product/:id").unwrap();
+    assert_eq!(
+        product_request.success_count + admin_product_request.success_count,
+        product.times_called()
+    );
+    assert_eq!(goose_stats.requests.len(), 4);
+}
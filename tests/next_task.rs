@@ -0,0 +1,147 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockRef, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const START_PATH: &str = "/start";
+const BROWSE_PATH: &str = "/browse";
+const CHECKOUT_PATH: &str = "/checkout";
+
+const USERS: usize = 2;
+const ITERATIONS: usize = 3;
+
+pub async fn start_and_checkout(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(START_PATH).await?;
+    // Jump straight to the checkout task instead of browsing.
+    user.set_next_task(2);
+    Ok(())
+}
+
+pub async fn start_and_skip(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(START_PATH).await?;
+    // Don't browse, continue with the task after it.
+    user.skip_next_task();
+    Ok(())
+}
+
+pub async fn start_invalid(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(START_PATH).await?;
+    // There is no such task, so the scheduled task runs.
+    user.set_next_task(10);
+    Ok(())
+}
+
+pub async fn start_on_start(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(START_PATH).await?;
+    // Browsing is an on_start task, so the scheduled task runs.
+    user.set_next_task(1);
+    Ok(())
+}
+
+pub async fn browse(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(BROWSE_PATH).await?;
+    Ok(())
+}
+
+pub async fn checkout(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(CHECKOUT_PATH).await?;
+    Ok(())
+}
+
+fn mock_path<'a>(server: &'a MockServer, path: &str) -> MockRef<'a> {
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(path)
+        .return_status(200)
+        .create_on(server)
+}
+
+/// Run each user through ITERATIONS iterations of a serial task set starting with the
+/// specified task, optionally browsing only when each user starts.
+fn run_load_test(server: &MockServer, start: GooseTask, browse_on_start: bool) {
+    let browse = if browse_on_start {
+        task!(browse).set_on_start()
+    } else {
+        task!(browse)
+    };
+    let mut config = common::build_configuration(server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .set_scheduler(GooseScheduler::Serial)
+                .register_task(start)
+                .register_task(browse)
+                .register_task(task!(checkout)),
+        )
+        .execute()
+        .unwrap();
+}
+
+#[test]
+/// The chosen task runs in place of the scheduled task, then the schedule continues.
+fn test_set_next_task() {
+    let server = MockServer::start();
+    let start = mock_path(&server, START_PATH);
+    let browse = mock_path(&server, BROWSE_PATH);
+    let checkout = mock_path(&server, CHECKOUT_PATH);
+
+    run_load_test(&server, task!(start_and_checkout), false);
+
+    assert_eq!(start.times_called(), USERS * ITERATIONS);
+    assert_eq!(browse.times_called(), 0);
+    assert_eq!(checkout.times_called(), USERS * ITERATIONS * 2);
+}
+
+#[test]
+/// The scheduled task is skipped, then the schedule continues.
+fn test_skip_next_task() {
+    let server = MockServer::start();
+    let start = mock_path(&server, START_PATH);
+    let browse = mock_path(&server, BROWSE_PATH);
+    let checkout = mock_path(&server, CHECKOUT_PATH);
+
+    run_load_test(&server, task!(start_and_skip), false);
+
+    assert_eq!(start.times_called(), USERS * ITERATIONS);
+    assert_eq!(browse.times_called(), 0);
+    assert_eq!(checkout.times_called(), USERS * ITERATIONS);
+}
+
+#[test]
+/// Choosing a task that doesn't exist runs the scheduled task.
+fn test_set_next_task_invalid() {
+    let server = MockServer::start();
+    let start = mock_path(&server, START_PATH);
+    let browse = mock_path(&server, BROWSE_PATH);
+    let checkout = mock_path(&server, CHECKOUT_PATH);
+
+    run_load_test(&server, task!(start_invalid), false);
+
+    assert_eq!(start.times_called(), USERS * ITERATIONS);
+    assert_eq!(browse.times_called(), USERS * ITERATIONS);
+    assert_eq!(checkout.times_called(), USERS * ITERATIONS);
+}
+
+#[test]
+/// Choosing a task the scheduler can't run, like an on_start task, runs the scheduled task.
+fn test_set_next_task_on_start() {
+    let server = MockServer::start();
+    let start = mock_path(&server, START_PATH);
+    let browse = mock_path(&server, BROWSE_PATH);
+    let checkout = mock_path(&server, CHECKOUT_PATH);
+
+    run_load_test(&server, task!(start_on_start), true);
+
+    assert_eq!(start.times_called(), USERS * ITERATIONS);
+    // Browsing only ran once when each user started.
+    assert_eq!(browse.times_called(), USERS);
+    assert_eq!(checkout.times_called(), USERS * ITERATIONS);
+}
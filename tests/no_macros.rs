@@ -0,0 +1,90 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const LOGIN_PATH: &str = "/login";
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+const ITERATIONS: usize = 2;
+
+pub async fn login(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(LOGIN_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+/// Build a task set at runtime from a list of functions, without the macros.
+fn build_taskset(tasks: &[(&str, GooseTaskFunction, usize, usize)]) -> GooseTaskSet {
+    let mut taskset = GooseTaskSet::new("LoadTest").set_weight(1).unwrap();
+    for (name, function, weight, sequence) in tasks {
+        taskset = taskset.register_task(
+            GooseTask::new(*function)
+                .set_name(name)
+                .set_weight(*weight)
+                .unwrap()
+                .set_sequence(*sequence),
+        );
+    }
+    taskset
+}
+
+#[test]
+/// Load tests can be built entirely without the task! and taskset! macros.
+fn test_no_macros() {
+    let server = MockServer::start();
+
+    let login_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path(LOGIN_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let taskset = build_taskset(&[
+        ("index", |user| Box::pin(get_index(user)), 3, 1),
+        ("about", |user| Box::pin(get_about(user)), 1, 2),
+    ])
+    .register_task(
+        GooseTask::new(|user| Box::pin(login(user)))
+            .set_name("login")
+            .set_on_start(),
+    );
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset)
+        .execute()
+        .unwrap();
+
+    // The on_start task runs once, weighted tasks run weight times per iteration.
+    assert_eq!(login_mock.times_called(), 1);
+    assert_eq!(index.times_called(), ITERATIONS * 3);
+    assert_eq!(about.times_called(), ITERATIONS);
+    assert_eq!(goose_stats.users, 1);
+}
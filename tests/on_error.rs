@@ -0,0 +1,95 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(INDEX_PATH).await?;
+    // Treat every response as a failure, so the on_error function is invoked.
+    user.set_failure("index failed", &mut goose.request, None, None)
+}
+
+pub async fn report_error(user: &GooseUser, error: &GooseTaskError) -> GooseTaskResult {
+    // Report the path of the failed request.
+    if let GooseTaskError::RequestFailed { raw_request } = error {
+        let _goose = user
+            .get(&format!("{}?failed={}", ERROR_PATH, raw_request.name))
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn failing_report_error(user: &GooseUser, _error: &GooseTaskError) -> GooseTaskResult {
+    let mut goose = user.get(ERROR_PATH).await?;
+    user.set_failure("on_error failed", &mut goose.request, None, None)
+}
+
+#[test]
+fn test_on_error() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .expect_query_param("failed", INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let config = common::build_configuration(&server);
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .set_on_error(|user, error| Box::pin(report_error(user, error))),
+        )
+        .execute()
+        .unwrap();
+
+    // The on_error function ran once for each failed task.
+    assert!(index.times_called() > 0);
+    assert_eq!(error.times_called(), index.times_called());
+}
+
+#[test]
+fn test_on_error_failure() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(&server);
+
+    let config = common::build_configuration(&server);
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .set_on_error(|user, error| Box::pin(failing_report_error(user, error))),
+        )
+        .execute()
+        .unwrap();
+
+    // An error in the on_error function doesn't stop the user from running more tasks.
+    assert!(index.times_called() > 1);
+    assert_eq!(error.times_called(), index.times_called());
+}
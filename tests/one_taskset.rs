@@ -52,6 +52,9 @@ fn test_single_taskset() {
         .execute()
         .unwrap();
 
+    // The load test ran to completion, it wasn't canceled.
+    assert!(!goose_stats.canceled);
+
     // Confirm that we loaded the mock endpoints.
     assert!(index.times_called() > 0);
     assert!(about.times_called() > 0);
@@ -110,7 +113,7 @@ fn test_single_taskset_empty_config_host() {
         .create_on(&server);
 
     let mut config = common::build_configuration(&server);
-    // Leaves an empty string in config.host.
+    // Leaves an empty vector in config.host.
     let host = std::mem::take(&mut config.host);
     // Enable statistics to confirm Goose and web server agree.
     config.no_stats = false;
@@ -122,7 +125,7 @@ fn test_single_taskset_empty_config_host() {
                 .register_task(task!(get_index).set_weight(9).unwrap())
                 .register_task(task!(get_about).set_weight(3).unwrap()),
         )
-        .set_host(&host)
+        .set_host(&host[0])
         .execute()
         .unwrap();
 
@@ -0,0 +1,92 @@
+#![cfg(feature = "openapi")]
+
+use httpmock::Method::{DELETE, GET, POST};
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const SPEC: &str = r#"{
+    "openapi": "3.0.0",
+    "paths": {
+        "/pets": {
+            "get": {"operationId": "listPets"},
+            "post": {"operationId": "createPet"}
+        },
+        "/pets/{petId}": {
+            "parameters": [{"name": "petId", "in": "path", "required": true}],
+            "delete": {"operationId": "deletePet"}
+        }
+    }
+}"#;
+
+const ITERATIONS: usize = 2;
+
+#[test]
+/// Tasks generated from an OpenAPI specification request each operation, filling in
+/// path parameters from the openapi feeder.
+fn test_openapi() {
+    let server = MockServer::start();
+
+    let list = Mock::new()
+        .expect_method(GET)
+        .expect_path("/pets")
+        .return_status(200)
+        .create_on(&server);
+    let create = Mock::new()
+        .expect_method(POST)
+        .expect_path("/pets")
+        .return_status(201)
+        .create_on(&server);
+    let delete = Mock::new()
+        .expect_method(DELETE)
+        .expect_path("/pets/42")
+        .return_status(204)
+        .create_on(&server);
+
+    let spec_file = std::env::temp_dir().join("goose-openapi-test.json");
+    std::fs::write(&spec_file, SPEC).unwrap();
+    let feeder_file = std::env::temp_dir().join("goose-openapi-test.csv");
+    std::fs::write(&feeder_file, "petId\n42\n").unwrap();
+
+    let tasks = goose::openapi::tasks_from_spec(spec_file.to_str().unwrap()).unwrap();
+    assert_eq!(tasks.len(), 3);
+    let mut taskset = taskset!("ApiUser");
+    for task in tasks {
+        // Renamed tasks still request the path of their operation.
+        let task = if task.name == "/pets" {
+            task
+        } else {
+            task.set_name("DeletePet")
+        };
+        taskset = taskset.register_task(task);
+    }
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_feeder(
+            "openapi",
+            GooseFeeder::from_csv(feeder_file.to_str().unwrap()).unwrap(),
+        )
+        .register_taskset(taskset)
+        .execute()
+        .unwrap();
+
+    assert_eq!(list.times_called(), ITERATIONS);
+    assert_eq!(create.times_called(), ITERATIONS);
+    assert_eq!(delete.times_called(), ITERATIONS);
+
+    // Statistics are grouped by path template, or by the name of renamed tasks.
+    assert!(goose_stats.requests.contains_key("DELETE DeletePet"));
+    assert!(goose_stats.requests.contains_key("POST /pets"));
+
+    // Invalid specifications are rejected.
+    std::fs::write(&spec_file, r#"{"openapi": "3.0.0"}"#).unwrap();
+    assert!(goose::openapi::tasks_from_spec(spec_file.to_str().unwrap()).is_err());
+}
@@ -0,0 +1,88 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{thread, time};
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+
+static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    REQUESTS.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+#[test]
+/// Users don't run tasks while the pause file exists, and resume once it's removed.
+fn test_pause_file() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let pause_file = std::env::temp_dir().join("goose-pause-test.pause");
+    fs::write(&pause_file, "").unwrap();
+
+    // Remove the pause file part way through the load test.
+    let remove_file = pause_file.clone();
+    let resume = thread::spawn(move || {
+        thread::sleep(time::Duration::from_secs(2));
+        let paused_requests = REQUESTS.load(Ordering::SeqCst);
+        fs::remove_file(remove_file).unwrap();
+        paused_requests
+    });
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "4".to_string();
+    config.pause_file = pause_file.to_str().unwrap().to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // No requests were made while paused, but were made after resuming.
+    assert_eq!(resume.join().unwrap(), 0);
+    assert!(index.times_called() > 0);
+    assert_eq!(index.times_called(), REQUESTS.load(Ordering::SeqCst));
+
+    // The pause was recorded, and the throughput buckets it covered are marked.
+    assert_eq!(goose_stats.pauses.len(), 1);
+    assert_eq!(goose_stats.pauses[0].started, 0);
+    assert!(goose_stats.pauses[0].resumed.is_some());
+    assert!(goose_stats.throughput[0].paused);
+    assert!(!goose_stats.throughput.last().unwrap().paused);
+}
+
+#[test]
+/// The manager doesn't run users, so it can't pause them.
+fn test_pause_file_manager() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.manager = true;
+    config.expect_workers = 1;
+    config.pause_file = "goose.pause".to_string();
+    let result = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute();
+
+    match result {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--pause-file"),
+        _ => panic!("expected --pause-file to be rejected on the manager"),
+    }
+}
@@ -0,0 +1,82 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::{thread, time};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const PROMETHEUS_PORT: u16 = 9_193;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Scrape the Prometheus endpoint once the load test is running.
+fn scrape_metrics() -> String {
+    thread::sleep(time::Duration::from_secs(2));
+    // A client that connects without sending a request doesn't block other scrapes.
+    let _idle = TcpStream::connect(("127.0.0.1", PROMETHEUS_PORT))
+        .expect("failed to connect to prometheus endpoint");
+    let mut stream = TcpStream::connect(("127.0.0.1", PROMETHEUS_PORT))
+        .expect("failed to connect to prometheus endpoint");
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_prometheus() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "4".to_string();
+    config.prometheus_port = Some(PROMETHEUS_PORT);
+    config.no_stats = false;
+
+    let scraper = thread::spawn(scrape_metrics);
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoint.
+    assert!(index.times_called() > 0);
+
+    // Confirm metrics were served while the load test was running.
+    let response = scraper.join().unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("# TYPE goose_requests_total counter"));
+    assert!(response.contains("goose_requests_total{method=\"GET\",name=\"/\"}"));
+    assert!(response.contains("goose_requests_failed_total{method=\"GET\",name=\"/\"} 0"));
+    assert!(response
+        .contains("goose_response_time_milliseconds{method=\"GET\",name=\"/\",quantile=\"0.99\"}"));
+}
+
+#[test]
+fn test_prometheus_requires_stats() {
+    let server = MockServer::start();
+
+    // Metrics can't be exposed if statistics are disabled.
+    let mut config = common::build_configuration(&server);
+    config.prometheus_port = Some(PROMETHEUS_PORT + 1);
+    config.no_stats = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
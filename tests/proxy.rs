@@ -0,0 +1,78 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_proxy() {
+    let server = MockServer::start();
+
+    // The mock server acts as the proxy, authenticated as goose:secret.
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .expect_header("proxy-authorization", "Basic Z29vc2U6c2VjcmV0")
+        .return_status(200)
+        .create_on(&server);
+
+    // The load tested host doesn't exist, so all requests must go through the proxy.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.host = vec!["http://goose.invalid".to_string()];
+    config.proxy = server.url("");
+    config.proxy_user = "goose:secret".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(index_request.fail_count, 0);
+}
+
+#[test]
+fn test_proxy_socks5() {
+    let server = MockServer::start();
+
+    // SOCKS5 proxies are supported, resolving hosts locally or on the proxy.
+    for proxy in &["socks5://127.0.0.1:1080", "socks5h://127.0.0.1:1080"] {
+        let mut config = common::build_configuration(&server);
+        config.proxy = proxy.to_string();
+        config.proxy_user = "goose:secret".to_string();
+        assert!(crate::GooseAttack::initialize_with_config(config)
+            .setup()
+            .is_ok());
+    }
+}
+
+#[test]
+fn test_proxy_invalid() {
+    let server = MockServer::start();
+
+    // The proxy must be a valid URL.
+    let mut config = common::build_configuration(&server);
+    config.proxy = "not a url".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Proxy authentication requires a proxy.
+    let mut config = common::build_configuration(&server);
+    config.proxy_user = "goose:secret".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,62 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+/// Quiet mode still collects statistics for the final summary.
+fn test_quiet() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.quiet = true;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+/// Quiet mode can't be combined with options that print to the console.
+fn test_quiet_invalid() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.quiet = true;
+    config.verbose = 1;
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--quiet"),
+        _ => panic!("expected --quiet and --verbose to be invalid together"),
+    }
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.quiet = true;
+    config.reset_interval = "1".to_string();
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--quiet"),
+        _ => panic!("expected --quiet and --reset-interval to be invalid together"),
+    }
+}
@@ -252,3 +252,43 @@ fn test_sticky_domain_redirect() {
     assert!(server2_index.times_called() > 0);
     assert!(server2_about.times_called() > 0);
 }
+
+#[test]
+/// With --max-redirects=0 redirects aren't followed, and are recorded with their
+/// actual status code.
+fn test_max_redirects() {
+    let server = MockServer::start();
+
+    let redirect = Mock::new()
+        .expect_method(GET)
+        .expect_path(REDIRECT_PATH)
+        .return_status(301)
+        .return_header("Location", ABOUT_PATH)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.max_redirects = Some(0);
+    config.status_codes = true;
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_domain_redirect)))
+        .execute()
+        .unwrap();
+
+    assert!(redirect.times_called() > 0);
+    assert_eq!(about.times_called(), 0);
+
+    let redirect_request = goose_stats.requests.get("GET /redirect").unwrap();
+    assert_eq!(redirect_request.fail_count, 0);
+    assert_eq!(
+        redirect_request.status_code_counts.get(&301),
+        Some(&redirect.times_called())
+    );
+}
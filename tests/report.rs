@@ -0,0 +1,216 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+fn cleanup_files(report_file: &str) {
+    if std::path::Path::new(report_file).exists() {
+        std::fs::remove_file(report_file).expect("failed to delete report file");
+    }
+}
+
+#[test]
+fn test_html_report() {
+    const REPORT_FILE: &str = "report.html";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.report_file = REPORT_FILE.to_string();
+    config.no_stats = false;
+    config.status_codes = true;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoints.
+    assert!(index.times_called() > 0);
+    assert!(about.times_called() > 0);
+
+    // Confirm the report was written, and includes all tables.
+    let report = std::fs::read_to_string(REPORT_FILE).expect("failed to read report file");
+    assert!(report.starts_with("<!DOCTYPE html>"));
+    assert!(report.contains("GET /about.html"));
+    assert!(report.contains("<h2>Requests</h2>"));
+    assert!(report.contains("<h2>Response times</h2>"));
+    assert!(report.contains("<th>99.9%</th>"));
+    assert!(report.contains("<h2>Status codes</h2>"));
+    assert!(report.contains("Aggregated"));
+    // Throughput over time is charted with an inline SVG.
+    assert!(report.contains("<h2>Requests per second</h2>"));
+    assert!(report.contains("<polyline class=\"requests\""));
+
+    cleanup_files(REPORT_FILE);
+}
+
+#[test]
+fn test_markdown_report() {
+    const REPORT_FILE: &str = "report.md";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.report_file = REPORT_FILE.to_string();
+    config.no_stats = false;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoints.
+    assert!(index.times_called() > 0);
+    assert!(about.times_called() > 0);
+
+    // Confirm the report was written as markdown, and includes a summary and tables.
+    let report = std::fs::read_to_string(REPORT_FILE).expect("failed to read report file");
+    assert!(report.starts_with("# Goose Load Test Report"));
+    assert!(report.contains(" - Failures: 0 (0%)"));
+    assert!(report.contains("## Requests"));
+    assert!(report.contains("| Name | # reqs | # fails | req/s | fail/s |"));
+    assert!(report.contains("| GET /about.html |"));
+    assert!(report.contains("| **Aggregated** |"));
+    // Status codes weren't enabled, so aren't included.
+    assert!(!report.contains("## Status codes"));
+
+    cleanup_files(REPORT_FILE);
+}
+
+#[test]
+fn test_json_summary() {
+    const SUMMARY_FILE: &str = "summary.json";
+
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(404)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.stats_summary_file = SUMMARY_FILE.to_string();
+    config.stats_percentiles = "50,99.9".to_string();
+    config.status_codes = true;
+    config.no_stats = false;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Confirm the summary was written with the versioned schema.
+    let summary = std::fs::read_to_string(SUMMARY_FILE).expect("failed to read summary file");
+    let summary: GooseStatsSummary =
+        serde_json::from_str(&summary).expect("failed to parse summary file");
+    assert_eq!(summary.version, 1);
+    assert!(!summary.canceled);
+
+    // Requests are sorted by method and name.
+    assert_eq!(summary.requests.len(), 2);
+    let index_summary = &summary.requests[0];
+    assert_eq!(index_summary.method, Some(GooseMethod::GET));
+    assert_eq!(index_summary.name, INDEX_PATH);
+    assert_eq!(index_summary.success_count, index.times_called());
+    assert_eq!(index_summary.fail_count, 0);
+    let about_summary = &summary.requests[1];
+    assert_eq!(about_summary.name, ABOUT_PATH);
+    assert_eq!(about_summary.fail_count, about.times_called());
+    assert_eq!(
+        about_summary.status_codes.get(&404),
+        Some(&about.times_called())
+    );
+
+    // Percentiles configured with --stats-percentiles are included.
+    let percentiles: Vec<&String> = index_summary.percentiles.keys().collect();
+    assert_eq!(percentiles, ["p50", "p99.9"]);
+
+    // The aggregated summary combines all requests.
+    assert_eq!(summary.aggregated.method, None);
+    assert_eq!(
+        summary.aggregated.success_count + summary.aggregated.fail_count,
+        index.times_called() + about.times_called()
+    );
+
+    cleanup_files(SUMMARY_FILE);
+}
+
+#[test]
+fn test_report_requires_stats() {
+    let server = MockServer::start();
+
+    // Reports can't be written if statistics are disabled.
+    let mut config = common::build_configuration(&server);
+    config.report_file = "no-stats-report.html".to_string();
+    config.no_stats = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Summaries can't be written if statistics are disabled.
+    let mut config = common::build_configuration(&server);
+    config.stats_summary_file = "no-stats-summary.json".to_string();
+    config.no_stats = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
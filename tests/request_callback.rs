@@ -0,0 +1,84 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(ERROR_PATH).await?;
+    // The task expects the error.
+    user.set_success(&mut goose.request)?;
+    Ok(())
+}
+
+#[test]
+fn test_request_callback() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(&server);
+
+    // Collect the name, status code and whether it's an update of every request.
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let callback_requests = requests.clone();
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_error)),
+        )
+        .set_request_callback(move |request| {
+            callback_requests.lock().unwrap().push((
+                request.name.clone(),
+                request.status_code,
+                request.update,
+            ));
+        })
+        .execute()
+        .unwrap();
+
+    // Every request was passed to the callback, including updates.
+    let requests = requests.lock().unwrap();
+    let count = |name: &str, status_code: u16, update: bool| {
+        requests
+            .iter()
+            .filter(|request| **request == (name.to_string(), status_code, update))
+            .count()
+    };
+    assert!(index.times_called() > 0);
+    assert_eq!(count(INDEX_PATH, 200, false), index.times_called());
+    assert!(error.times_called() > 0);
+    assert_eq!(count(ERROR_PATH, 500, false), error.times_called());
+    assert_eq!(count(ERROR_PATH, 500, true), error.times_called());
+    assert_eq!(
+        requests.len(),
+        index.times_called() + error.times_called() * 2
+    );
+
+    // The callback sees the same requests as the statistics.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
@@ -0,0 +1,114 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Marks a request as failed after the statistics were reset, once a request with the
+/// same name has failed since the reset.
+pub async fn fail_after_reset(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(INDEX_PATH).await?;
+    tokio::time::delay_for(Duration::from_millis(1_500)).await;
+    let _goose = user.get_named(ERROR_PATH, INDEX_PATH).await?;
+    let _ = user.set_failure("late", &mut goose.request, None, None);
+    Ok(())
+}
+
+#[test]
+fn test_reset_interval() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "3".to_string();
+    config.reset_interval = "1".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Statistics were reset at least once, so the final summary only reflects the last
+    // window of requests.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index_request.success_count > 0);
+    assert!(index_request.success_count < index.times_called());
+    assert!(goose_stats.duration < 3);
+}
+
+#[test]
+fn test_reset_interval_invalid() {
+    let server = MockServer::start();
+
+    // Statistics can't be reset if they're disabled.
+    let mut config = common::build_configuration(&server);
+    config.reset_interval = "1".to_string();
+    config.no_stats = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // The reset interval must be at least 1 second.
+    let mut config = common::build_configuration(&server);
+    config.reset_interval = "0".to_string();
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
+
+#[test]
+/// Updating a request counted before the statistics were reset doesn't change the
+/// statistics of the requests counted since.
+fn test_reset_interval_update() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.run_time = "3".to_string();
+    config.reset_interval = "1".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(fail_after_reset)))
+        .execute()
+        .unwrap();
+
+    // Each request is counted at most once, and stale updates weren't applied.
+    assert!(index.times_called() > 0);
+    assert!(error.times_called() > 0);
+    if let Some(index_request) = goose_stats.requests.get("GET /") {
+        assert!(
+            index_request.success_count + index_request.fail_count
+                <= index.times_called() + error.times_called()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use std::sync::{Arc, Mutex};
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_resolve() {
+    let server = MockServer::start();
+
+    // Requests are sent to the mock server with the Host header of the mapped host.
+    let host = format!("goose.invalid:{}", server.port());
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .expect_header("host", &host)
+        .return_status(200)
+        .create_on(&server);
+
+    // The load tested host doesn't exist, so all requests must connect to the mapped IP.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.host = vec![format!("http://{}", host)];
+    config.resolve = vec![format!("{}:127.0.0.1", host)];
+    let urls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = urls.clone();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .set_request_callback(move |request| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((request.url.clone(), request.final_url.clone()));
+        })
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(index_request.fail_count, 0);
+
+    // Requests are recorded with the host name, not the IP connected to.
+    let expected = format!("http://{}/", host);
+    let urls = urls.lock().unwrap();
+    assert!(!urls.is_empty());
+    for (url, final_url) in urls.iter() {
+        assert_eq!(url, &expected);
+        assert_eq!(final_url, &expected);
+    }
+}
+
+#[test]
+fn test_resolve_invalid() {
+    let server = MockServer::start();
+
+    // Mappings must be formatted host:port:ip.
+    for resolve in &[
+        "goose.invalid",
+        "goose.invalid:80",
+        "goose.invalid:http:127.0.0.1",
+        "goose.invalid:80:localhost",
+        // TLS can't be mapped.
+        "goose.invalid:443:127.0.0.1",
+    ] {
+        let mut config = common::build_configuration(&server);
+        config.resolve = vec![resolve.to_string()];
+        match crate::GooseAttack::initialize_with_config(config).setup() {
+            Err(GooseError::InvalidOption { option, value, .. }) => {
+                assert_eq!(option, "--resolve");
+                assert_eq!(&value, resolve);
+            }
+            _ => panic!("invalid --resolve {} must be rejected", resolve),
+        }
+    }
+}
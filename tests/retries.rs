@@ -0,0 +1,124 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::{Duration, Instant};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_error(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ERROR_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_retries() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index).set_retries(2, Duration::from_millis(10)))
+                .register_task(task!(get_error).set_retries(2, Duration::from_millis(10))),
+        )
+        .execute()
+        .unwrap();
+
+    // Successful requests aren't retried.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+    assert_eq!(index_request.retry_count, 0);
+
+    // Failed requests are retried twice, only the final attempt is counted as a failure.
+    // The one user stops retrying when the load test ends, so its last failed request may
+    // have been retried fewer times.
+    let error_request = goose_stats.requests.get("GET /error").unwrap();
+    assert!(error_request.fail_count > 0);
+    assert_eq!(error_request.success_count, 0);
+    assert!(error_request.retry_count <= error_request.fail_count * 2);
+    assert!(error_request.retry_count >= (error_request.fail_count - 1) * 2);
+    assert_eq!(
+        error.times_called(),
+        error_request.fail_count + error_request.retry_count
+    );
+}
+
+#[test]
+fn test_no_retries() {
+    let server = MockServer::start();
+
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    // By default failed requests aren't retried.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_error)))
+        .execute()
+        .unwrap();
+
+    let error_request = goose_stats.requests.get("GET /error").unwrap();
+    assert_eq!(error_request.fail_count, error.times_called());
+    assert_eq!(error_request.retry_count, 0);
+}
+
+#[test]
+/// Users stop backing off when the load test ends, instead of delaying its end.
+fn test_retry_backoff_exit() {
+    let server = MockServer::start();
+
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.run_time = "1".to_string();
+    let started = Instant::now();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_error).set_retries(2, Duration::from_secs(60))),
+        )
+        .execute()
+        .unwrap();
+
+    // The load test ended without waiting out the backoff, and wasn't retried.
+    assert!(started.elapsed() < Duration::from_secs(30));
+    let error_request = goose_stats.requests.get("GET /error").unwrap();
+    assert_eq!(error_request.retry_count, 0);
+    assert_eq!(error_request.fail_count, error.times_called());
+}
@@ -0,0 +1,102 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_running_stats_interval() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Display running statistics every second.
+    let mut config = common::build_configuration(&server);
+    config.run_time = "2".to_string();
+    config.running_stats_interval = 1;
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Displaying running statistics doesn't affect the final summary.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+fn test_running_percentiles() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Display percentiles in running statistics every second.
+    let mut config = common::build_configuration(&server);
+    config.run_time = "2".to_string();
+    config.running_stats_interval = 1;
+    config.running_percentiles = true;
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+    assert!(goose_stats.running_percentiles);
+}
+
+#[test]
+fn test_running_percentiles_invalid() {
+    let server = MockServer::start();
+
+    // Running percentiles require statistics.
+    let mut config = common::build_configuration(&server);
+    config.running_percentiles = true;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Running statistics aren't displayed with --only-summary.
+    let mut config = common::build_configuration(&server);
+    config.running_percentiles = true;
+    config.only_summary = true;
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
+
+#[test]
+fn test_running_stats_interval_invalid() {
+    let server = MockServer::start();
+
+    // The running statistics interval must be at least 1 second.
+    let mut config = common::build_configuration(&server);
+    config.running_stats_interval = 0;
+    config.no_stats = false;
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
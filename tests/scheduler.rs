@@ -0,0 +1,102 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockRef, MockServer};
+use std::sync::Mutex;
+
+mod common;
+
+use goose::prelude::*;
+
+const BROWSE_PATH: &str = "/products";
+const CART_PATH: &str = "/cart";
+const CHECKOUT_PATH: &str = "/checkout";
+
+const ITERATIONS: usize = 3;
+
+// The order tasks ran in.
+static TASK_ORDER: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+pub async fn browse(user: &GooseUser) -> GooseTaskResult {
+    TASK_ORDER.lock().unwrap().push(BROWSE_PATH);
+    let _goose = user.get(BROWSE_PATH).await?;
+    Ok(())
+}
+
+pub async fn add_to_cart(user: &GooseUser) -> GooseTaskResult {
+    TASK_ORDER.lock().unwrap().push(CART_PATH);
+    let _goose = user.get(CART_PATH).await?;
+    Ok(())
+}
+
+pub async fn checkout(user: &GooseUser) -> GooseTaskResult {
+    TASK_ORDER.lock().unwrap().push(CHECKOUT_PATH);
+    let _goose = user.get(CHECKOUT_PATH).await?;
+    Ok(())
+}
+
+// Run a load test with one user, returning how often each path was requested and the
+// order the tasks ran in.
+fn run_load_test(task_set: GooseTaskSet) -> (Vec<usize>, Vec<&'static str>) {
+    let server = MockServer::start();
+
+    let mocks: Vec<MockRef> = [BROWSE_PATH, CART_PATH, CHECKOUT_PATH]
+        .iter()
+        .map(|path| {
+            Mock::new()
+                .expect_method(GET)
+                .expect_path(path)
+                .return_status(200)
+                .create_on(&server)
+        })
+        .collect();
+
+    TASK_ORDER.lock().unwrap().clear();
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(task_set)
+        .execute()
+        .unwrap();
+
+    let times_called = mocks.iter().map(|mock| mock.times_called()).collect();
+    let task_order = TASK_ORDER.lock().unwrap().clone();
+    (times_called, task_order)
+}
+
+// Both schedulers are tested in one test, as they share the recorded task order.
+#[test]
+fn test_scheduler() {
+    let (times_called, task_order) = run_load_test(
+        taskset!("LoadTest")
+            .set_scheduler(GooseScheduler::Serial)
+            .register_task(task!(browse).set_weight(3).unwrap())
+            .register_task(task!(add_to_cart))
+            .register_task(task!(checkout)),
+    );
+
+    // Weights are ignored, each task ran once per iteration in the order they were
+    // registered.
+    assert_eq!(times_called, vec![ITERATIONS; 3]);
+    assert_eq!(
+        task_order,
+        [BROWSE_PATH, CART_PATH, CHECKOUT_PATH].repeat(ITERATIONS)
+    );
+
+    let (times_called, task_order) = run_load_test(
+        taskset!("LoadTest")
+            .set_scheduler(GooseScheduler::RoundRobin)
+            .register_task(task!(browse).set_weight(2).unwrap())
+            .register_task(task!(add_to_cart))
+            .register_task(task!(checkout).set_sequence(1)),
+    );
+
+    // Weights are respected.
+    assert_eq!(times_called, vec![ITERATIONS * 2, ITERATIONS, ITERATIONS]);
+    // Sequenced tasks run first, then the other tasks take turns, always in the same order.
+    assert_eq!(
+        task_order,
+        [CHECKOUT_PATH, BROWSE_PATH, CART_PATH, BROWSE_PATH].repeat(ITERATIONS)
+    );
+}
@@ -0,0 +1,74 @@
+use httpmock::MockServer;
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+mod common;
+
+use goose::data;
+use goose::prelude::*;
+
+const ITERATIONS: usize = 10;
+
+lazy_static! {
+    /// The tasks run by the load test, and the random data each generated.
+    static ref RUN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+fn record(task: &str) {
+    RUN.lock()
+        .unwrap()
+        .push(format!("{}: {}", task, data::random_int(0, 1_000_000)));
+}
+
+pub async fn task_a(_user: &GooseUser) -> GooseTaskResult {
+    record("a");
+    Ok(())
+}
+
+pub async fn task_b(_user: &GooseUser) -> GooseTaskResult {
+    record("b");
+    Ok(())
+}
+
+pub async fn task_c(_user: &GooseUser) -> GooseTaskResult {
+    record("c");
+    Ok(())
+}
+
+/// Run the load test with a seed, returning the tasks it ran and the data they generated.
+fn run_load_test(server: &MockServer, seed: Option<u64>) -> Vec<String> {
+    RUN.lock().unwrap().clear();
+
+    let mut config = common::build_configuration(server);
+    config.run_time = "".to_string();
+    config.iterations = ITERATIONS;
+    config.seed = seed;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(task_a).set_weight(2).unwrap())
+                .register_task(task!(task_b))
+                .register_task(task!(task_c)),
+        )
+        .execute()
+        .unwrap();
+
+    RUN.lock().unwrap().clone()
+}
+
+#[test]
+fn test_seed() {
+    let server = MockServer::start();
+
+    // Runs with the same seed select the same tasks in the same order, and generate the
+    // same data.
+    let first = run_load_test(&server, Some(1234));
+    assert_eq!(first.len(), ITERATIONS * 4);
+    assert_eq!(first, run_load_test(&server, Some(1234)));
+
+    // A different seed, or no seed, changes the run.
+    assert_ne!(first, run_load_test(&server, Some(4321)));
+    assert_ne!(first, run_load_test(&server, None));
+}
@@ -0,0 +1,98 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_server_timing() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .return_header("Server-Timing", "db;dur=2, app;dur=1.4")
+        .create_on(&server);
+    // The about page doesn't report server timings.
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.server_timing = true;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_index))
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Every index request reported its server time, rounded to milliseconds.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.server_timing_counter, index.times_called());
+    assert_eq!(index_request.total_server_time, index.times_called() * 3);
+    assert!(index_request.total_server_timed_response_time <= index_request.total_response_time);
+
+    let about_request = goose_stats.requests.get("GET /about.html").unwrap();
+    assert!(about.times_called() > 0);
+    assert_eq!(about_request.server_timing_counter, 0);
+
+    // The comparison is summarized, and displayed with the statistics.
+    let summary = goose_stats.summary();
+    assert_eq!(
+        summary.requests[0].server_timing_count,
+        index.times_called()
+    );
+    assert!((summary.requests[0].average_server_time - 3.0).abs() < f32::EPSILON);
+    assert!(goose_stats.to_string().contains("Server avg"));
+}
+
+#[test]
+fn test_server_timing_disabled() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .return_header("Server-Timing", "db;dur=2")
+        .create_on(&server);
+
+    // Headers aren't parsed unless enabled.
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index.times_called() > 0);
+    assert_eq!(index_request.server_timing_counter, 0);
+    assert!(!goose_stats.to_string().contains("Server avg"));
+}
@@ -0,0 +1,68 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+const USERS: usize = 2;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_shutdown_time() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Run for 1 second, then stop users over 2 seconds.
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    config.shutdown_time = "2".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoint.
+    assert!(index.times_called() > 0);
+
+    // Statistics continue to be collected while users are stopped.
+    assert!(goose_stats.duration >= 2);
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+fn test_shutdown_time_invalid() {
+    let server = MockServer::start();
+
+    // Users stop on their own after running all iterations.
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.iterations = 1;
+    config.shutdown_time = "2".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // The shutdown time must be at least 1 second.
+    let mut config = common::build_configuration(&server);
+    config.shutdown_time = "0".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,55 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::process::Command;
+use std::{thread, time};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+/// SIGTERM stops the load test gracefully, returning the statistics collected so far.
+fn test_sigterm() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Send SIGTERM to this process once the load test is running.
+    thread::spawn(|| {
+        thread::sleep(time::Duration::from_secs(2));
+        Command::new("kill")
+            .arg("-TERM")
+            .arg(std::process::id().to_string())
+            .status()
+            .unwrap();
+    });
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.run_time = "60".to_string();
+    let started = time::Instant::now();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // The load test stopped long before the run time expired, with statistics.
+    assert!(started.elapsed() < time::Duration::from_secs(30));
+    assert!(index.times_called() > 0);
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
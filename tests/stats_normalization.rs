@@ -0,0 +1,84 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const PRODUCT_PATH: &str = "/product/";
+const RELEASE_PATH: &str = "/release/";
+
+pub async fn get_product(user: &GooseUser) -> GooseTaskResult {
+    let id = goose::data::random_int(1, 1_000);
+    let _goose = user.get(&format!("{}{}", PRODUCT_PATH, id)).await?;
+    Ok(())
+}
+
+pub async fn get_named_product(user: &GooseUser) -> GooseTaskResult {
+    let id = goose::data::random_int(1, 1_000);
+    let _goose = user
+        .get_named(&format!("{}{}", PRODUCT_PATH, id), "product")
+        .await?;
+    Ok(())
+}
+
+pub async fn get_release(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(&format!("{}2020", RELEASE_PATH)).await?;
+    Ok(())
+}
+
+#[test]
+fn test_stats_normalization() {
+    let server = MockServer::start();
+
+    let product = Mock::new()
+        .expect_method(GET)
+        .expect_path_contains(PRODUCT_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let release = Mock::new()
+        .expect_method(GET)
+        .expect_path_contains(RELEASE_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .add_stats_normalization(r"/\d+", "/:id")
+        .unwrap()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(get_product))
+                .register_task(task!(get_named_product))
+                .register_task(task!(get_release).set_stats_normalization(false)),
+        )
+        .execute()
+        .unwrap();
+
+    // Numeric IDs were collapsed into a single statistic.
+    let normalized_request = goose_stats.requests.get("GET /product/:id").unwrap();
+    let named_request = goose_stats.requests.get("GET product").unwrap();
+    assert_eq!(
+        normalized_request.success_count + named_request.success_count,
+        product.times_called()
+    );
+
+    // Requests from tasks with normalization disabled keep their path.
+    let release_request = goose_stats.requests.get("GET /release/2020").unwrap();
+    assert_eq!(release_request.success_count, release.times_called());
+    assert_eq!(goose_stats.requests.len(), 3);
+}
+
+#[test]
+fn test_stats_normalization_invalid() {
+    let server = MockServer::start();
+
+    // Normalization rules must be valid regular expressions.
+    let config = common::build_configuration(&server);
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .add_stats_normalization(r"/(\d+", "/:id")
+        .is_err());
+}
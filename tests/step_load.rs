@@ -0,0 +1,117 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Instant;
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_step_load() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    // Launch 1 user and hold for 1 second, then launch 1 more and hold for 1 second.
+    let mut config = common::build_configuration(&server);
+    config.users = None;
+    config.run_time = "".to_string();
+    config.step_load = "1@1s,2@1s".to_string();
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Confirm that we loaded the mock endpoint.
+    assert!(index.times_called() > 0);
+
+    // Confirm that all users from the final step were launched.
+    assert_eq!(goose_stats.users, 2);
+
+    // Statistics span all steps, including the time spent holding each step.
+    assert!(goose_stats.duration >= 2);
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+pub async fn stop_load_test(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Err(GooseTaskError::StopLoadTest {
+        reason: "stopping while holding a step".to_string(),
+    })
+}
+
+#[test]
+/// Stopping the load test interrupts a step that's being held.
+fn test_step_load_interrupted() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = None;
+    config.run_time = "".to_string();
+    config.step_load = "1@60s,2@60s".to_string();
+    config.no_stats = false;
+    let started = Instant::now();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(stop_load_test)))
+        .execute()
+        .unwrap();
+
+    // The first step wasn't held, and the second step wasn't launched.
+    assert!(started.elapsed().as_secs() < 30);
+    assert!(index.times_called() > 0);
+    assert!(goose_stats.canceled);
+    assert_eq!(goose_stats.users, 1);
+}
+
+#[test]
+fn test_step_load_invalid() {
+    let server = MockServer::start();
+
+    // The number of users is determined by the step load schedule.
+    let mut config = common::build_configuration(&server);
+    config.run_time = "".to_string();
+    config.step_load = "1@1s,2@1s".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // The run time is determined by the step load schedule.
+    let mut config = common::build_configuration(&server);
+    config.users = None;
+    config.step_load = "1@1s,2@1s".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+
+    // Each step must launch more users than the previous step.
+    let mut config = common::build_configuration(&server);
+    config.users = None;
+    config.run_time = "".to_string();
+    config.step_load = "2@1s,1@1s".to_string();
+    assert!(crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .is_err());
+}
@@ -0,0 +1,52 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Instant;
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let goose = user.get(INDEX_PATH).await?;
+    // The server is broken, there's no point in continuing the load test.
+    if let Ok(response) = goose.response {
+        if response.status().is_server_error() {
+            return Err(GooseTaskError::StopLoadTest {
+                reason: format!("{} returned {}", INDEX_PATH, response.status()),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+/// A task returning StopLoadTest cancels the load test long before the run time expires.
+fn test_stop_load_test() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(503)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(4);
+    config.run_time = "60".to_string();
+
+    let started = Instant::now();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // The load test stopped before all users launched, at one user per second.
+    assert!(started.elapsed().as_secs() < 3);
+    assert!(goose_stats.canceled);
+    assert!(goose_stats.users < 4);
+    assert!(index.times_called() > 0);
+}
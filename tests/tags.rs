@@ -0,0 +1,104 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const CHECKOUT_PATH: &str = "/checkout";
+const REPORT_PATH: &str = "/report";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_checkout(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(CHECKOUT_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_report(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(REPORT_PATH).await?;
+    Ok(())
+}
+
+/// Run a load test with a shopper task set and an admin task set, selecting tasks with
+/// the specified tags, and return how many times each path was requested.
+fn run_load_test(tags: &str, exclude_tags: &str) -> Result<(usize, usize, usize), GooseError> {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let checkout = Mock::new()
+        .expect_method(GET)
+        .expect_path(CHECKOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let report = Mock::new()
+        .expect_method(GET)
+        .expect_path(REPORT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.run_time = "".to_string();
+    config.iterations = 1;
+    config.tags = tags.to_string();
+    config.exclude_tags = exclude_tags.to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()?
+        .register_taskset(
+            taskset!("Shopper")
+                .register_task(task!(get_index))
+                .register_task(task!(get_checkout).set_tags(&["smoke", "checkout"])),
+        )
+        .register_taskset(
+            taskset!("Admin").register_task(task!(get_report).set_tags(&["admin", "slow"])),
+        )
+        .execute()?;
+
+    Ok((
+        index.times_called(),
+        checkout.times_called(),
+        report.times_called(),
+    ))
+}
+
+#[test]
+fn test_no_tags() {
+    // Without tags, each user runs all of its task set's tasks.
+    assert_eq!(run_load_test("", "").unwrap(), (1, 1, 1));
+}
+
+#[test]
+fn test_tags() {
+    // Only tasks with any of the tags run, and the Admin task set is skipped so both
+    // users run the Shopper task set.
+    assert_eq!(run_load_test("smoke", "").unwrap(), (0, 2, 0));
+    assert_eq!(run_load_test("checkout,admin", "").unwrap(), (0, 1, 1));
+}
+
+#[test]
+fn test_exclude_tags() {
+    // Tasks with any of the excluded tags don't run, including untagged tasks.
+    assert_eq!(run_load_test("", "slow").unwrap(), (2, 2, 0));
+    // Excluded tags take precedence.
+    assert_eq!(run_load_test("smoke,admin", "slow").unwrap(), (0, 2, 0));
+    // Task sets with only excluded tasks are skipped.
+    assert_eq!(run_load_test("", "smoke,admin").unwrap(), (2, 0, 0));
+}
+
+#[test]
+fn test_no_tasks_selected() {
+    match run_load_test("missing", "") {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--tags"),
+        _ => panic!("tags that select no tasks must be rejected"),
+    }
+}
@@ -125,3 +125,55 @@ fn test_throttle() {
     // Cleanup log file.
     std::fs::remove_file(STATS_LOG_FILE).expect("failed to delete stats log file");
 }
+
+#[test]
+fn test_taskset_throttle() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let global_throttle_requests = 50;
+    let taskset_throttle_requests = 5;
+    let users = 4;
+    let run_time = 3;
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    // Enable the global throttle, which only applies to task sets without their own throttle.
+    config.throttle_requests = Some(global_throttle_requests);
+    config.users = Some(users);
+    // Start all users in half a second.
+    config.hatch_rate = users * 2;
+    config.run_time = run_time.to_string();
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("IndexUsers").register_task(task!(get_index)))
+        .register_taskset(
+            taskset!("AboutUsers")
+                .set_throttle(taskset_throttle_requests)
+                .unwrap()
+                .register_task(task!(get_about)),
+        )
+        .execute()
+        .unwrap();
+
+    // Requests are made while GooseUsers are hatched, and then for run_time seconds.
+    assert!(about.times_called() > 0);
+    assert!(about.times_called() <= (run_time + 1) * taskset_throttle_requests);
+    assert!(index.times_called() <= (run_time + 1) * global_throttle_requests);
+    // The global throttle allows users of the other task set to make far more requests.
+    assert!(index.times_called() > about.times_called() * 3);
+
+    // A task set can't be throttled to 0 requests per second.
+    assert!(taskset!("NoRequests").set_throttle(0).is_err());
+}
@@ -0,0 +1,83 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockRef, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+
+const USERS: usize = 2;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Mock the index, only matching requests made by the specified user.
+fn mock_user<'a>(server: &'a MockServer, header: &str, user: usize) -> MockRef<'a> {
+    Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .expect_header(header, &user.to_string())
+        .return_status(200)
+        .create_on(server)
+}
+
+/// Run a load test with --user-id, optionally naming the header.
+fn run_load_test(server: &MockServer, user_id_header: Option<&str>) {
+    let mut config = common::build_configuration(server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    config.user_id = true;
+    if let Some(user_id_header) = user_id_header {
+        config.user_id_header = user_id_header.to_string();
+    }
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+}
+
+#[test]
+/// Each user identifies itself with its index in the X-Goose-User header.
+fn test_user_id() {
+    let server = MockServer::start();
+    let first = mock_user(&server, "x-goose-user", 0);
+    let second = mock_user(&server, "x-goose-user", 1);
+
+    run_load_test(&server, None);
+
+    assert!(first.times_called() > 0);
+    assert!(second.times_called() > 0);
+}
+
+#[test]
+/// The name of the header is configurable.
+fn test_user_id_header() {
+    let server = MockServer::start();
+    let first = mock_user(&server, "x-request-user", 0);
+    let second = mock_user(&server, "x-request-user", 1);
+
+    run_load_test(&server, Some("X-Request-User"));
+
+    assert!(first.times_called() > 0);
+    assert!(second.times_called() > 0);
+}
+
+#[test]
+/// The header must have a valid name.
+fn test_user_id_header_invalid() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.user_id = true;
+    config.user_id_header = "not a header".to_string();
+    match crate::GooseAttack::initialize_with_config(config).setup() {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--user-id-header"),
+        _ => panic!("expected --user-id-header to be invalid"),
+    }
+}
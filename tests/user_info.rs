@@ -0,0 +1,50 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+
+const USERS: usize = 3;
+
+// The indexes of all users that ran a task.
+static USER_INDEXES: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    USER_INDEXES.lock().unwrap().insert(user.user_index());
+    // The load test only runs for about a second.
+    assert!(user.elapsed() < Duration::from_secs(10));
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+#[test]
+fn test_user_info() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(USERS);
+    config.hatch_rate = USERS;
+    let _goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Each user has its own index, starting from 0.
+    assert!(index.times_called() > 0);
+    let expected: BTreeSet<usize> = (0..USERS).collect();
+    assert_eq!(*USER_INDEXES.lock().unwrap(), expected);
+}
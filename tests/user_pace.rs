@@ -0,0 +1,101 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about.html";
+
+pub async fn get_index_twice(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn get_about(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(ABOUT_PATH).await?;
+    Ok(())
+}
+
+#[test]
+/// Paced users make a steady number of requests per second, while other users are not
+/// slowed down.
+fn test_user_pace() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let about = Mock::new()
+        .expect_method(GET)
+        .expect_path(ABOUT_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.run_time = "3".to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("PacedUser")
+                .set_user_pace(2.0)
+                .unwrap()
+                .register_task(task!(get_index_twice)),
+        )
+        .register_taskset(taskset!("UnpacedUser").register_task(task!(get_about)))
+        .execute()
+        .unwrap();
+
+    // The paced user makes about 2 requests per second for 3 seconds, even though each
+    // task makes 2 requests.
+    assert!(index.times_called() >= 4);
+    assert!(index.times_called() <= 8);
+    assert!(about.times_called() > index.times_called() * 10);
+
+    // The pace must be more than 0 and at most 1,000,000 requests per second.
+    assert!(taskset!("Fractional").set_user_pace(0.5).is_ok());
+    assert!(taskset!("NoRequests").set_user_pace(0.0).is_err());
+    assert!(taskset!("Negative").set_user_pace(-1.0).is_err());
+    assert!(taskset!("TooManyRequests")
+        .set_user_pace(1_000_001.0)
+        .is_err());
+}
+
+#[test]
+/// A fractional pace spaces requests more than a second apart.
+fn test_user_pace_fractional() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.users = Some(1);
+    config.hatch_rate = 1;
+    config.run_time = "3".to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(
+            taskset!("PacedUser")
+                .set_user_pace(0.5)
+                .unwrap()
+                .register_task(task!(get_index_twice)),
+        )
+        .execute()
+        .unwrap();
+
+    // One request every 2 seconds for 3 seconds, the user stops waiting when it exits.
+    assert!(index.times_called() >= 2);
+    assert!(index.times_called() <= 4);
+}
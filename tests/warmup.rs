@@ -0,0 +1,160 @@
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::time::Duration;
+
+mod common;
+
+use goose::prelude::*;
+use goose::GooseError;
+
+const INDEX_PATH: &str = "/";
+const ERROR_PATH: &str = "/error";
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+/// Marks a request made during the warmup as failed after the warmup completed, once a
+/// request with the same name has failed since.
+pub async fn fail_after_warmup(user: &GooseUser) -> GooseTaskResult {
+    let mut goose = user.get(INDEX_PATH).await?;
+    tokio::time::delay_for(Duration::from_millis(1_500)).await;
+    let _goose = user.get_named(ERROR_PATH, INDEX_PATH).await?;
+    let _ = user.set_failure("late", &mut goose.request, None, None);
+    Ok(())
+}
+
+#[test]
+/// Requests made during the warmup aren't included in the statistics.
+fn test_warmup() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.run_time = "3".to_string();
+    config.warmup = "2".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    // Only requests made during the last second are counted.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index_request.success_count > 0);
+    assert!(index_request.success_count < index.times_called() / 2);
+}
+
+#[test]
+/// Updating a request made during the warmup once it completed doesn't change the
+/// statistics of the requests counted since.
+fn test_warmup_update() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let error = Mock::new()
+        .expect_method(GET)
+        .expect_path(ERROR_PATH)
+        .return_status(500)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.run_time = "3".to_string();
+    config.warmup = "1".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(fail_after_warmup)))
+        .execute()
+        .unwrap();
+
+    // The first request was made during the warmup, so isn't counted, and its update
+    // wasn't applied to the failed request counted after the warmup.
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert!(index_request.fail_count > 0);
+    assert!(
+        index_request.success_count + index_request.fail_count
+            < index.times_called() + error.times_called()
+    );
+}
+
+#[test]
+/// The warmup must end before the load test does.
+fn test_warmup_run_time() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.run_time = "30".to_string();
+    config.warmup = "30".to_string();
+    let result = crate::GooseAttack::initialize_with_config(config).setup();
+
+    match result {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--warmup"),
+        _ => panic!("expected --warmup to be shorter than --run-time"),
+    }
+}
+
+#[test]
+/// Connection warmup requests are sent by each user before it starts, and aren't
+/// included in the statistics.
+fn test_warmup_requests() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let warmup = Mock::new()
+        .expect_method(GET)
+        .expect_path("/health")
+        .return_status(200)
+        .create_on(&server);
+
+    let mut config = common::build_configuration(&server);
+    config.no_stats = false;
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.warmup_requests = 3;
+    config.warmup_path = "/health".to_string();
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(get_index)))
+        .execute()
+        .unwrap();
+
+    assert_eq!(warmup.times_called(), 6);
+    assert!(!goose_stats.requests.contains_key("GET /health"));
+    let index_request = goose_stats.requests.get("GET /").unwrap();
+    assert_eq!(index_request.success_count, index.times_called());
+}
+
+#[test]
+/// A warmup path without warmup requests is an error.
+fn test_warmup_path_requires_requests() {
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.warmup_path = "/health".to_string();
+    let result = crate::GooseAttack::initialize_with_config(config).setup();
+
+    match result {
+        Err(GooseError::InvalidOption { option, .. }) => assert_eq!(option, "--warmup-path"),
+        _ => panic!("expected --warmup-path to require --warmup-requests"),
+    }
+}
@@ -0,0 +1,80 @@
+use futures::{SinkExt, StreamExt};
+use httpmock::MockServer;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+mod common;
+
+use goose::prelude::*;
+use goose::websocket::Message;
+
+const ECHO_PATH: &str = "/echo";
+
+pub async fn echo(user: &GooseUser) -> GooseTaskResult {
+    let mut websocket = user.connect_websocket(ECHO_PATH).await?;
+    websocket.send(Message::text("hello")).await?;
+    let message = websocket.recv().await?.unwrap();
+    assert_eq!(message.into_text().unwrap(), "hello");
+    websocket.close().await?;
+    Ok(())
+}
+
+/// Start a WebSocket server in its own thread, echoing every message it receives.
+fn start_echo_server() -> SocketAddr {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async move {
+            let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            sender.send(listener.local_addr().unwrap()).unwrap();
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut websocket = match tokio_tungstenite::accept_async(stream).await {
+                        Ok(websocket) => websocket,
+                        Err(_) => return,
+                    };
+                    while let Some(Ok(message)) = websocket.next().await {
+                        if message.is_text() && websocket.send(message).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+    });
+    receiver.recv().unwrap()
+}
+
+#[test]
+/// Opening a WebSocket, and each message sent and received, is recorded in the statistics.
+fn test_websocket() {
+    let address = start_echo_server();
+    // The mock server is only used to build the configuration.
+    let server = MockServer::start();
+
+    let mut config = common::build_configuration(&server);
+    config.host = vec![format!("http://{}", address)];
+    config.no_stats = false;
+    let goose_stats = crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("LoadTest").register_task(task!(echo)))
+        .execute()
+        .unwrap();
+
+    let connect = goose_stats.requests.get("WEBSOCKET /echo connect").unwrap();
+    let send = goose_stats.requests.get("WEBSOCKET /echo send").unwrap();
+    let recv = goose_stats.requests.get("WEBSOCKET /echo recv").unwrap();
+    assert!(connect.success_count > 0);
+    assert_eq!(connect.fail_count, 0);
+    // One message was sent and received over each connection.
+    assert_eq!(send.success_count, connect.success_count);
+    assert_eq!(recv.success_count, connect.success_count);
+    assert_eq!(send.bytes, 5 * send.success_count as u64);
+    assert_eq!(recv.bytes, 5 * recv.success_count as u64);
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "gaggle")]
+
+mod common;
+
+use httpmock::Method::GET;
+use httpmock::{Mock, MockServer};
+use std::thread;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const WORKER_START_PATH: &str = "/worker/start";
+const WORKER_STOP_PATH: &str = "/worker/stop";
+// Use a different port than tests/gaggle.rs, as the tests may run at the same time.
+const MANAGER_PORT: u16 = 5_116;
+
+pub async fn get_index(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(INDEX_PATH).await?;
+    Ok(())
+}
+
+pub async fn worker_start(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(WORKER_START_PATH).await?;
+    Ok(())
+}
+
+pub async fn worker_stop(user: &GooseUser) -> GooseTaskResult {
+    let _goose = user.get(WORKER_STOP_PATH).await?;
+    Ok(())
+}
+
+/// Register the same load test on the manager and the worker, so their hashes match.
+fn load_test(configuration: GooseConfiguration) -> GooseAttack {
+    crate::GooseAttack::initialize_with_config(configuration)
+        .setup()
+        .unwrap()
+        .register_taskset(taskset!("User").register_task(task!(get_index)))
+        .worker_start(task!(worker_start))
+        .worker_stop(task!(worker_stop))
+}
+
+#[test]
+fn test_worker_start_stop() {
+    let server = MockServer::start();
+
+    let index = Mock::new()
+        .expect_method(GET)
+        .expect_path(INDEX_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let start = Mock::new()
+        .expect_method(GET)
+        .expect_path(WORKER_START_PATH)
+        .return_status(200)
+        .create_on(&server);
+    let stop = Mock::new()
+        .expect_method(GET)
+        .expect_path(WORKER_STOP_PATH)
+        .return_status(200)
+        .create_on(&server);
+
+    let mut configuration = common::build_configuration(&server);
+    configuration.manager_bind_port = MANAGER_PORT;
+    configuration.manager_port = MANAGER_PORT;
+
+    // Start manager instance of the load test.
+    let mut manager_configuration = configuration.clone();
+    let manager_handle = thread::spawn(move || {
+        manager_configuration.users = Some(2);
+        manager_configuration.hatch_rate = 4;
+        manager_configuration.manager = true;
+        manager_configuration.expect_workers = 1;
+        manager_configuration.run_time = "2".to_string();
+        let _goose_stats = load_test(manager_configuration).execute().unwrap();
+    });
+
+    // Start worker instance of the load test.
+    let worker_handle = thread::spawn(move || {
+        configuration.worker = true;
+        configuration.host = Vec::new();
+        configuration.users = None;
+        configuration.run_time = "".to_string();
+        let _goose_stats = load_test(configuration).execute().unwrap();
+    });
+
+    // Wait for the load test to finish.
+    let _ = worker_handle.join();
+    let _ = manager_handle.join();
+
+    // The worker ran its users, and its start and stop tasks one time each.
+    assert!(index.times_called() > 0);
+    assert_eq!(start.times_called(), 1);
+    assert_eq!(stop.times_called(), 1);
+}